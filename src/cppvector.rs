@@ -72,11 +72,11 @@ impl<T> CppVector<T> {
         }
     }
 
-    pub fn iter(&self) -> CppVectorIterator<T> {
+    pub fn iter(&self) -> CppVectorIterator<'_, T> {
         self.into_iter()
     }
 
-    pub fn iter_mut(&mut self) -> CppVectorIteratorMut<T> {
+    pub fn iter_mut(&mut self) -> CppVectorIteratorMut<'_, T> {
         self.into_iter()
     }
 
@@ -124,7 +124,7 @@ impl<T> CppVector<T> {
 
 impl<T: Copy + Clone> CppVector<T> {
     pub fn from_slice(slice: &[T]) -> Self {
-        let layout = Layout::from_size_align(slice.len() * std::mem::size_of::<T>(), 1).unwrap();
+        let layout = Layout::from_size_align(std::mem::size_of_val(slice), 1).unwrap();
         let (start, eos) = unsafe {
             let start = std::alloc::alloc(layout) as *mut T;
             (start, start.add(slice.len()))
@@ -137,7 +137,7 @@ impl<T: Copy + Clone> CppVector<T> {
 
 impl<T: Clone> CppVector<T> {
     pub fn clone_from_slice(slice: &[T]) -> Self {
-        let layout = Layout::from_size_align(slice.len() * std::mem::size_of::<T>(), 1).unwrap();
+        let layout = Layout::from_size_align(std::mem::size_of_val(slice), 1).unwrap();
         let (start, eos) = unsafe {
             let start = std::alloc::alloc(layout) as *mut T;
             (start, start.add(slice.len()))
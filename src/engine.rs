@@ -1,4 +1,6 @@
-use crate::prelude::*;
+use std::sync::LazyLock;
+
+use crate::{il2cpp::{field, Il2CppType}, prelude::*};
 
 pub mod ui;
 pub mod rendering;
@@ -39,6 +41,42 @@ impl<T> Vector3<T> {
     }
 }
 
+/// Implement [`FieldValue`] and [`ValueType`] for structures mirroring a struct of `UnityEngine`.
+macro_rules! impl_engine_value_type {
+    ($($ty:ty => $name:literal),* $(,)?) => {
+        $(
+            impl FieldValue for $ty {
+                fn can_read(ty: &Il2CppType) -> bool {
+                    field::is_struct(ty, "UnityEngine", $name)
+                }
+
+                fn boxed_class() -> Option<&'static Il2CppClass> {
+                    Il2CppClass::from_name("UnityEngine", $name).ok().map(|class| &*class)
+                }
+            }
+
+            impl ValueType for $ty {
+                fn value_class() -> &'static Il2CppClass {
+                    static CLASS: LazyLock<&'static Il2CppClass> = LazyLock::new(|| {
+                        &*Il2CppClass::from_name("UnityEngine", $name).expect(concat!("Failed to find class UnityEngine.", $name))
+                    });
+
+                    &CLASS
+                }
+            }
+        )*
+    };
+}
+
+impl_engine_value_type! {
+    Color => "Color",
+    Vector2<f32> => "Vector2",
+    Vector2<i32> => "Vector2Int",
+    Vector3<f32> => "Vector3",
+    Vector3<i32> => "Vector3Int",
+    Rect => "Rect",
+}
+
 #[crate::class("UnityEngine", "Material")]
 pub struct Material { }
 
@@ -79,7 +117,7 @@ impl Texture2D {
     }
 
     pub fn set_filter_mode(&mut self, mode: FilterMode) {
-        unsafe { texture2d_set_filter_mode(&self, mode, None) }
+        unsafe { texture2d_set_filter_mode(self, mode, None) }
     }
 }
 
@@ -100,15 +138,12 @@ pub enum SpriteMeshType {
 }
 
 impl Sprite {
-    pub fn create2(texture: &Texture2D, rect: Rect, pivot: Vector2<f32>, pixels_to_unit: f32, extrude: u32, mesh_type: SpriteMeshType) -> &'static mut Self {
-        unsafe { sprite_create2(texture, rect, pivot, pixels_to_unit, extrude, mesh_type, None) }
+    /// Returns [`Il2CppError::UnresolvedSymbol`] if the runtime cannot find this overload of `UnityEngine.Sprite::Create`.
+    pub fn create2(texture: &Texture2D, rect: Rect, pivot: Vector2<f32>, pixels_to_unit: f32, extrude: u32, mesh_type: SpriteMeshType) -> Il2CppResult<&'static mut Self> {
+        crate::il2cpp::api::sprite_create2(texture, rect, pivot, pixels_to_unit, extrude, mesh_type)
     }
 }
 
-// ddlc offset?
-#[skyline::from_offset(0x1b1bf70)]
-fn sprite_create2(texture: &Texture2D, rect: Rect, pivot: Vector2<f32>, pixels_to_unit: f32, extrude: u32, mesh_type: SpriteMeshType, method_info: OptionalMethod) -> &'static mut Sprite;
-
 
 #[crate::class("UnityEngine", "ImageConversion")]
 pub struct ImageConversion { }
@@ -123,6 +158,7 @@ impl ImageConversion {
 fn imageconversion_load_image(tex: &Texture2D, data: &Il2CppArray<u8>, method_info: OptionalMethod) -> bool;
 
 #[repr(C)]
+#[derive(Clone, Copy, Debug)]
 pub struct Rect {
     x: f32,
     y: f32,
@@ -145,7 +181,7 @@ impl AssetBundle {
     /// If you wish to skip the crc check, simply provide 0.
     pub fn load_from_memory_async_internal(array: &mut Il2CppArray<u8>, crc: u32) -> *const u8 {
         let method = unsafe {
-            std::mem::transmute::<_, extern "C" fn(&mut Il2CppArray<u8>, u32, OptionalMethod) -> *const u8>(
+            std::mem::transmute::<*const u8, extern "C" fn(&mut Il2CppArray<u8>, u32, OptionalMethod) -> *const u8>(
                 crate::il2cpp::method_from_name("UnityEngine.AssetBundle::LoadFromMemoryAsync_Internal(System.Byte[],System.UInt32)"),
             )
         };
@@ -153,3 +189,9 @@ impl AssetBundle {
         method(array, crc, None)
     }
 }
+
+/// `UnityEngine.Events.UnityAction`, the delegate type of the listeners of a `UnityEvent` such as `Button.onClick`.
+///
+/// See [`Delegate::from_fn`](crate::system::Delegate::from_fn) to create one from a Rust function.
+#[crate::class("UnityEngine.Events", "UnityAction")]
+pub struct UnityAction { }
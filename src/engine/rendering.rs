@@ -1,6 +1,7 @@
 pub mod universal {
     #[crate::class("UnityEngine.Rendering.Universal", "UniversalRenderPipelineAsset")]
     pub struct UniversalRenderPipelineAsset {
+        #[field_name("m_CachedPtr")]
         pub zuper: *const u8,
         pub default_shader: *const u8,
         pub renderers: *const u8,
@@ -21,7 +21,7 @@ impl Image {
             .get_virtual_method("set_color")
             .map(|method| {
                 let set_color = unsafe {
-                    std::mem::transmute::<_, extern "C" fn(f32, f32, f32, f32, &Image, &MethodInfo)>(method.method_info.method_ptr)
+                    std::mem::transmute::<*mut u8, extern "C" fn(f32, f32, f32, f32, &Image, &MethodInfo)>(method.method_info.method_ptr)
                 };
                 set_color(red, green, blue, alpha, self, method.method_info);
                 // #B00B69
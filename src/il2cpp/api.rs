@@ -1,96 +1,259 @@
 #![allow(dead_code)]
 
-use std::sync::LazyLock;
+use std::{ffi::CStr, sync::OnceLock};
 
+#[cfg(target_os = "horizon")]
 use lazysimd;
+use unity_core::scan::ScanError;
+pub use unity_core::{profile::OffsetProfile, signatures::Symbol};
 
 use super::*;
-use crate::cppvector::CppVector;
-
-// #[lazysimd::from_pattern("fd 7b be a9 f3 0b 00 f9 fd 03 00 91 f3 03 00 aa ?? ?? ?? ?? ?? ?? ?? ?? c0 00 80 52 ?? ?? ?? ?? e0 03 13 aa ?? ?? ?? ?? f3 0b 40 f9 00 00 00 12 fd 7b c2 a8 c0 03 5f d6")]
-// ddlc offset
-#[skyline::from_offset(0x14ce40)]
-pub(crate) fn init(domain_name: *const i8) -> i32;
-
-// Get Image By Assembly Name*
-// #[lazysimd::from_pattern("ff 03 01 d1 fd 7b 01 a9 fd 43 00 91 f6 57 02 a9 f4 4f 03 a9 f3 03 00 aa ?? ?? ?? ?? ?? ?? ?? ?? 08 21 32 91 f4 03 00 aa ?? ?? ?? ?? df 02 08 eb 80 01 00 54 ?? ?? ?? ??")]
-// ddlc offset
-#[skyline::from_offset(0x14be60)]
-pub(crate) fn get_image_by_assembly_name(c_str: *const u8) -> &'static Il2CppImage;
-
-// #[lazysimd::from_pattern(
-//     "ff c3 02 d1 fd 7b 05 a9 fd 43 01 91 fc 6f 06 a9 fa 67 07 a9 f8 5f 08 a9 f6 57 09 a9 f4 4f 0a a9 f8 03 00 aa 16 0f 43 f8 f9 03 02 aa f4 03 01 aa"
-// )]
-// ddlc offset
-#[skyline::from_offset(0x151e30)]
-pub fn class_from_name(image: &Il2CppImage, namespace: *const u8, name: *const u8) -> Option<&'static mut Il2CppClass>;
-
-// #[lazysimd::from_pattern("ff 43 01 d1 fd 7b 01 a9 fd 43 00 91 f7 13 00 f9 f6 57 03 a9 f4 4f 04 a9 08 c8 44 39 f3 03 00 aa e8 02 10 37")]
-// ddlc offset
-#[skyline::from_offset(0x15fb60)]
-pub(crate) fn object_new<T>(klass: &Il2CppClass) -> Option<&'static mut T>;
-
-// #[lazysimd::from_pattern(
-//     "ff 43 01 d1 fd 7b 01 a9 fd 43 00 91 f8 5f 02 a9 f6 57 03 a9 f4 4f 04 a9 08 c8 44 39 f3 03 03 2a f6 03 02 2a f4 03 01 aa f5 03 00 aa"
-// )]
-
-// ddlc offset
-#[skyline::from_offset(0x14f0c0)]
-pub(crate) fn get_method_from_name_flags(
+use crate::{
+    cppvector::CppVector,
+    engine::{Rect, Sprite, SpriteMeshType, Texture2D, Vector2},
+    system::Il2CppString,
+};
+
+static PROFILE: OnceLock<OffsetProfile> = OnceLock::new();
+static OFFSETS: OnceLock<Vec<(Symbol, Result<usize, ScanError>)>> = OnceLock::new();
+
+/// Provide the offsets to use for the current game instead of relying on signatures alone.
+///
+/// Symbols missing from the profile are still looked up through their signature.  
+/// This must be called before anything in the crate calls into the runtime, usually at the very start of the plugin's main.
+///
+/// Without a call to this function, the [DDLC profile](OffsetProfile::ddlc) is used, as the crate only supported that game before profiles existed.
+/// Plugins for other games have to provide their own profile, or `OffsetProfile::new()` to only rely on the signatures.
+///
+/// Returns [`Il2CppError::UnresolvedSymbol`] if a required symbol is neither in the profile nor found by its signature.
+///
+/// Example:
+///
+/// ```no_run
+/// # use unity::{il2cpp::api::OffsetProfile, Il2CppResult};
+/// # fn main() -> Il2CppResult<()> {
+/// unity::il2cpp::api::set_profile(OffsetProfile::from_file("sd:/engage/offsets.toml")?)?;
+///
+/// // Or with the profile embedded in the plugin
+/// # macro_rules! include_str { ($path:literal) => { "" } }
+/// unity::il2cpp::api::set_profile(OffsetProfile::from_toml(include_str!("offsets.toml"))?)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn set_profile(profile: OffsetProfile) -> Il2CppResult<()> {
+    if OFFSETS.get().is_some() {
+        return Err(Il2CppError::ProfileAlreadyApplied);
+    }
+
+    PROFILE.set(profile).map_err(|_| Il2CppError::ProfileAlreadyApplied)?;
+
+    #[cfg(target_os = "horizon")]
+    resolve_required()?;
+
+    Ok(())
+}
+
+/// Get the offset profile currently in use, if one was set or the runtime was already used with the default one.
+pub fn get_profile() -> Option<&'static OffsetProfile> {
+    PROFILE.get()
+}
+
+#[cfg(target_os = "horizon")]
+fn offsets() -> &'static [(Symbol, Result<usize, ScanError>)] {
+    OFFSETS.get_or_init(|| {
+        let profile = PROFILE.get_or_init(OffsetProfile::ddlc);
+        let text = lazysimd::scan::get_text();
+
+        Symbol::ALL
+            .iter()
+            .map(|symbol| {
+                let offset = match profile.get(*symbol) {
+                    Some(offset) => Ok(offset),
+                    None => symbol.scan(&text),
+                };
+
+                (*symbol, offset)
+            })
+            .collect()
+    })
+}
+
+/// Get the offset of a function relative to the start of `.text`, either from the [`OffsetProfile`] or by scanning for it.
+///
+/// Every known symbol is looked up the first time this is called.
+#[cfg(target_os = "horizon")]
+pub fn resolve(symbol: Symbol) -> Il2CppResult<usize> {
+    offsets()
+        .iter()
+        .find(|(entry, _)| *entry == symbol)
+        .map(|(_, result)| result.clone())
+        .unwrap_or(Err(ScanError::NoSignature(symbol.name())))
+        .map_err(Il2CppError::UnresolvedSymbol)
+}
+
+/// Make sure every [required](Symbol::is_required) runtime function can be found, returning the first one that could not.
+///
+/// The runtime refuses to be used until this succeeds, so the functions it calls never jump to an unresolved address.  
+/// Features backed by the other symbols return [`Il2CppError::UnresolvedSymbol`] when used without them.
+#[cfg(target_os = "horizon")]
+pub fn resolve_required() -> Il2CppResult<()> {
+    static REQUIRED: OnceLock<Result<(), ScanError>> = OnceLock::new();
+
+    REQUIRED
+        .get_or_init(|| {
+            offsets()
+                .iter()
+                .filter(|(symbol, _)| symbol.is_required())
+                .try_for_each(|(_, result)| result.clone().map(|_| ()))
+        })
+        .clone()
+        .map_err(Il2CppError::UnresolvedSymbol)
+}
+
+/// Offset used by the `from_offset` declarations of the crate.
+///
+/// Required symbols are checked by [`resolve_required`] before the runtime can be used, and the optional ones by their callers through [`resolve`].
+/// Reaching an unresolved symbol is therefore a bug in the crate, which panics instead of letting the call jump to an arbitrary address.
+#[cfg(target_os = "horizon")]
+pub(crate) fn offset(symbol: Symbol) -> usize {
+    resolve(symbol).unwrap_or_else(|err| panic!("{}", err))
+}
+
+// The following helpers forward to the current runtime, see [`runtime::Il2CppRuntime`] for details.
+
+pub(crate) unsafe fn init(domain_name: *const i8) -> i32 {
+    runtime::get().init(CStr::from_ptr(domain_name))
+}
+
+pub(crate) unsafe fn get_image_by_assembly_name(c_str: *const u8) -> Option<&'static Il2CppImage> {
+    runtime::get().get_image_by_assembly_name(CStr::from_ptr(c_str as _))
+}
+
+/// Look for a class of the image by namespace and name.
+///
+/// # Safety
+///
+/// `namespace` and `name` must point to null-terminated strings.
+pub unsafe fn class_from_name(image: &Il2CppImage, namespace: *const u8, name: *const u8) -> Option<&'static mut Il2CppClass> {
+    runtime::get().class_from_name(image, CStr::from_ptr(namespace as _), CStr::from_ptr(name as _))
+}
+
+pub(crate) unsafe fn object_new<T>(klass: &Il2CppClass) -> Option<&'static mut T> {
+    runtime::get().object_new(klass).map(|object| &mut *(object as *mut Il2CppObject<()> as *mut T))
+}
+
+pub(crate) unsafe fn get_method_from_name_flags(
     class: &Il2CppClass,
     method_name: *const u8,
     args_count: usize,
     flags: u32,
-) -> Option<&'static mut MethodInfo>;
-
-// ddlc offset
-#[skyline::from_offset(0x14bde0)]
-pub(crate) fn assembly_getallassemblies() -> &'static CppVector<&'static Il2CppAssembly>;
-
-//#[lazysimd::from_pattern("ff 03 01 d1 fd 7b 01 a9 fd 43 00 91 f6 57 02 a9 f4 4f 03 a9 08 c8 44 39 f4 03 01 aa f3 03 00 aa")]
-// ddlc offset
-#[skyline::from_offset(0x14bbc0)]
-pub(crate) fn array_new_specific<T>(array_typeinfo: &Il2CppClass, length: usize) -> Option<&'static mut Il2CppArray<T>>;
-
-// #[lazysimd::from_pattern(
-//     "fd 7b be a9 f3 0b 00 f9 fd 03 00 91 f3 03 01 aa 21 00 80 52 e2 03 1f 2a ?? ?? ?? ?? e1 03 13 aa f3 0b 40 f9 fd 7b c2 a8"
-// )]
-// ddlc offset
-#[skyline::from_offset(0x14bb90)]
-pub(crate) fn array_new<T>(element_typeinfo: &Il2CppClass, length: usize) -> Option<&'static mut Il2CppArray<T>>;
-
-// #[lazysimd::from_pattern(
-//     "ff 03 01 d1 fd 7b 01 a9 fd 43 00 91 f5 13 00 f9 f4 4f 03 a9 ?? ?? ?? ?? ?? ?? ?? ?? a0 0f 00 f9 e0 03 13 aa ff 07 00 f9"
-// )]
-// ddlc offset
-#[skyline::from_offset(0x162ad0)]
-pub(crate) fn type_get_object(ty: &Il2CppType) -> Option<&'static mut Il2CppReflectionType>;
+) -> Option<&'static mut MethodInfo> {
+    runtime::get().get_method_from_name_flags(class, CStr::from_ptr(method_name as _), args_count, flags)
+}
+
+pub(crate) unsafe fn assembly_getallassemblies() -> &'static CppVector<&'static Il2CppAssembly> {
+    runtime::get().get_assemblies()
+}
+
+pub(crate) unsafe fn array_new_specific<T>(array_typeinfo: &Il2CppClass, length: usize) -> Option<&'static mut Il2CppArray<T>> {
+    runtime::get().array_new_specific(array_typeinfo, length).map(|array| &mut *(array as *mut Il2CppArray<()> as *mut Il2CppArray<T>))
+}
+
+pub(crate) unsafe fn array_new<T>(element_typeinfo: &Il2CppClass, length: usize) -> Option<&'static mut Il2CppArray<T>> {
+    runtime::get().array_new(element_typeinfo, length).map(|array| &mut *(array as *mut Il2CppArray<()> as *mut Il2CppArray<T>))
+}
 
+pub(crate) unsafe fn type_get_object(ty: &Il2CppType) -> Option<&'static mut Il2CppReflectionType> {
+    runtime::get().type_get_object(ty)
+}
+
+#[cfg(target_os = "horizon")]
 fn domain_getcurrent_scan() -> usize {
-    static OFFSETS: LazyLock<usize> = LazyLock::new(|| {
-        //let text = lazysimd::scan::get_text();
-        //lazysimd::get_offset_neon(
-        //     &text,
-        //     "fd 7b be a9 f3 0b 00 f9 fd 03 00 91 ?? ?? ?? ?? ?? ?? ?? ?? ?? ?? ?? ?? 80 00 00 b4 f3 0b 40 f9 fd 7b c2 a8 c0 03 5f d6",
-        // )
-        // .unwrap()
-        // ddlc offset
-        0x1547d0
-    });
-
-    *OFFSETS
-}
-
-// #[lazysimd::from_pattern(
-//     "ff 03 01 d1 fd 7b 01 a9 fd 43 00 91 f6 57 02 a9 f4 4f 03 a9 f3 03 00 aa e0 03 1f aa 68 2a 40 39 08 05 00 51 1f 75 00 71"
-// )]
-// ddlc offset
-#[skyline::from_offset(0x14de90)]
-pub(crate) fn class_from_il2cpptype(ty: &Il2CppType) -> Option<&'static mut Il2CppClass>;
-
-// #[lazysimd::from_pattern(
-//     "fd 7b bd a9 f5 0b 00 f9 fd 03 00 91 f4 4f 02 a9 08 c8 44 39 08 03 10 37 ?? ?? ?? ?? ?? ?? ?? ?? f3 03 00 aa b5 0f 00 f9"
-// )]
-// ddlc offset
-#[skyline::from_offset(0x14bd30)]
-pub(crate) fn class_init(class: &Il2CppClass);
+    offset(Symbol::DomainGetCurrent)
+}
+
+pub(crate) unsafe fn class_from_il2cpptype(ty: &Il2CppType) -> Option<&'static mut Il2CppClass> {
+    runtime::get().class_from_il2cpptype(ty)
+}
+
+pub(crate) unsafe fn class_init(class: &Il2CppClass) {
+    runtime::get().class_init(class)
+}
+
+pub(crate) unsafe fn string_new<'a>(c_str: *const u8) -> &'a mut crate::system::Il2CppString {
+    runtime::get()
+        .string_new(CStr::from_ptr(c_str as _))
+        .expect("the runtime could not allocate a string")
+}
+
+pub(crate) fn string_replace(
+    string: &Il2CppString,
+    old_value: &Il2CppString,
+    new_value: &Il2CppString,
+) -> Il2CppResult<&'static mut Il2CppString> {
+    runtime::get().string_replace(string, old_value, new_value)
+}
+
+pub(crate) fn sprite_create2(
+    texture: &Texture2D,
+    rect: Rect,
+    pivot: Vector2<f32>,
+    pixels_to_unit: f32,
+    extrude: u32,
+    mesh_type: SpriteMeshType,
+) -> Il2CppResult<&'static mut Sprite> {
+    runtime::get().sprite_create2(texture, rect, pivot, pixels_to_unit, extrude, mesh_type)
+}
+
+pub(crate) unsafe fn gc_wbarrier_set_field(object: Option<&Il2CppObject<()>>, field: *mut *const u8, value: *const u8) -> Il2CppResult<()> {
+    runtime::get().gc_wbarrier_set_field(object, field, value)
+}
+
+pub(crate) fn gc_alloc_fixed(size: usize) -> Il2CppResult<*mut u8> {
+    runtime::get().gc_alloc_fixed(size)
+}
+
+pub(crate) fn gchandle_new(object: &Il2CppObject<()>, pinned: bool) -> Il2CppResult<u32> {
+    runtime::get().gchandle_new(object, pinned)
+}
+
+pub(crate) fn gchandle_new_weakref(object: &Il2CppObject<()>, track_resurrection: bool) -> Il2CppResult<u32> {
+    runtime::get().gchandle_new_weakref(object, track_resurrection)
+}
+
+pub(crate) fn gchandle_get_target(handle: u32) -> Il2CppResult<Option<&'static mut Il2CppObject<()>>> {
+    runtime::get().gchandle_get_target(handle)
+}
+
+pub(crate) fn gchandle_free(handle: u32) -> Il2CppResult<()> {
+    runtime::get().gchandle_free(handle)
+}
+
+pub(crate) unsafe fn value_box(class: &Il2CppClass, data: *const u8) -> Option<&'static mut Il2CppObject<()>> {
+    runtime::get().value_box(class, data)
+}
+
+pub(crate) unsafe fn runtime_invoke(
+    method: &MethodInfo,
+    this: *const u8,
+    args: *const *const u8,
+    exception: &mut Option<&'static mut exception::Il2CppException>,
+) -> Il2CppResult<Option<&'static mut Il2CppObject<()>>> {
+    runtime::get().runtime_invoke(method, this, args, exception)
+}
+
+pub(crate) fn raise_exception(exception: &exception::Il2CppException) -> ! {
+    runtime::get().raise_exception(exception)
+}
+
+pub(crate) unsafe fn method_name(name: *const u8) -> *const u8 {
+    runtime::get().method_from_name(CStr::from_ptr(name as _)).unwrap_or(std::ptr::null())
+}
+
+#[cfg(target_os = "horizon")]
+#[skyline::from_offset(offset(Symbol::GcMallocKind))]
+pub(crate) fn gc_malloc_kind<T>(size: usize, kind: u32) -> &'static mut T;
+
+#[cfg(target_os = "horizon")]
+#[skyline::from_offset(offset(Symbol::SetupGcDescriptor))]
+pub fn setup_gc_descriptor(class: &Il2CppClass);
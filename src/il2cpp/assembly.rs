@@ -6,8 +6,8 @@ use crate::cppvector::CppVector;
 #[repr(C)]
 pub struct Il2CppImage {
     pub name: *const u8,
-    name_no_ext: *const u8,
-    assembly: &'static Il2CppAssembly,
+    pub(crate) name_no_ext: *const u8,
+    pub(crate) assembly: &'static Il2CppAssembly,
     // ...
 }
 
@@ -22,8 +22,8 @@ impl Il2CppImage {
 pub struct Il2CppAssembly {
     pub image: &'static Il2CppImage,
     pub token: u32,
-    referenced_assembly_start: i32,
-    referenced_assembly_count: i32,
+    pub(crate) referenced_assembly_start: i32,
+    pub(crate) referenced_assembly_count: i32,
     // ...
 }
 
@@ -1,52 +1,80 @@
-use std::ffi::CStr;
+use std::{ffi::CStr, fmt, sync::RwLock};
 
 use super::{
     api,
     assembly::Il2CppImage,
-    method::MethodInfo,
-    object::Il2CppArray,
+    field::{FieldInfo, FieldValue},
+    layout::ClassLayout,
+    method::{MethodInfo, METHOD_ATTRIBUTE_VIRTUAL},
+    object::{Il2CppArray, Il2CppObject},
+    property::PropertyInfo,
     Il2CppType,
+    Il2CppTypeEnum,
 };
 use crate::{Il2CppResult, Il2CppError, system::{SystemType, runtime_type_make_generic_type}};
 
+pub(crate) const TYPE_ATTRIBUTE_PUBLIC: u32 = 0x1;
+pub(crate) const TYPE_ATTRIBUTE_INTERFACE: u32 = 0x20;
+pub(crate) const TYPE_ATTRIBUTE_SEALED: u32 = 0x100;
+
+// Copies made by Il2CppClass::clone and the class they were made from, by address.
+static CLONES: RwLock<Vec<(usize, usize)>> = RwLock::new(Vec::new());
+
 #[repr(C)]
 pub struct Il2CppClass1 {
     pub image: &'static Il2CppImage,
     pub gc_desc: *const u8,
-    name: *const u8,
-    namespace: *const u8,
+    pub(crate) name: *const u8,
+    pub(crate) namespace: *const u8,
     pub byval_arg: Il2CppType,
-    this_arg: Il2CppType,
+    pub(crate) this_arg: Il2CppType,
     pub element_class: &'static Il2CppClass,
-    _1_start: [u8; 0x10],
+    pub(crate) cast_class: *const Il2CppClass,
+    pub declaring_type: Option<&'static Il2CppClass>,
     pub parent: &'static Il2CppClass,
     pub generic_class: Option<&'static Il2CppGenericClass>,
-    _1_end: [u8; 0x30],
+    pub(crate) type_metadata_handle: *const u8,
+    pub(crate) interop_data: *const u8,
+    pub(crate) klass: *const Il2CppClass,
+    pub fields: *const FieldInfo,
+    pub(crate) events: *const u8,
+    pub properties: *const PropertyInfo,
     pub methods: *const &'static MethodInfo,
     pub nested_types: *const &'static Il2CppClass,
-    implemented_interfaces: *const u8,
-    interface_offsets: *const u8,
+    pub implemented_interfaces: *const &'static Il2CppClass,
+    pub interface_offsets: *const Il2CppRuntimeInterfaceOffsetPair,
 }
 
 #[repr(C)]
 pub struct Il2CppClass2 {
-    _2_start: [u8; 0x30],
+    pub(crate) type_hierarchy: *const &'static Il2CppClass,
+    _2_start: [u8; 0x28],
     pub instance_size: u32,
     pub actual_size: u32,
-    __: [u8; 0x18],
+    pub element_size: u32,
+    native_size: i32,
+    pub static_fields_size: u32,
+    pub(crate) thread_static_fields_size: u32,
+    thread_static_fields_offset: i32,
+    pub flags: u32,
     pub token: u32,
     pub method_count: u16,
-    property_count: u16,
-    field_count: u16,
-    event_count: u16,
+    pub property_count: u16,
+    pub field_count: u16,
+    pub(crate) event_count: u16,
     pub nested_type_count: u16,
     pub vtable_count: u16,
-    interfaces_count: u16,
-    interface_offsets_count: u16,
-    type_hierarchy_depth: u8,
+    pub interfaces_count: u16,
+    pub interface_offsets_count: u16,
+    pub(crate) type_hierarchy_depth: u8,
     generic_recursion_depth: u8,
     pub rank: u8,
-    _2_end: [u8; 0x9],
+    minimum_alignment: u8,
+    natural_alignment: u8,
+    packing_size: u8,
+    pub(crate) bitflags1: u8,
+    pub(crate) bitflags2: u8,
+    _2_end: [u8; 0x4],
 }
 
 #[repr(C)]
@@ -61,6 +89,14 @@ pub struct Il2CppClass {
 unsafe impl Send for Il2CppClass {}
 unsafe impl Sync for Il2CppClass {}
 
+/// Where the methods of an interface start in the vtable of a class implementing it.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct Il2CppRuntimeInterfaceOffsetPair {
+    pub interface_type: &'static Il2CppClass,
+    pub offset: i32,
+}
+
 #[repr(C)]
 pub struct Il2CppRGCTXData {
     // dummy: *const u8,
@@ -75,19 +111,35 @@ pub struct Il2CppRGCTXData {
 
 #[repr(C)]
 pub struct Il2CppGenericClass {
-    type_definition_idx: i32,
-    class_inst: *const u8,
-    method_inst: *const u8,
+    pub(crate) type_definition_idx: i32,
+    pub(crate) class_inst: *const Il2CppGenericInst,
+    pub(crate) method_inst: *const Il2CppGenericInst,
     pub cached_class: *const Il2CppClass,
 }
 
-// ddlc offset
-#[skyline::from_offset(0x12c6cf8)]
-fn memcpy<T>(dest: &mut T, src: &T, size: usize) -> &'static mut T;
+impl Il2CppGenericClass {
+    /// The type arguments of the class, such as `int` for `List<int>`.
+    pub fn get_type_arguments(&self) -> &'static [&'static Il2CppType] {
+        unsafe { self.class_inst.as_ref() }.map(Il2CppGenericInst::get_types).unwrap_or_default()
+    }
+}
+
+/// The list of type arguments given to a generic class or method.
+#[repr(C)]
+pub struct Il2CppGenericInst {
+    pub(crate) type_argc: u32,
+    pub(crate) type_argv: *const &'static Il2CppType,
+}
 
-// ddlc offset
-#[skyline::from_offset(0x18fe10)]
-fn gc_malloc_kind<T>(size: usize, kind: u32) -> &'static mut T;
+impl Il2CppGenericInst {
+    pub fn get_types(&self) -> &'static [&'static Il2CppType] {
+        if self.type_argv.is_null() {
+            &[]
+        } else {
+            unsafe { std::slice::from_raw_parts(self.type_argv, self.type_argc as _) }
+        }
+    }
+}
 
 impl Il2CppClass {
     pub fn from_name(namespace: impl AsRef<str>, name: impl AsRef<str>) -> Il2CppResult<&'static mut Self> {
@@ -106,16 +158,47 @@ impl Il2CppClass {
         make_generic(self, args)
     }
 
-    pub fn clone(&self) -> &'static mut Il2CppClass {
-        let size = 0x138 + (0x10 * self._2.vtable_count) as usize;
-        let layout = std::alloc::Layout::from_size_align(size, 1).unwrap();
+    /// Copy the class along with its vtable, so entries of the copy can be replaced without affecting the original.
+    ///
+    /// Everything else is shared with the original. The checks of this crate, such as [`is_assignable_from`](Self::is_assignable_from), see the copy as the original through [`get_original`](Self::get_original),
+    /// but the runtime's own `IsAssignableFrom` and casts compare class pointers and do not.  
+    /// The copy is never freed, as objects might still point to it.  
+    /// Fails if the runtime cannot allocate memory for it that the garbage collector scans.
+    pub fn clone(&self) -> Il2CppResult<&'static mut Il2CppClass> {
+        // The vtable is only filled in once the class is initialized
+        unsafe { api::class_init(self) };
 
-        unsafe {
-            // Malloc kind is "Normal" here, meaning the class and its inner pointers can be managed and freed by the Garbage Collector (BoehmGC)
-            let dest = &mut *(std::alloc::alloc(layout) as *mut Il2CppClass);
-            memcpy(dest, self, size);
-            dest
-        }
+        let size = std::mem::size_of::<Il2CppClass>() + std::mem::size_of::<VirtualInvoke>() * self._2.vtable_count as usize;
+        let dest = api::gc_alloc_fixed(size)?;
+
+        unsafe { std::ptr::copy_nonoverlapping(self as *const Il2CppClass as *const u8, dest, size) };
+
+        let original = self.get_original() as *const Il2CppClass as usize;
+        CLONES.write().unwrap().push((dest as usize, original));
+
+        Ok(unsafe { &mut *(dest as *mut Il2CppClass) })
+    }
+
+    /// The class this one is a [copy](Self::clone) of, or the class itself if it is not a copy.
+    pub fn get_original(&self) -> &Il2CppClass {
+        self.cloned_from().map_or(self, |original| original)
+    }
+
+    /// The class this one is a copy of, if it is one.
+    pub(crate) fn cloned_from(&self) -> Option<&'static mut Il2CppClass> {
+        let address = self as *const Il2CppClass as usize;
+
+        CLONES
+            .read()
+            .unwrap()
+            .iter()
+            .find(|(clone, _)| *clone == address)
+            .map(|(_, original)| unsafe { &mut *(*original as *mut Il2CppClass) })
+    }
+
+    /// Whether the class is a [copy](Self::clone) of another one.
+    pub fn is_clone(&self) -> bool {
+        self.cloned_from().is_some()
     }
 
     // TODO: Should return a Result instead but this needs further testing
@@ -129,11 +212,19 @@ impl Il2CppClass {
     // }
 
     pub fn get_static_fields<T>(&self) -> &T {
-        unsafe { std::mem::transmute(self.static_fields) }
+        unsafe { &*(self.static_fields as *const T) }
     }
 
     pub fn get_static_fields_mut<T>(&self) -> &mut T {
-        unsafe { std::mem::transmute(self.static_fields) }
+        unsafe { &mut *(self.static_fields as *mut T) }
+    }
+
+    pub fn is_valuetype(&self) -> bool {
+        self._2.bitflags1 & 0x2 != 0
+    }
+
+    pub fn is_enum(&self) -> bool {
+        self._2.bitflags1 & 0x8 != 0
     }
 
     pub fn get_type(&self) -> &Il2CppType {
@@ -168,8 +259,376 @@ impl Il2CppClass {
             .find(|method| method.get_name().unwrap_or_default() == name.as_ref())
     }
 
+    /// The parent of the class, or `None` for `System.Object` and interfaces.
+    pub(crate) fn parent(&self) -> Option<&'static Il2CppClass> {
+        unsafe { *(&self._1.parent as *const &'static Il2CppClass as *const Option<&'static Il2CppClass>) }
+    }
+
+    /// Iterate over the parents of the class, from its direct parent up to `System.Object`.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use unity::prelude::*;
+    /// # fn main() -> Il2CppResult<()> {
+    /// # let class: &Il2CppClass = unimplemented!();
+    /// let names = class.parents().map(|parent| parent.get_name()).collect::<Vec<_>>();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn parents(&self) -> impl Iterator<Item = &'static Il2CppClass> {
+        std::iter::successors(self.parent(), |class| class.parent())
+    }
+
+    /// The class itself followed by its parents.
+    fn hierarchy(&self) -> impl Iterator<Item = &Il2CppClass> {
+        std::iter::successors(Some(self), |class| class.parent())
+    }
+
+    pub fn is_interface(&self) -> bool {
+        self._2.flags & TYPE_ATTRIBUTE_INTERFACE != 0
+    }
+
+    /// Get the interfaces the class declares, not including the ones of its parents.
+    pub fn get_interfaces(&self) -> &[&'static Il2CppClass] {
+        unsafe { api::class_init(self) };
+
+        match self._1.implemented_interfaces.is_null() {
+            true => &[],
+            false => unsafe { std::slice::from_raw_parts(self._1.implemented_interfaces, self._2.interfaces_count as _) },
+        }
+    }
+
+    /// Get the interfaces of the class and its parents along with where their methods are in the vtable.
+    pub fn get_interface_offsets(&self) -> &[Il2CppRuntimeInterfaceOffsetPair] {
+        unsafe { api::class_init(self) };
+
+        match self._1.interface_offsets.is_null() {
+            true => &[],
+            false => unsafe { std::slice::from_raw_parts(self._1.interface_offsets, self._2.interface_offsets_count as _) },
+        }
+    }
+
+    /// Get the index of the vtable where the methods of the interface start, if the class implements it.
+    pub fn get_interface_offset(&self, interface: &Il2CppClass) -> Option<usize> {
+        self.get_interface_offsets()
+            .iter()
+            .find(|pair| std::ptr::eq(pair.interface_type, interface))
+            .map(|pair| pair.offset as usize)
+    }
+
+    /// Find the vtable entry implementing an interface method for this class.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use unity::prelude::*;
+    /// # fn main() -> Il2CppResult<()> {
+    /// # let object: &Il2CppObject<()> = unimplemented!();
+    /// let compare_to = Il2CppClass::from_name("System", "IComparable")?.get_method_from_name("CompareTo", 1)?;
+    /// let implementation = object.get_class().get_interface_method(compare_to)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_interface_method(&self, method: &MethodInfo) -> Il2CppResult<&VirtualInvoke> {
+        let interface = method
+            .class
+            .filter(|class| class.is_interface())
+            .ok_or_else(|| Il2CppError::NotAnInterfaceMethod(method.get_name().unwrap_or_default()))?;
+
+        self.get_interface_offset(interface)
+            .and_then(|offset| self.get_vtable().get(offset + method.slot as usize))
+            .ok_or_else(|| {
+                Il2CppError::MissingInterface(
+                    format!("{}.{}", self.get_namespace(), self.get_name()),
+                    format!("{}.{}", interface.get_namespace(), interface.get_name()),
+                )
+            })
+    }
+
+    /// Find the vtable entry implementing a method of the interface for this class, by name and parameter count.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use unity::prelude::*;
+    /// # fn main() -> Il2CppResult<()> {
+    /// # let list: &Il2CppObject<()> = unimplemented!();
+    /// let enumerable = Il2CppClass::from_name("System.Collections", "IEnumerable")?;
+    /// let get_enumerator = list.get_class().get_interface_method_from_name(enumerable, "GetEnumerator", 0)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_interface_method_from_name(&self, interface: &Il2CppClass, name: impl AsRef<str>, args_count: usize) -> Il2CppResult<&VirtualInvoke> {
+        self.get_interface_method(interface.get_method_from_name(name, args_count)?)
+    }
+
+    /// The implementation of a virtual method for this class, through the vtable or the interface offsets.
+    ///
+    /// `None` for methods that are not virtual.
+    pub(crate) fn resolve_virtual(&self, method: &MethodInfo) -> Option<&VirtualInvoke> {
+        match method.class {
+            _ if method.flags & METHOD_ATTRIBUTE_VIRTUAL == 0 => None,
+            Some(class) if class.is_interface() => self.get_interface_method(method).ok(),
+            _ => self.get_vtable().get(method.slot as usize),
+        }
+    }
+
+    /// The vtable entry of this class implementing a virtual method, through the vtable or the interface offsets.
+    pub fn get_vtable_entry_mut(&mut self, method: &MethodInfo) -> Il2CppResult<&mut VirtualInvoke> {
+        let index = match method.class {
+            _ if method.flags & METHOD_ATTRIBUTE_VIRTUAL == 0 => None,
+            Some(class) if class.is_interface() => self.get_interface_offset(class).map(|offset| offset + method.slot as usize),
+            _ => Some(method.slot as usize),
+        };
+
+        let class_name = self.to_string();
+
+        index
+            .and_then(|index| self.get_vtable_mut().get_mut(index))
+            .ok_or_else(|| Il2CppError::MissingVtableEntry(method.to_string(), class_name))
+    }
+
+    /// Replace the implementation of a virtual method for this class only, returning the one it replaced so it can still be called.
+    ///
+    /// Subclasses have a vtable of their own and are not affected, which makes it possible to change the behavior of a single subclass.  
+    /// Only calls going through the vtable, as virtual calls made by the game do, reach the new implementation: `base.Method()` and [`MethodInfo::invoke`] still call the original.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use unity::prelude::*;
+    /// # extern "C" fn sword_get_damage(this: &Il2CppObject<()>, method_info: OptionalMethod) -> i32 { 0 }
+    /// # fn main() -> Il2CppResult<()> {
+    /// let sword = Il2CppClass::from_name("App", "Sword")?;
+    /// let get_damage = sword.get_method_from_name("GetDamage", 0)?;
+    /// let original = sword.replace_virtual_method(get_damage, sword_get_damage as _)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn replace_virtual_method(&mut self, method: &MethodInfo, method_ptr: *mut u8) -> Il2CppResult<*mut u8> {
+        self.get_vtable_entry_mut(method)
+            .map(|entry| std::mem::replace(&mut entry.method_ptr, method_ptr))
+    }
+
+    /// Whether the class derives from `class`, directly or not.
+    ///
+    /// Like `Type.IsSubclassOf` in C#, interfaces are not considered and a class is not a subclass of itself.
+    pub fn is_subclass_of(&self, class: &Il2CppClass) -> bool {
+        self.parents().any(|parent| std::ptr::eq(parent, class))
+    }
+
+    /// Whether an instance of `class` can be stored in a variable of this class, like `Type.IsAssignableFrom` in C#.
+    ///
+    /// This is the case when `class` is this class, one of its subclasses, implements it as an interface, or is an array of such classes.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use unity::prelude::*;
+    /// # fn main() -> Il2CppResult<()> {
+    /// # let object: &Il2CppObject<()> = unimplemented!();
+    /// let component = Il2CppClass::from_name("UnityEngine", "Component")?;
+    /// assert!(component.is_assignable_from(object.get_class()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn is_assignable_from(&self, class: &Il2CppClass) -> bool {
+        if std::ptr::eq(self, class) || std::ptr::eq(self, class.get_original()) || class.is_subclass_of(self) {
+            return true;
+        }
+
+        // System.Object, which every class and interface can be stored as
+        if self.parent().is_none() && !self.is_interface() && self._2.rank == 0 {
+            return true;
+        }
+
+        if self.is_interface() {
+            return class.implements(self);
+        }
+
+        // Arrays of references are covariant, so a `string[]` can be stored as an `object[]`
+        let (element, other_element) = (self._1.element_class, class._1.element_class);
+
+        self._2.rank != 0
+            && self._2.rank == class._2.rank
+            && !other_element.is_valuetype()
+            && element.is_assignable_from(other_element)
+    }
+
+    /// Whether the class or one of its parents implements the interface, directly or through another interface.
+    fn implements(&self, interface: &Il2CppClass) -> bool {
+        self.hierarchy()
+            .flat_map(|class| class.get_interfaces())
+            .any(|implemented| std::ptr::eq(*implemented, interface) || implemented.implements(interface))
+    }
+
+    /// Whether the class is `base`, inherits from it or implements it.
+    ///
+    /// Instances of generic classes are also accepted for their definition, as the fields and methods looked up on the definition apply to all of them.  
+    /// Casts are stricter, see [`Il2CppObject::cast`](super::object::Il2CppObject::cast).
+    pub(crate) fn derives_from(&self, base: &Il2CppClass) -> bool {
+        base.is_assignable_from(self) || self.hierarchy().any(|class| class.is_instance_of(base, None))
+    }
+
+    /// Whether the class, one of its parents or one of their interfaces is an instance of the generic `definition` with these type arguments.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use unity::prelude::*;
+    /// # fn main() -> Il2CppResult<()> {
+    /// # let object: &Il2CppObject<()> = unimplemented!();
+    /// let list = Il2CppClass::from_name("System.Collections.Generic", "List`1")?;
+    /// let int = Il2CppClass::from_name("System", "Int32")?;
+    /// let is_list_of_int = object.get_class().is_generic_instance_of(list, &[int]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn is_generic_instance_of(&self, definition: &Il2CppClass, arguments: &[&Il2CppClass]) -> bool {
+        self.hierarchy().any(|class| {
+            class.is_instance_of(definition, Some(arguments))
+                || class.get_interfaces().iter().any(|interface| interface.is_generic_instance_of(definition, arguments))
+        })
+    }
+
+    /// Whether the class itself is an instance of `definition`, with the provided type arguments if any.
+    fn is_instance_of(&self, definition: &Il2CppClass, arguments: Option<&[&Il2CppClass]>) -> bool {
+        let Some(generic_class) = self._1.generic_class else { return false };
+
+        let same_arguments = |arguments: &[&Il2CppClass]| {
+            let types = generic_class.get_type_arguments();
+
+            types.len() == arguments.len()
+                && types.iter().zip(arguments).all(|(ty, argument)| {
+                    Il2CppClass::from_il2cpptype(ty).is_ok_and(|class| std::ptr::eq(class, argument.get_original()))
+                })
+        };
+
+        self.is_definition_of(definition) && arguments.is_none_or(same_arguments)
+    }
+
+    fn is_definition_of(&self, other: &Il2CppClass) -> bool {
+        std::ptr::eq(self._1.image, other._1.image) && self.get_namespace() == other.get_namespace() && self.get_name() == other.get_name()
+    }
+
+    /// Get the fields declared by the class itself, static ones included.
+    ///
+    /// The class is initialized first, as the runtime only sets up the fields at that point.
+    pub fn get_declared_fields(&self) -> &[FieldInfo] {
+        unsafe { api::class_init(self) };
+
+        match self._1.fields.is_null() {
+            true => &[],
+            false => unsafe { std::slice::from_raw_parts(self._1.fields, self._2.field_count as _) },
+        }
+    }
+
+    /// Get the fields of the class followed by the ones of its parents, static ones included.
+    pub fn get_fields(&self) -> Vec<&FieldInfo> {
+        self.hierarchy()
+            .flat_map(|class| class.get_declared_fields())
+            .collect()
+    }
+
+    /// Find a field of the class or its parents by name, such as `m_Name` or `<Name>k__BackingField`.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use unity::prelude::*;
+    /// # fn main() -> Il2CppResult<()> {
+    /// let offset = Il2CppClass::from_name("App", "Unit")?.get_field_from_name("m_Hp")?.get_offset();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_field_from_name(&self, name: impl AsRef<str>) -> Il2CppResult<&FieldInfo> {
+        self.get_fields()
+            .into_iter()
+            .find(|field| field.get_name() == name.as_ref())
+            .ok_or_else(|| Il2CppError::MissingField(name.as_ref().to_string()))
+    }
+
+    /// Read a static field of the class or its parents by name.
+    ///
+    /// Fails if the field does not exist, is not static or cannot be read as `V`.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use unity::prelude::*;
+    /// # fn main() -> Il2CppResult<()> {
+    /// let count: i32 = Il2CppClass::from_name("App", "Unit")?.get_static_field("s_Count")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_static_field<V: FieldValue>(&self, name: impl AsRef<str>) -> Il2CppResult<V> {
+        self.get_field_from_name(name)?.get_value(None)
+    }
+
+    /// Write a static field of the class or its parents by name.
+    ///
+    /// Fails if the field does not exist, is not static or cannot hold `value`.
+    pub fn set_static_field<V: FieldValue>(&self, name: impl AsRef<str>, value: V) -> Il2CppResult<()> {
+        self.get_field_from_name(name)?.set_value(None, value)
+    }
+
+    /// Get the properties declared by the class itself, static ones included.
+    pub fn get_declared_properties(&self) -> &[PropertyInfo] {
+        unsafe { api::class_init(self) };
+
+        match self._1.properties.is_null() {
+            true => &[],
+            false => unsafe { std::slice::from_raw_parts(self._1.properties, self._2.property_count as _) },
+        }
+    }
+
+    /// Get the properties of the class followed by the ones of its parents, static ones included.
+    ///
+    /// Properties overridden by the class appear once for the class and once for the parent declaring them.
+    pub fn get_properties(&self) -> Vec<&PropertyInfo> {
+        self.hierarchy()
+            .flat_map(|class| class.get_declared_properties())
+            .collect()
+    }
+
+    /// Find a property of the class or its parents by name, starting with the class itself.
+    pub fn get_property_from_name(&self, name: impl AsRef<str>) -> Il2CppResult<&PropertyInfo> {
+        self.get_properties()
+            .into_iter()
+            .find(|property| property.get_name() == name.as_ref())
+            .ok_or_else(|| Il2CppError::MissingProperty(name.as_ref().to_string()))
+    }
+
+    /// Call the getter of a static property of the class or its parents by name.
+    ///
+    /// Fails if the property does not exist, is not static, has no getter or does not return a `V`.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use unity::prelude::*;
+    /// # fn main() -> Il2CppResult<()> {
+    /// let delta: f32 = Il2CppClass::from_name("UnityEngine", "Time")?.get_static_property("deltaTime")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_static_property<V: FieldValue>(&self, name: impl AsRef<str>) -> Il2CppResult<V> {
+        self.get_property_from_name(name)?.get_value(None)
+    }
+
+    /// Call the setter of a static property of the class or its parents by name.
+    ///
+    /// Fails if the property does not exist, is not static, has no setter or does not take a `V`.
+    pub fn set_static_property<V: FieldValue>(&self, name: impl AsRef<str>, value: V) -> Il2CppResult<()> {
+        self.get_property_from_name(name)?.set_value(None, value)
+    }
+
     pub fn get_methods(&self) -> &[&'static MethodInfo] {
-        unsafe { std::slice::from_raw_parts(self._1.methods, self._2.method_count as _) }
+        match self._1.methods.is_null() {
+            true => &[],
+            false => unsafe { std::slice::from_raw_parts(self._1.methods, self._2.method_count as _) },
+        }
     }
 
     pub fn get_nested_types(&self) -> &[&'static Il2CppClass] {
@@ -185,6 +644,92 @@ impl Il2CppClass {
 
         unsafe { api::get_method_from_name_flags(self, name.as_ptr() as _, args_count, flag) }.ok_or(Il2CppError::MissingMethod)
     }
+
+    /// Find a method of the class or its parents by name and the classes of its parameters, starting with the class itself.
+    ///
+    /// Unlike [`get_method_from_name`](Self::get_method_from_name), this tells apart overloads taking as many arguments.  
+    /// `ref` and `out` parameters are only matched by [`get_method_from_signature`](Self::get_method_from_signature).
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use unity::prelude::*;
+    /// # fn main() -> Il2CppResult<()> {
+    /// let int = Il2CppClass::from_name("System", "Int32")?;
+    /// let string = Il2CppClass::from_name("System", "String")?;
+    /// let insert = string.get_method("Insert", &[int, string])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_method(&self, name: impl AsRef<str>, parameters: &[&Il2CppClass]) -> Il2CppResult<&'static MethodInfo> {
+        self.find_method(name.as_ref(), |method| {
+            let method_parameters = method.get_parameters();
+
+            method_parameters.len() == parameters.len()
+                && method_parameters.iter().zip(parameters).all(|(parameter, class)| {
+                    !parameter.parameter_type.is_byref()
+                        && unsafe { api::class_from_il2cpptype(parameter.parameter_type) }.is_some_and(|parameter_class| std::ptr::eq(parameter_class, *class))
+                })
+        })
+    }
+
+    /// Find a method of the class or its parents by name and signature, as formatted by [`MethodInfo::get_signature`].
+    ///
+    /// The signature lists the full names of the parameter types, which allows telling apart `ref` parameters and generic instances.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use unity::prelude::*;
+    /// # fn main() -> Il2CppResult<()> {
+    /// let try_parse = Il2CppClass::from_name("System", "Int32")?.get_method_from_signature("TryParse", "(System.String, System.Int32&)")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_method_from_signature(&self, name: impl AsRef<str>, signature: impl AsRef<str>) -> Il2CppResult<&'static MethodInfo> {
+        let signature = signature.as_ref().replace(' ', "");
+
+        self.find_method(name.as_ref(), |method| method.get_signature() == signature)
+    }
+
+    fn find_method(&self, name: &str, predicate: impl Fn(&MethodInfo) -> bool) -> Il2CppResult<&'static MethodInfo> {
+        self.hierarchy()
+            .flat_map(|class| {
+                unsafe { api::class_init(class) };
+                class.get_methods()
+            })
+            .find(|method| method.get_name().as_deref() == Some(name) && predicate(method))
+            .copied()
+            .ok_or(Il2CppError::MissingMethod)
+    }
+}
+
+impl Il2CppClass {
+    /// Write the full name of the class without its type arguments, prefixed by its declaring class for nested classes.
+    ///
+    /// The arity of generic classes (`List`1`) is left out, like C# does.
+    pub(crate) fn write_name(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let namespace = self.get_namespace();
+
+        match self._1.declaring_type {
+            Some(declaring_type) => write!(f, "{}/", declaring_type)?,
+            None if !namespace.is_empty() => write!(f, "{}.", namespace)?,
+            None => (),
+        }
+
+        let name = self.get_name();
+        f.write_str(name.split('`').next().unwrap_or_default())
+    }
+}
+
+/// Formats the class the way the runtime names it, such as `System.Collections.Generic.List<System.String>` or `App.Unit/Skill`.
+impl fmt::Display for Il2CppClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.get_type().get_type_enum() {
+            Some(Il2CppTypeEnum::SzArray | Il2CppTypeEnum::Array | Il2CppTypeEnum::Ptr | Il2CppTypeEnum::GenericInst) => self.get_type().fmt(f),
+            _ => self.write_name(f),
+        }
+    }
 }
 
 #[repr(C)]
@@ -203,7 +748,7 @@ impl VirtualInvoke {
 #[repr(C)]
 #[crate::class("System", "ReflectionType")]
 pub struct Il2CppReflectionType {
-    ty: &'static Il2CppType,
+    pub(crate) ty: &'static Il2CppType,
 }
 
 pub fn get_class_from_name(namespace: impl AsRef<str>, name: impl AsRef<str>) -> Il2CppResult<&'static mut Il2CppClass> {
@@ -214,6 +759,7 @@ pub fn get_class_from_name(namespace: impl AsRef<str>, name: impl AsRef<str>) ->
             let name = std::ffi::CString::new(name.as_ref()).unwrap();
             unsafe { api::class_from_name(assembly.image, namespace.as_ptr() as _, name.as_ptr() as _) }
         })
+        .or_else(|| super::injection::find(namespace.as_ref(), name.as_ref()))
         .ok_or(Il2CppError::MissingClass(name.as_ref().to_string()))
 }
 
@@ -242,37 +788,24 @@ fn class_from_system_type(ty: &Il2CppReflectionType) -> Il2CppResult<&'static mu
     class_from_il2cpptype(ty.ty)
 }
 
-struct MakeGenericTypeArgs<'a> {
-    generic: &'a Il2CppReflectionType,
-    args: &'a Il2CppArray<&'a mut Il2CppReflectionType>,
-}
-
 /// Helper method to call System.ReflectionType.MakeGenericType
 pub fn make_generic_type(
-    generic: &Il2CppReflectionType,
-    args: &Il2CppArray<&mut Il2CppReflectionType>,
+    generic: &'static Il2CppReflectionType,
+    args: &'static Il2CppArray<&'static mut Il2CppReflectionType>,
 ) -> Il2CppResult<&'static mut Il2CppReflectionType> {
-    let make_generic_method = runtime_type_make_generic_type::get_ref();
+    let make_generic_method = runtime_type_make_generic_type::find_method()?;
 
-    let params = MakeGenericTypeArgs {
-        generic,
-        args,
-    };
-    
-    let runtime_invoke = unsafe {
-        std::mem::transmute::<_, extern "C" fn(*const u8, &MethodInfo, Option<&()>, *const MakeGenericTypeArgs) -> Option<&'static mut Il2CppReflectionType>>(
-            make_generic_method.invoker_method,
-        )
-    };
-
-    runtime_invoke(make_generic_method.method_ptr, make_generic_method, None, &params).ok_or(Il2CppError::FailedMethodInvocation)
+    match make_generic_method.invoke(None, &[&generic, &args])?.into_object() {
+        Some(object) => Ok(unsafe { &mut *(object as *mut Il2CppObject<()> as *mut Il2CppReflectionType) }),
+        None => Err(Il2CppError::FailedMethodInvocation),
+    }
 }
 
 pub fn make_generic<'a>(generic_class: &Il2CppClass, types: impl AsRef<[&'a Il2CppClass]>) -> Il2CppResult<&'static mut Il2CppClass> {
     let types = types.as_ref();
 
     // Represent it as ReflectionType instead, as they have the same layout
-    let array: &mut Il2CppArray<&mut Il2CppReflectionType> = Il2CppArray::new_specific(SystemType::class(), types.len())?;
+    let array: &mut Il2CppArray<&mut Il2CppReflectionType> = unsafe { api::array_new(SystemType::class(), types.len()) }.ok_or(Il2CppError::FailedArrayInstantiation)?;
 
     // Populate the array with the type of every argument
     for (arg, entry) in types.iter().zip(array.iter_mut()) {
@@ -281,7 +814,7 @@ pub fn make_generic<'a>(generic_class: &Il2CppClass, types: impl AsRef<[&'a Il2C
 
     let class_type = Il2CppType::get_object(generic_class.get_type())?;
 
-    let reflection_type = make_generic_type(class_type, array).unwrap();
+    let reflection_type = make_generic_type(class_type, array)?;
 
     Il2CppClass::from_system_type(reflection_type)
 }
@@ -304,6 +837,28 @@ pub trait Il2CppClassData {
     fn instantiate_as<T: 'static>() -> Il2CppResult<&'static mut T> {
         super::instantiate_class(Self::class())
     }
+
+    /// Layout of the Rust structure of the class, recorded by `#[unity::class]`.
+    fn layout() -> Option<ClassLayout> {
+        None
+    }
+
+    /// Make sure the fields of the Rust structure are where the class in the game has them.
+    ///
+    /// Meant to be called when the plugin starts, as a mismatched layout silently corrupts memory.  
+    /// See [`layout`](super::layout) for how fields are matched.
+    ///
+    /// Example:
+    ///
+    /// ```ignore
+    /// UniversalRenderPipelineAsset::verify_layout().unwrap_or_else(|err| panic!("{}", err));
+    /// ```
+    fn verify_layout() -> Il2CppResult<()>
+    where
+        Self: Sized,
+    {
+        super::layout::verify::<Self>()
+    }
 }
 
 impl Il2CppClassData for u8 {
@@ -313,14 +868,14 @@ impl Il2CppClassData for u8 {
     fn class() -> &'static Il2CppClass {
         static CLASS_TYPE: std::sync::LazyLock<&'static mut Il2CppClass> = std::sync::LazyLock::new(|| {
             Il2CppClass::from_name("System", "Byte")
-                .expect(&format!("Failed to find class {}.{}", "System", "Byte"))
+                .unwrap_or_else(|_| panic!("Failed to find class {}.{}", "System", "Byte"))
         });
 
         &CLASS_TYPE
     }
 
     fn class_mut() -> &'static mut Il2CppClass {
-        Self::class().clone()
+        Self::class().clone().unwrap_or_else(|err| panic!("Failed to copy class {}.{}: {}", "System", "Byte", err))
     }
 }
 
@@ -335,6 +890,236 @@ macro_rules! get_generic_class {
     };
 }
 
-// ddlc offset
-#[skyline::from_offset(0x16d9c0)]
-pub fn setup_gc_descriptor(class: &Il2CppClass);
+#[cfg(target_os = "horizon")]
+pub use super::api::setup_gc_descriptor;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        il2cpp::method::OptionalMethod,
+        mock::{ClassBuilder, MethodBuilder, Mock},
+        system::Il2CppString,
+    };
+
+    #[test]
+    fn find_classes_and_methods() {
+        let mock = Mock::install();
+
+        let base = ClassBuilder::new(mock.image("ClassTests"), "Tests.Class", "Base")
+            .method(MethodBuilder::new("Run").parameters_count(1))
+            .build();
+
+        let derived = ClassBuilder::new(mock.image("ClassTests"), "Tests.Class", "Derived")
+            .parent(base)
+            .method(MethodBuilder::new("Run").parameters_count(2))
+            .build();
+
+        let found = Il2CppClass::from_name("Tests.Class", "Derived").unwrap();
+        assert!(std::ptr::eq(found, derived));
+        assert_eq!(found.get_namespace(), "Tests.Class");
+        assert_eq!(found.get_name(), "Derived");
+        assert!(std::ptr::eq(found.parent().unwrap(), base));
+
+        assert!(matches!(Il2CppClass::from_name("Tests.Class", "Missing"), Err(Il2CppError::MissingClass(name)) if name == "Missing"));
+
+        // Methods of the parents are found too, like the runtime does
+        assert_eq!(found.get_method_from_name("Run", 2).unwrap().parameters_count, 2);
+        assert_eq!(found.get_method_from_name("Run", 1).unwrap().parameters_count, 1);
+        assert!(found.get_method_from_name("Run", 3).is_err());
+
+        assert!(found.is_subclass_of(base));
+        assert!(!base.is_subclass_of(found));
+    }
+
+    #[crate::class("Tests.Overload", "Healer")]
+    struct Healer {}
+
+    #[crate::from_offset("Tests.Overload", "Healer", "Heal", "(System.Single)")]
+    fn healer_heal_single(this: &Healer, amount: f32, method_info: OptionalMethod) -> i32;
+
+    #[test]
+    fn overloads() {
+        let mock = Mock::install();
+        let image = mock.image("ClassTests");
+        let (int, single, string) = (mock.class("System", "Int32").unwrap(), mock.class("System", "Single").unwrap(), mock.class("System", "String").unwrap());
+
+        let unit = ClassBuilder::new(image, "Tests.Overload", "Unit")
+            .method(MethodBuilder::new("Heal").parameter("name", string).returns(int).function(|_this: &Healer, _name: &Il2CppString| 3))
+            .build();
+
+        let healer = ClassBuilder::new(image, "Tests.Overload", "Healer")
+            .parent(unit)
+            .method(MethodBuilder::new("Heal").parameter("amount", int).returns(int).function(|_this: &Healer, _amount: i32| 1))
+            .method(MethodBuilder::new("Heal").parameter("amount", single).returns(int).function(|_this: &Healer, _amount: f32| 2))
+            .build();
+
+        assert_eq!(healer.get_method("Heal", &[int]).unwrap().get_signature(), "(System.Int32)");
+        assert_eq!(healer.get_method("Heal", &[single]).unwrap().get_signature(), "(System.Single)");
+        assert!(std::ptr::eq(healer.get_method("Heal", &[string]).unwrap().class.unwrap(), unit));
+        assert!(matches!(healer.get_method("Heal", &[int, int]), Err(Il2CppError::MissingMethod)));
+        assert!(matches!(unit.get_method("Heal", &[single]), Err(Il2CppError::MissingMethod)));
+
+        let by_signature = healer.get_method_from_signature("Heal", "(System.Single)").unwrap();
+        assert!(std::ptr::eq(by_signature, healer.get_method("Heal", &[single]).unwrap()));
+
+        // The signature given to the macro picks the overload, where the argument count alone would not
+        let this = Healer::instantiate().unwrap();
+        assert_eq!(unsafe { healer_heal_single(this, 1.5, None) }, 2);
+    }
+
+    #[test]
+    fn hierarchy_queries() {
+        let mock = Mock::install();
+        let image = mock.image("ClassTests");
+
+        let usable = ClassBuilder::new(image, "Tests.Hierarchy", "IUsable").interface().build();
+        let consumable = ClassBuilder::new(image, "Tests.Hierarchy", "IConsumable").interface().implements(usable).build();
+        let item = ClassBuilder::new(image, "Tests.Hierarchy", "Item").build();
+        let potion = ClassBuilder::new(image, "Tests.Hierarchy", "Potion").parent(item).implements(consumable).build();
+        let elixir = ClassBuilder::new(image, "Tests.Hierarchy", "Elixir").parent(potion).build();
+        let object = mock.class("System", "Object").unwrap();
+
+        let parents = elixir.parents().map(|class| class.get_name()).collect::<Vec<_>>();
+        assert_eq!(parents, ["Potion", "Item", "Object"]);
+
+        assert!(elixir.is_subclass_of(item) && elixir.is_subclass_of(potion));
+        assert!(!item.is_subclass_of(item) && !item.is_subclass_of(potion));
+        assert!(!potion.is_subclass_of(consumable));
+
+        assert!(item.is_assignable_from(item) && item.is_assignable_from(elixir));
+        assert!(!potion.is_assignable_from(item));
+        assert!(object.is_assignable_from(elixir) && object.is_assignable_from(usable));
+
+        // Interfaces are implemented by subclasses, and through the interfaces they inherit
+        assert!(consumable.is_assignable_from(elixir) && usable.is_assignable_from(elixir));
+        assert!(!usable.is_assignable_from(item) && !consumable.is_assignable_from(usable));
+
+        // Arrays of references are covariant, unlike arrays of values
+        let (items, elixirs) = (mock.class("Tests.Hierarchy", "Item[]").unwrap(), mock.class("Tests.Hierarchy", "Elixir[]").unwrap());
+        assert!(items.is_assignable_from(elixirs) && !elixirs.is_assignable_from(items));
+
+        let (objects, ints) = (mock.class("System", "Object[]").unwrap(), mock.class("System", "Int32[]").unwrap());
+        assert!(objects.is_assignable_from(items) && !objects.is_assignable_from(ints));
+    }
+
+    #[test]
+    fn interface_dispatch() {
+        let mock = Mock::install();
+        let image = mock.image("ClassTests");
+
+        let ranked = ClassBuilder::new(image, "Tests.Interface", "IRanked")
+            .interface()
+            .virtual_method(MethodBuilder::new("CompareTo").parameters_count(1))
+            .virtual_method(MethodBuilder::new("GetRank"))
+            .build();
+
+        let soldier = ClassBuilder::new(image, "Tests.Interface", "Soldier")
+            .implements(ranked)
+            .method(MethodBuilder::new("CompareTo").parameters_count(1))
+            .virtual_method(MethodBuilder::new("GetRank"))
+            .build();
+
+        let knight = ClassBuilder::new(image, "Tests.Interface", "Knight").parent(soldier).virtual_method(MethodBuilder::new("GetRank")).build();
+        let villager = ClassBuilder::new(image, "Tests.Interface", "Villager").virtual_method(MethodBuilder::new("GetRank")).build();
+
+        assert!(std::ptr::eq(soldier.get_interfaces()[0], ranked));
+        assert!(knight.get_interfaces().is_empty());
+        assert!(knight.get_interface_offset(ranked).is_some());
+
+        let declaring_class = |entry: &VirtualInvoke| entry.method_info.class.unwrap().get_name();
+
+        let compare_to = ranked.get_method_from_name("CompareTo", 1).unwrap();
+        assert_eq!(declaring_class(soldier.get_interface_method(compare_to).unwrap()), "Soldier");
+        assert_eq!(declaring_class(knight.get_interface_method(compare_to).unwrap()), "Soldier");
+
+        // Overrides of the parent implementation are found through the interface too
+        let get_rank = ranked.get_method_from_name("GetRank", 0).unwrap();
+        assert_eq!(declaring_class(soldier.get_interface_method(get_rank).unwrap()), "Soldier");
+        assert_eq!(declaring_class(knight.get_interface_method(get_rank).unwrap()), "Knight");
+        assert_eq!(declaring_class(knight.get_interface_method_from_name(ranked, "GetRank", 0).unwrap()), "Knight");
+
+        assert!(matches!(villager.get_interface_method(get_rank), Err(Il2CppError::MissingInterface(..))));
+        assert!(matches!(
+            knight.get_interface_method(soldier.get_method_from_name("GetRank", 0).unwrap()),
+            Err(Il2CppError::NotAnInterfaceMethod(name)) if name == "GetRank"
+        ));
+    }
+
+    #[test]
+    fn generic_instances() {
+        let mock = Mock::install();
+        let image = mock.image("ClassTests");
+
+        let stack = ClassBuilder::new(image, "Tests.Hierarchy", "IStack`1").interface().build();
+        let list = ClassBuilder::new(image, "Tests.Hierarchy", "List`1").build();
+        let (int, string) = (mock.class("System", "Int32").unwrap(), mock.class("System", "String").unwrap());
+
+        let ints = make_generic(list, [&*int]).unwrap();
+        let strings = make_generic(list, [&*string]).unwrap();
+        assert!(ints.is_generic_instance_of(list, &[int]));
+        assert!(!ints.is_generic_instance_of(list, &[string]) && !ints.is_generic_instance_of(list, &[]));
+        assert!(!list.is_generic_instance_of(list, &[int]));
+
+        // Field and method lookups on the definition apply to any instance, unlike assignments
+        assert!(ints.derives_from(list) && strings.derives_from(list));
+        assert!(!list.is_assignable_from(ints) && !strings.is_assignable_from(ints));
+
+        // Through a parent, and through an interface
+        let int_stack = make_generic(stack, [&*int]).unwrap();
+        let scores = ClassBuilder::new(image, "Tests.Hierarchy", "Scores").parent(ints).implements(int_stack).build();
+        assert!(scores.is_generic_instance_of(list, &[int]) && scores.is_generic_instance_of(stack, &[int]));
+        assert!(!scores.is_generic_instance_of(stack, &[string]));
+    }
+
+    #[test]
+    fn make_generic_exceptions() {
+        let mock = Mock::install();
+        let class = ClassBuilder::new(mock.image("ClassTests"), "Tests.Class", "NotGeneric").build();
+        let int = mock.class("System", "Int32").unwrap();
+
+        assert!(matches!(make_generic(class, [&*int]), Err(Il2CppError::Exception(name, ..)) if name == "System.InvalidOperationException"));
+    }
+
+    #[test]
+    fn from_il2cpptype() {
+        let mock = Mock::install();
+        let class = ClassBuilder::new(mock.image("ClassTests"), "Tests.Class", "FromType").build();
+
+        let found = Il2CppClass::from_il2cpptype(class.get_type()).unwrap();
+        assert!(std::ptr::eq(found, class));
+    }
+
+    #[crate::vhook("Tests.Class", "Sword", "GetDamage")]
+    fn sword_get_damage(this: &Il2CppObject<()>, method_info: OptionalMethod) -> i32 {
+        call_original!(this, method_info) + 5
+    }
+
+    #[test]
+    fn vhook_install() {
+        let mock = Mock::install();
+
+        // Installing fails until the class is loaded, and can be tried again
+        assert!(matches!(sword_get_damage::install(), Err(Il2CppError::MissingClass(_))));
+        assert!(sword_get_damage::original().is_null());
+
+        let sword = ClassBuilder::new(mock.image("ClassTests"), "Tests.Class", "Sword")
+            .virtual_method(MethodBuilder::new("GetDamage").function(|_this: &Il2CppObject<()>| 10))
+            .build();
+
+        sword_get_damage::install().unwrap();
+        sword_get_damage::install().unwrap();
+
+        let method = sword.get_method_from_name("GetDamage", 0).unwrap();
+        let entry = sword.resolve_virtual(method).unwrap();
+
+        assert_eq!(entry.method_ptr, sword_get_damage as *mut u8);
+        assert_ne!(sword_get_damage::original(), sword_get_damage as *mut u8);
+
+        // Called through the vtable, like the game does
+        let object: &Il2CppObject<()> = crate::il2cpp::instantiate_class(sword).unwrap();
+        let get_damage = unsafe { std::mem::transmute::<*mut u8, extern "C" fn(&Il2CppObject<()>, OptionalMethod) -> i32>(entry.method_ptr) };
+
+        assert_eq!(get_damage(object, Some(entry.method_info)), 15);
+    }
+}
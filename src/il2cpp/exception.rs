@@ -0,0 +1,137 @@
+//! Managed exceptions thrown by methods called from Rust.
+//!
+//! Methods called through [`MethodInfo::invoke`](super::method::MethodInfo::invoke) catch the exceptions they throw, which are returned as [`Il2CppError::Exception`].
+//! In the other direction, hooks declared `extern "C-unwind"` can throw exceptions of their own with [`raise`](super::raise), the way the original C# would.
+//!
+//! Example:
+//!
+//! ```ignore
+//! #[unity::vhook("App", "Unit", "SetHp")]
+//! pub extern "C-unwind" fn unit_set_hp(this: &mut Unit, hp: i32, method_info: OptionalMethod) {
+//!     if hp < 0 {
+//!         unity::il2cpp::raise(Il2CppException::new_argument("hp cannot be negative").unwrap());
+//!     }
+//!
+//!     call_original!(this, hp, method_info)
+//! }
+//! ```
+
+use super::{class::Il2CppClass, object::{Il2CppArray, Il2CppObject}};
+use crate::{system::Il2CppString, Il2CppError, Il2CppResult};
+
+/// Represents a C# `System.Exception`, or any of its subclasses.
+#[repr(C)]
+#[crate::class("System", "Exception")]
+pub struct Il2CppException {
+    class_name: Option<&'static Il2CppString>,
+    pub message: Option<&'static Il2CppString>,
+    data: *const u8,
+    pub inner_exception: Option<&'static Il2CppException>,
+    help_url: Option<&'static Il2CppString>,
+    trace_ips: Option<&'static Il2CppArray<usize>>,
+    stack_trace: Option<&'static Il2CppString>,
+    remote_stack_trace: Option<&'static Il2CppString>,
+    remote_stack_index: i32,
+    dynamic_methods: *const u8,
+    pub hresult: i32,
+    source: Option<&'static Il2CppString>,
+    safe_serialization_manager: *const u8,
+    captured_traces: *const u8,
+    native_trace_ips: *const u8,
+}
+
+impl Il2CppException {
+    /// Create an exception of the class `namespace.name`, calling its constructor taking a message.
+    pub fn from_name(namespace: impl AsRef<str>, name: impl AsRef<str>, message: impl Into<&'static Il2CppString>) -> Il2CppResult<&'static mut Il2CppException> {
+        let message: &'static Il2CppString = message.into();
+        let class = Il2CppClass::from_name(namespace, name)?;
+        let exception: &'static mut Il2CppException = super::instantiate_class(class)?;
+
+        let constructor = class.get_method_from_name(".ctor", 1)?;
+        let this = unsafe { &*(exception as *const Il2CppException as *const Il2CppObject<()>) };
+
+        constructor.invoke(Some(this), &[&message])?;
+
+        Ok(exception)
+    }
+
+    /// Create a `System.ArgumentException`, for arguments that are not valid.
+    pub fn new_argument(message: impl Into<&'static Il2CppString>) -> Il2CppResult<&'static mut Il2CppException> {
+        Self::from_name("System", "ArgumentException", message)
+    }
+
+    /// Create a `System.InvalidOperationException`, for calls that are not valid in the current state of the object.
+    pub fn new_invalid_operation(message: impl Into<&'static Il2CppString>) -> Il2CppResult<&'static mut Il2CppException> {
+        Self::from_name("System", "InvalidOperationException", message)
+    }
+
+    /// Create a `System.NullReferenceException`.
+    pub fn new_null_reference(message: impl Into<&'static Il2CppString>) -> Il2CppResult<&'static mut Il2CppException> {
+        Self::from_name("System", "NullReferenceException", message)
+    }
+
+    /// The full name of the class of the exception, such as `System.ArgumentException`.
+    pub fn get_class_name(&self) -> String {
+        self.get_class().to_string()
+    }
+
+    /// The message of the exception, as returned by `Exception.Message` which subclasses can override.
+    pub fn get_message(&self) -> Option<String> {
+        self.get_property::<Option<&Il2CppString>>("Message")
+            .unwrap_or(self.message)
+            .map(ToString::to_string)
+    }
+
+    /// The managed stack trace of the exception, as returned by `Exception.StackTrace`.
+    pub fn get_stack_trace(&self) -> Option<String> {
+        self.get_property::<Option<&Il2CppString>>("StackTrace")
+            .unwrap_or(self.stack_trace)
+            .map(ToString::to_string)
+    }
+
+    /// Turn the exception into an [`Il2CppError::Exception`], so it can be returned like any other error.
+    pub fn to_error(&self) -> Il2CppError {
+        Il2CppError::Exception(self.get_class_name(), self.get_message().unwrap_or_default(), self.get_stack_trace().unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{il2cpp::class::Il2CppClassData, mock::{ClassBuilder, Mock}};
+
+    #[test]
+    fn builders() {
+        let mock = Mock::install();
+        let exception = Il2CppException::class();
+
+        let argument = Il2CppException::new_argument("hp cannot be negative").unwrap();
+        assert_eq!(argument.get_class_name(), "System.ArgumentException");
+        assert_eq!(argument.get_message().as_deref(), Some("hp cannot be negative"));
+        assert!(exception.is_assignable_from(argument.get_class()));
+
+        let invalid_operation = Il2CppException::new_invalid_operation("the unit is not deployed").unwrap();
+        assert_eq!(invalid_operation.get_class_name(), "System.InvalidOperationException");
+
+        let null_reference = Il2CppException::new_null_reference("the unit is null").unwrap();
+        assert!(matches!(
+            null_reference.to_error(),
+            Il2CppError::Exception(name, message, _) if name == "System.NullReferenceException" && message == "the unit is null"
+        ));
+
+        // Only classes with a constructor taking a message can be made this way
+        ClassBuilder::new(mock.image("ExceptionTests"), "Tests.Exception", "NotAnException").build();
+        assert!(matches!(Il2CppException::from_name("Tests.Exception", "NotAnException", "message"), Err(Il2CppError::MissingMethod)));
+        assert!(matches!(Il2CppException::from_name("Tests.Exception", "Missing", "message"), Err(Il2CppError::MissingClass(..))));
+    }
+
+    #[test]
+    fn raise_on_host() {
+        Mock::install();
+
+        let exception = Il2CppException::new_argument("hp cannot be negative").unwrap();
+        let panic = std::panic::catch_unwind(|| crate::il2cpp::raise(exception)).unwrap_err();
+
+        assert_eq!(panic.downcast_ref::<String>().map(String::as_str), Some("`System.ArgumentException` was thrown: hp cannot be negative"));
+    }
+}
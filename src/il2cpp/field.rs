@@ -0,0 +1,368 @@
+use std::{ffi::CStr, mem::MaybeUninit};
+
+use unity_core::types::Il2CppTypeEnum;
+
+use super::{
+    api,
+    class::{Il2CppClass, Il2CppClassData},
+    object::Il2CppObject,
+    Il2CppType,
+};
+use crate::{Il2CppError, Il2CppResult};
+
+pub(crate) const FIELD_ATTRIBUTE_STATIC: u16 = 0x10;
+const FIELD_ATTRIBUTE_INIT_ONLY: u16 = 0x20;
+const FIELD_ATTRIBUTE_LITERAL: u16 = 0x40;
+
+/// Offset given to thread static fields, as they are stored apart from the other static fields.
+const THREAD_STATIC_FIELD_OFFSET: i32 = -1;
+
+/// Type representing the reflection information of a C# field.
+///
+/// Can be used to find where a field is stored without mirroring the whole class.
+///
+/// Example:
+///
+/// ```no_run
+/// # use unity::prelude::*;
+/// # fn main() -> Il2CppResult<()> {
+/// let field = Il2CppClass::from_name("App", "Unit")?.get_field_from_name("m_Hp")?;
+/// println!("{} is at {:#x}", field.get_name(), field.get_offset());
+/// # Ok(())
+/// # }
+/// ```
+#[repr(C)]
+pub struct FieldInfo {
+    pub(crate) name: *const u8,
+    pub(crate) ty: &'static Il2CppType,
+    pub(crate) parent: &'static Il2CppClass,
+    pub(crate) offset: i32,
+    pub token: u32,
+}
+
+unsafe impl Send for FieldInfo {}
+unsafe impl Sync for FieldInfo {}
+
+impl FieldInfo {
+    pub fn get_name(&self) -> String {
+        unsafe { String::from_utf8_lossy(CStr::from_ptr(self.name as _).to_bytes()).to_string() }
+    }
+
+    pub fn get_type(&self) -> &'static Il2CppType {
+        self.ty
+    }
+
+    /// The class declaring the field, which can be a parent of the class it was found through.
+    pub fn get_parent(&self) -> &'static Il2CppClass {
+        self.parent
+    }
+
+    /// Offset of the field from the start of the object, including the object header even for value types.
+    ///
+    /// Offsets of static fields are relative to the static fields of the class instead, see [`Il2CppClass::get_static_fields`].
+    pub fn get_offset(&self) -> i32 {
+        self.offset
+    }
+
+    /// The `FieldAttributes` of the field, such as `Static` (0x10) or `Literal` (0x40).
+    pub fn get_attributes(&self) -> u16 {
+        self.ty.get_attributes()
+    }
+
+    pub fn is_static(&self) -> bool {
+        self.get_attributes() & FIELD_ATTRIBUTE_STATIC != 0
+    }
+
+    /// Whether the field is `readonly`.
+    pub fn is_init_only(&self) -> bool {
+        self.get_attributes() & FIELD_ATTRIBUTE_INIT_ONLY != 0
+    }
+
+    /// Whether the field is a `const`, which only lives in the metadata and has no storage.
+    pub fn is_literal(&self) -> bool {
+        self.get_attributes() & FIELD_ATTRIBUTE_LITERAL != 0
+    }
+
+    pub fn is_thread_static(&self) -> bool {
+        self.is_static() && self.offset == THREAD_STATIC_FIELD_OFFSET
+    }
+
+    /// Read the field from `object`, or from the static fields of its class if `None`.
+    pub(crate) fn get_value<V: FieldValue>(&self, object: Option<*const u8>) -> Il2CppResult<V> {
+        let ptr = self.value_ptr(object)?;
+
+        if !V::can_read(self.ty) {
+            return Err(Il2CppError::FieldTypeMismatch(self.get_name(), std::any::type_name::<V>()));
+        }
+
+        unsafe { read_value(std::ptr::read_unaligned(ptr as *const MaybeUninit<V>), || self.get_name()) }
+    }
+
+    /// Write the field of `object`, or of the static fields of its class if `None`.
+    pub(crate) fn set_value<V: FieldValue>(&self, object: Option<*mut u8>, value: V) -> Il2CppResult<()> {
+        let ptr = self.value_ptr(object.map(|object| object as *const u8))?;
+
+        if !value.can_write(self.ty) {
+            return Err(Il2CppError::FieldTypeMismatch(self.get_name(), std::any::type_name::<V>()));
+        }
+
+        unsafe {
+            if V::IS_REFERENCE {
+                let object = object.map(|object| &*(object as *const Il2CppObject<()>));
+                api::gc_wbarrier_set_field(object, ptr as *mut *const u8, std::mem::transmute_copy(&value))?;
+            } else {
+                std::ptr::write_unaligned(ptr as *mut V, value);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn value_ptr(&self, object: Option<*const u8>) -> Il2CppResult<*mut u8> {
+        let reason = match object {
+            Some(_) if self.is_static() => "it is static",
+            Some(object) => return Ok(unsafe { object.add(self.offset as usize) as *mut u8 }),
+            None if !self.is_static() => "it is not static",
+            None if self.is_literal() => "it is a constant",
+            None if self.is_thread_static() => "it is thread static",
+            None if self.parent.static_fields.is_null() => "its class has no static fields",
+            None => return Ok(unsafe { (self.parent.static_fields as *mut u8).add(self.offset as usize) }),
+        };
+
+        Err(Il2CppError::InvalidFieldAccess(self.get_name(), reason))
+    }
+}
+
+/// A Rust type that fields and properties can be read as and written with, such as through [`Il2CppObject::get_field`].
+///
+/// Implemented for the primitive types and for references to classes.  
+/// Enum fields can be accessed as their underlying type.
+///
+/// Reading `null` as `&T` or `&mut T` fails with [`Il2CppError::NullReference`], `Option<&T>` should be used for references that can be `null`.
+pub trait FieldValue: Sized {
+    /// Whether the value is a reference to a managed object, which has to be written through the write barrier of the garbage collector.
+    const IS_REFERENCE: bool = false;
+
+    /// Whether the value can be `null`, which is only false for references that are not wrapped in an `Option`.
+    const NULLABLE: bool = true;
+
+    /// Whether a field of this type can be read as `Self`.
+    fn can_read(ty: &Il2CppType) -> bool;
+
+    /// Whether this value can be stored in a field of this type.
+    fn can_write(&self, ty: &Il2CppType) -> bool {
+        Self::can_read(ty)
+    }
+
+    /// The class of the value once boxed, so value types can be given where a reference such as `object` is expected.
+    fn boxed_class() -> Option<&'static Il2CppClass> {
+        None
+    }
+}
+
+/// Finish reading a value from managed code, refusing `null` for the references that cannot hold it.
+///
+/// # Safety
+///
+/// `value` must have been read from a field, or returned by a method, of a type [`FieldValue::can_read`] accepts.
+pub(crate) unsafe fn read_value<V: FieldValue>(value: MaybeUninit<V>, name: impl FnOnce() -> String) -> Il2CppResult<V> {
+    if !V::NULLABLE && std::ptr::read_unaligned(value.as_ptr() as *const *const u8).is_null() {
+        return Err(Il2CppError::NullReference(name(), std::any::type_name::<V>()));
+    }
+
+    Ok(value.assume_init())
+}
+
+/// A Rust type mirroring a C# value type, which can be boxed into an object with [`Il2CppObject::box_value`].
+///
+/// Implemented for the primitive types, which are boxed as `System.Int32` and the like.  
+/// Enums can be boxed as their underlying type, but are then boxed as that type rather than as the enum.
+pub trait ValueType: FieldValue + Copy {
+    /// The class of the value type, which boxed values are instances of.
+    fn value_class() -> &'static Il2CppClass;
+}
+
+/// Whether the type is the struct `namespace.name`, to implement [`FieldValue`] for a structure mirroring it.
+///
+/// Example:
+///
+/// ```no_run
+/// # use unity::prelude::*;
+/// # use unity::il2cpp::{field::is_struct, Il2CppType};
+/// # #[derive(Clone, Copy)]
+/// # struct Color { r: f32, g: f32, b: f32, a: f32 }
+/// # fn main() -> Il2CppResult<()> {
+/// impl FieldValue for Color {
+///     fn can_read(ty: &Il2CppType) -> bool {
+///         is_struct(ty, "UnityEngine", "Color")
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn is_struct(ty: &Il2CppType, namespace: impl AsRef<str>, name: impl AsRef<str>) -> bool {
+    ty.get_type_enum() == Some(Il2CppTypeEnum::ValueType)
+        && Il2CppClass::from_il2cpptype(ty).is_ok_and(|class| class.get_namespace() == namespace.as_ref() && class.get_name() == name.as_ref())
+}
+
+/// The type enum of a field, looking through enums for their underlying type.
+pub(crate) fn underlying_type_enum(ty: &Il2CppType) -> Option<Il2CppTypeEnum> {
+    match ty.get_type_enum()? {
+        Il2CppTypeEnum::ValueType => {
+            let class = Il2CppClass::from_il2cpptype(ty).ok()?;
+
+            match class.is_enum() {
+                true => class._1.element_class.get_type().get_type_enum(),
+                false => Some(Il2CppTypeEnum::ValueType),
+            }
+        },
+        type_enum => Some(type_enum),
+    }
+}
+
+/// The class of a field holding a reference to a managed object, or `None` for value types.
+pub(crate) fn reference_class(ty: &Il2CppType) -> Option<&'static Il2CppClass> {
+    match ty.get_type_enum()? {
+        Il2CppTypeEnum::String | Il2CppTypeEnum::Class | Il2CppTypeEnum::Object | Il2CppTypeEnum::SzArray | Il2CppTypeEnum::Array => {
+            Il2CppClass::from_il2cpptype(ty).ok().map(|class| &*class)
+        },
+        Il2CppTypeEnum::GenericInst => Il2CppClass::from_il2cpptype(ty).ok().filter(|class| !class.is_valuetype()).map(|class| &*class),
+        _ => None,
+    }
+}
+
+macro_rules! impl_primitive_field_value {
+    ($($ty:ty as $class:literal => $($type_enum:ident)|+),* $(,)?) => {
+        $(
+            impl FieldValue for $ty {
+                fn can_read(ty: &Il2CppType) -> bool {
+                    matches!(underlying_type_enum(ty), Some($(Il2CppTypeEnum::$type_enum)|+))
+                }
+
+                fn boxed_class() -> Option<&'static Il2CppClass> {
+                    Il2CppClass::from_name("System", $class).ok().map(|class| &*class)
+                }
+            }
+
+            impl ValueType for $ty {
+                fn value_class() -> &'static Il2CppClass {
+                    static CLASS: std::sync::LazyLock<&'static Il2CppClass> = std::sync::LazyLock::new(|| {
+                        &*Il2CppClass::from_name("System", $class).expect(concat!("Failed to find class System.", $class))
+                    });
+
+                    &CLASS
+                }
+            }
+        )*
+    };
+}
+
+impl_primitive_field_value! {
+    bool as "Boolean" => Boolean,
+    i8 as "SByte" => I1,
+    u8 as "Byte" => U1,
+    i16 as "Int16" => I2,
+    u16 as "UInt16" => U2 | Char,
+    i32 as "Int32" => I4,
+    u32 as "UInt32" => U4,
+    i64 as "Int64" => I8,
+    u64 as "UInt64" => U8,
+    f32 as "Single" => R4,
+    f64 as "Double" => R8,
+    isize as "IntPtr" => I,
+    usize as "UIntPtr" => U,
+}
+
+macro_rules! impl_reference_field_value {
+    ($($ty:ty => $nullable:literal),* $(,)?) => {
+        $(
+            impl<T: Il2CppClassData> FieldValue for $ty {
+                const IS_REFERENCE: bool = true;
+                const NULLABLE: bool = $nullable;
+
+                // The field may hold a subclass of `T`, but never a parent.
+                fn can_read(ty: &Il2CppType) -> bool {
+                    reference_class(ty).is_some_and(|class| class.derives_from(T::class()))
+                }
+
+                fn can_write(&self, ty: &Il2CppType) -> bool {
+                    let Some(class) = reference_class(ty) else { return false };
+                    let value: Option<&Il2CppObject<()>> = unsafe { std::mem::transmute_copy(self) };
+
+                    value.map_or(true, |value| value.get_class().derives_from(class))
+                }
+            }
+        )*
+    };
+}
+
+impl_reference_field_value! {
+    &'static T => false,
+    &'static mut T => false,
+    Option<&'static T> => true,
+    Option<&'static mut T> => true,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        mock::{ClassBuilder, MethodBuilder, Mock},
+        system::Il2CppString,
+    };
+
+    #[crate::class("Tests.Field", "Named")]
+    struct Named {
+        name: Option<&'static Il2CppString>,
+        hp: i32,
+    }
+
+    #[crate::class("Tests.Field", "Titled")]
+    struct Titled {
+        title: Option<&'static Il2CppString>,
+    }
+
+    #[test]
+    fn null_references() {
+        let mock = Mock::install();
+
+        ClassBuilder::new(mock.image("FieldTests"), "Tests.Field", "Named")
+            .fields::<NamedFields>()
+            .field("name", Il2CppString::class(), 0x10)
+            .field("hp", mock.class("System", "Int32").unwrap(), 0x18)
+            .build();
+
+        let named = Named::instantiate().unwrap();
+
+        assert!(matches!(named.get_field::<&Il2CppString>("name"), Err(Il2CppError::NullReference(..))));
+        assert!(named.get_field::<Option<&Il2CppString>>("name").unwrap().is_none());
+        assert!(matches!(named.get_field::<i32>("name"), Err(Il2CppError::FieldTypeMismatch(..))));
+
+        named.set_field("name", Il2CppString::new_static("Marth")).unwrap();
+        named.set_field("hp", 20i32).unwrap();
+
+        assert_eq!(named.get_field::<&Il2CppString>("name").unwrap().to_string(), "Marth");
+        assert_eq!(named.hp, 20);
+    }
+
+    #[test]
+    fn null_property() {
+        let mock = Mock::install();
+
+        ClassBuilder::new(mock.image("FieldTests"), "Tests.Field", "Titled")
+            .fields::<TitledFields>()
+            .field("title", Il2CppString::class(), 0x10)
+            .property("Title")
+            .method(MethodBuilder::new("get_Title").returns(Il2CppString::class()).function(|this: &Titled| this.title))
+            .build();
+
+        let titled = Titled::instantiate().unwrap();
+
+        assert!(matches!(titled.get_property::<&Il2CppString>("Title"), Err(Il2CppError::NullReference(..))));
+        assert!(titled.get_property::<Option<&Il2CppString>>("Title").unwrap().is_none());
+
+        titled.title = Some(Il2CppString::new_static("Lyn"));
+
+        assert_eq!(titled.get_property::<&Il2CppString>("Title").unwrap().to_string(), "Lyn");
+    }
+}
@@ -0,0 +1,174 @@
+//! Handles keeping managed objects alive while Rust holds onto them.
+//!
+//! The garbage collector does not scan the memory of the plugin, so an object only referenced from a `static` or a Rust structure gets collected eventually.
+//! Hold a [`GcHandle`] instead of the reference to keep it alive, and a [`WeakGcHandle`] to know if it still is.
+//!
+//! Example:
+//!
+//! ```no_run
+//! # use unity::prelude::*;
+//! # use std::sync::OnceLock;
+//! # use unity::engine::Sprite;
+//! # fn main() -> Il2CppResult<()> {
+//! # let sprite: &Sprite = unimplemented!();
+//! static ICON: OnceLock<GcHandle<Sprite>> = OnceLock::new();
+//!
+//! let _ = ICON.set(GcHandle::new(sprite)?);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{marker::PhantomData, ops::Deref, ptr::NonNull};
+
+use super::{api, class::Il2CppClassData, object::Il2CppObject};
+use crate::Il2CppResult;
+
+/// A strong handle, keeping the object alive until it is dropped.
+///
+/// Only shared references to the object are given, as every clone of the handle points to it.
+pub struct GcHandle<T: Il2CppClassData> {
+    handle: u32,
+    // Strong handles never lose their target, so there is no need to ask the runtime for it.
+    object: NonNull<T>,
+}
+
+impl<T: Il2CppClassData> GcHandle<T> {
+    /// Keep the object alive until the handle is dropped.
+    ///
+    /// Fails if the runtime function making handles cannot be found.
+    pub fn new(object: &T) -> Il2CppResult<Self> {
+        Self::create(object, false)
+    }
+
+    /// Like [`new`](Self::new), but also prevents the object from being moved.
+    ///
+    /// Use it for objects whose address is given to native code.
+    pub fn pinned(object: &T) -> Il2CppResult<Self> {
+        Self::create(object, true)
+    }
+
+    fn create(object: &T, pinned: bool) -> Il2CppResult<Self> {
+        let handle = api::gchandle_new(Self::as_object(object), pinned)?;
+
+        Ok(Self { handle, object: NonNull::from(object) })
+    }
+
+    fn as_object(object: &T) -> &Il2CppObject<()> {
+        unsafe { &*(object as *const T as *const Il2CppObject<()>) }
+    }
+
+    pub fn get(&self) -> &T {
+        unsafe { &*self.object.as_ptr() }
+    }
+
+    /// The handle as given by the runtime, such as to store in a managed `GCHandle`.
+    pub fn raw(&self) -> u32 {
+        self.handle
+    }
+
+    /// Create a weak handle to the same object.
+    pub fn downgrade(&self) -> Il2CppResult<WeakGcHandle<T>> {
+        WeakGcHandle::new(self.get())
+    }
+}
+
+impl<T: Il2CppClassData> Clone for GcHandle<T> {
+    fn clone(&self) -> Self {
+        // The runtime function was found when this handle was made
+        Self::new(self.get()).unwrap_or_else(|err| panic!("{}", err))
+    }
+}
+
+impl<T: Il2CppClassData> Deref for GcHandle<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.get()
+    }
+}
+
+impl<T: Il2CppClassData> Drop for GcHandle<T> {
+    fn drop(&mut self) {
+        // Without the runtime function, the object is kept alive for good
+        let _ = api::gchandle_free(self.handle);
+    }
+}
+
+// The runtime handles are global, and the objects are already shared with every managed thread.
+unsafe impl<T: Il2CppClassData> Send for GcHandle<T> {}
+unsafe impl<T: Il2CppClassData> Sync for GcHandle<T> {}
+
+/// A weak handle, which lets the object be collected.
+pub struct WeakGcHandle<T: Il2CppClassData> {
+    handle: u32,
+    phantom: PhantomData<*mut T>,
+}
+
+impl<T: Il2CppClassData> WeakGcHandle<T> {
+    /// Fails if the runtime function making weak handles cannot be found.
+    pub fn new(object: &T) -> Il2CppResult<Self> {
+        Self::create(object, false)
+    }
+
+    /// Like [`new`](Self::new), but keeps the target until the finalizer of the object is done instead of until it is queued.
+    pub fn track_resurrection(object: &T) -> Il2CppResult<Self> {
+        Self::create(object, true)
+    }
+
+    fn create(object: &T, track_resurrection: bool) -> Il2CppResult<Self> {
+        let handle = api::gchandle_new_weakref(GcHandle::as_object(object), track_resurrection)?;
+
+        Ok(Self { handle, phantom: PhantomData })
+    }
+
+    /// Get the object, or `None` if it was collected.
+    ///
+    /// The object can be collected right after, use [`upgrade`](Self::upgrade) to keep it alive while using it.
+    pub fn get(&self) -> Il2CppResult<Option<&T>> {
+        Ok(api::gchandle_get_target(self.handle)?.map(|object| unsafe { &*(object as *const Il2CppObject<()> as *const T) }))
+    }
+
+    /// Get a strong handle to the object, or `None` if it was collected.
+    pub fn upgrade(&self) -> Il2CppResult<Option<GcHandle<T>>> {
+        self.get()?.map(GcHandle::new).transpose()
+    }
+
+    pub fn raw(&self) -> u32 {
+        self.handle
+    }
+}
+
+impl<T: Il2CppClassData> Drop for WeakGcHandle<T> {
+    fn drop(&mut self) {
+        let _ = api::gchandle_free(self.handle);
+    }
+}
+
+unsafe impl<T: Il2CppClassData> Send for WeakGcHandle<T> {}
+unsafe impl<T: Il2CppClassData> Sync for WeakGcHandle<T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{il2cpp::runtime::Il2CppRuntime, mock::Mock, system::Il2CppString};
+
+    #[test]
+    fn handles() {
+        let mock = Mock::install();
+        let string = Il2CppString::new("Ike");
+
+        let strong = GcHandle::new(string).unwrap();
+        let weak = strong.downgrade().unwrap();
+        let copy = strong.clone();
+
+        assert_ne!(strong.raw(), copy.raw());
+        assert_eq!(copy.to_string(), "Ike");
+        assert!(weak.get().unwrap().is_some_and(|target| std::ptr::eq(target, string)));
+        assert!(weak.upgrade().unwrap().is_some_and(|upgraded| std::ptr::eq(upgraded.get(), string)));
+
+        let raw = weak.raw();
+        drop(weak);
+
+        assert!(mock.runtime().gchandle_get_target(raw).unwrap().is_none());
+    }
+}
@@ -0,0 +1,775 @@
+//! Classes defined in Rust and added to the runtime, for the game to use like any of its own.
+//!
+//! An injected class inherits from a class of the game and its methods are Rust closures.
+//! The runtime gets an [`Il2CppClass`] and [`MethodInfo`]s like the ones generated from the metadata, with a vtable where overridden methods and interfaces point to the closures.
+//! As a result, instances can be given to anything expecting the parent class, such as `GameObject.AddComponent` for a `MonoBehaviour`, and `GetType()` returns the injected class.
+//!
+//! Example:
+//!
+//! ```ignore
+//! #[unity::class("MyMod", "Spinner", inject)]
+//! pub struct Spinner {
+//!     cached_ptr: *const u8,
+//!     speed: f32,
+//! }
+//!
+//! Spinner::injector()
+//!     .parent(MonoBehaviour::class())
+//!     .method(InjectedMethod::new("Update", |this: &mut Spinner| this.speed += 1.0))
+//!     .inject()?;
+//! ```
+
+use std::{
+    any::Any,
+    collections::HashMap,
+    sync::{Arc, LazyLock, RwLock},
+};
+
+use super::{
+    api,
+    class::{Il2CppClass, Il2CppRuntimeInterfaceOffsetPair, VirtualInvoke, TYPE_ATTRIBUTE_PUBLIC, TYPE_ATTRIBUTE_SEALED},
+    exception::Il2CppException,
+    leak_str,
+    method::{
+        MethodInfo,
+        OptionalMethod,
+        ParameterInfo,
+        INVALID_SLOT,
+        METHOD_ATTRIBUTE_HIDE_BY_SIG,
+        METHOD_ATTRIBUTE_PUBLIC,
+        METHOD_ATTRIBUTE_STATIC,
+        METHOD_ATTRIBUTE_VIRTUAL,
+    },
+    object::{Il2CppObject, OBJECT_HEADER_SIZE},
+    Il2CppType,
+    Il2CppTypeEnum,
+};
+use crate::{Il2CppError, Il2CppResult};
+
+const CLASS_ENUMTYPE: u8 = 0x8;
+const CLASS_IS_GENERIC: u8 = 0x10;
+const CLASS_HAS_REFERENCES: u8 = 0x20;
+const CLASS_HAS_CCTOR: u8 = 0x2;
+
+// Classes made by ClassInjector::inject, by address.
+static INJECTED: RwLock<Vec<usize>> = RwLock::new(Vec::new());
+
+// Closures backing injected and mocked methods, by address of their MethodInfo.
+static FUNCTIONS: LazyLock<RwLock<HashMap<usize, Arc<dyn Any + Send + Sync>>>> = LazyLock::new(Default::default);
+
+/// Builds a new class and adds it to the runtime.
+///
+/// The class has no fields or properties of its own as far as the runtime knows. Its instances are given the size of the Rust structure mirroring them,
+/// which is set by the `injector` function `#[unity::class]` generates in `inject` mode.
+pub struct ClassInjector {
+    namespace: String,
+    name: String,
+    parent: Option<&'static Il2CppClass>,
+    instance_size: Option<usize>,
+    interfaces: Vec<&'static Il2CppClass>,
+    methods: Vec<InjectedMethod>,
+}
+
+impl ClassInjector {
+    pub fn new(namespace: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            namespace: namespace.into(),
+            name: name.into(),
+            parent: None,
+            instance_size: None,
+            interfaces: Vec::new(),
+            methods: Vec::new(),
+        }
+    }
+
+    /// Set the class to inherit from, `System.Object` by default.
+    pub fn parent(mut self, parent: &'static Il2CppClass) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    /// Set the size of an instance, header included. By default, instances are as big as the ones of the parent.
+    pub fn instance_size(mut self, size: usize) -> Self {
+        self.instance_size = Some(size);
+        self
+    }
+
+    /// Implement an interface, whose methods are implemented by the methods of the class with the same name and parameter count.
+    pub fn implements(mut self, interface: &'static Il2CppClass) -> Self {
+        self.interfaces.push(interface);
+        self
+    }
+
+    /// Add a method to the class.
+    ///
+    /// It overrides the virtual method of the parent with the same name and parameter count, if there is one.
+    pub fn method(mut self, method: InjectedMethod) -> Self {
+        self.methods.push(method);
+        self
+    }
+
+    /// Create the class and make it available to the runtime, which includes [`Il2CppClass::from_name`].
+    pub fn inject(self) -> Il2CppResult<&'static mut Il2CppClass> {
+        let full_name = format!("{}.{}", self.namespace, self.name);
+        let fail = |reason| Err(Il2CppError::InvalidInjection(full_name.clone(), reason));
+
+        if find(&self.namespace, &self.name).is_some() {
+            return fail("a class of the same name was already injected");
+        }
+
+        let parent: &'static Il2CppClass = match self.parent {
+            Some(parent) => parent,
+            None => Il2CppClass::from_name("System", "Object")?,
+        };
+
+        // The vtable of the parent is only filled in once it is initialized
+        unsafe { api::class_init(parent) };
+
+        if parent.is_valuetype() || parent.is_interface() || parent._2.flags & TYPE_ATTRIBUTE_SEALED != 0 {
+            return fail("its parent cannot be inherited from");
+        }
+
+        let instance_size = self.instance_size.unwrap_or(parent._2.instance_size as usize);
+
+        if instance_size < parent._2.instance_size as usize {
+            return fail("its instances are smaller than the ones of its parent");
+        }
+
+        let void: &'static Il2CppClass = Il2CppClass::from_name("System", "Void")?;
+
+        for method in &self.methods {
+            let parameters = method.parameters.iter().map(|(_, class)| &class._1.byval_arg);
+            let return_type = &method.return_type.unwrap_or(void)._1.byval_arg;
+
+            if let Err(reason) = check_signature(method.is_static(), parameters, Some(return_type), method.argument_sizes, method.return_size) {
+                return fail(reason);
+            }
+        }
+
+        let inherited = parent.get_vtable();
+        let mut interface_offsets = parent.get_interface_offsets().to_vec();
+
+        // Slots holding the methods of an interface are never overridden directly, only through the method implementing them.
+        let mut slots = inherited
+            .iter()
+            .map(|entry| entry.get_name().map(|name| (name, entry.method_info.parameters_count as usize)))
+            .collect::<Vec<_>>();
+
+        for pair in &interface_offsets {
+            let start = pair.offset as usize;
+            slots[start..start + pair.interface_type.get_vtable().len()].fill(None);
+        }
+
+        let mut methods = self.methods;
+
+        for method in methods.iter_mut().filter(|method| !method.is_static()) {
+            let signature = (method.name.clone(), method.parameters.len());
+
+            method.slot = match slots.iter().position(|slot| slot.as_ref() == Some(&signature)) {
+                Some(slot) => slot as u16,
+                None if method.flags & METHOD_ATTRIBUTE_VIRTUAL != 0 => {
+                    slots.push(Some(signature));
+                    (slots.len() - 1) as u16
+                },
+                None => continue,
+            };
+
+            method.flags |= METHOD_ATTRIBUTE_VIRTUAL;
+        }
+
+        // Each new interface gets a range of the vtable after the virtual methods, including the ones it inherits.
+        let mut interfaces = self.interfaces.clone();
+        let first_new_interface = interface_offsets.len();
+
+        while let Some(interface) = interfaces.pop() {
+            if !interface.is_interface() {
+                return fail("it implements a class that is not an interface");
+            }
+
+            if !interface_offsets.iter().any(|pair| std::ptr::eq(pair.interface_type, interface)) {
+                unsafe { api::class_init(interface) };
+
+                interface_offsets.push(Il2CppRuntimeInterfaceOffsetPair { interface_type: interface, offset: slots.len() as i32 });
+                slots.resize(slots.len() + interface.get_vtable().len(), None);
+                interfaces.extend(interface.get_interfaces());
+            }
+        }
+
+        let size = std::mem::size_of::<Il2CppClass>() + std::mem::size_of::<VirtualInvoke>() * slots.len();
+        let dest = api::gc_alloc_fixed(size)?;
+
+        // Start from the parent, so whatever the runtime expects to be set is set to something sensible
+        unsafe { std::ptr::copy_nonoverlapping(parent as *const Il2CppClass as *const u8, dest, std::mem::size_of::<Il2CppClass>()) };
+
+        let class = unsafe { &mut *(dest as *mut Il2CppClass) };
+        let class_ref: &'static Il2CppClass = unsafe { &*(dest as *const Il2CppClass) };
+
+        class._1.name = leak_str(&self.name);
+        class._1.namespace = leak_str(&self.namespace);
+
+        // Injected classes have no definition in the metadata, so the data of their type points to the class itself.
+        // The runtime is taught to find the class from it by [`Il2CppRuntime::inject_class`](super::runtime::Il2CppRuntime::inject_class).
+        class._1.byval_arg = Il2CppType::new(Il2CppTypeEnum::Class, dest);
+        class._1.this_arg = Il2CppType::new(Il2CppTypeEnum::Class, dest);
+        class._1.this_arg.set_byref(true);
+
+        class._1.element_class = class_ref;
+        class._1.cast_class = class_ref;
+        class._1.declaring_type = None;
+        class._1.parent = parent;
+        class._1.generic_class = None;
+        // Every class points to itself there, the runtime reading classes where it expects a vtable.
+        class._1.klass = class_ref;
+        // The type_metadata_handle of the parent is kept, so queries of the metadata, such as custom attributes, keep answering for the parent.
+        class._1.interop_data = std::ptr::null();
+
+        // The fields of the instances are unknown to the collector, which scans them entirely.
+        class._1.gc_desc = std::ptr::null();
+        class._1.fields = std::ptr::null();
+        class._1.events = std::ptr::null();
+        class._1.properties = std::ptr::null();
+        class._1.nested_types = std::ptr::null();
+        class.static_fields = std::ptr::null_mut();
+
+        let mut hierarchy = match parent._2.type_hierarchy.is_null() {
+            true => Vec::new(),
+            false => unsafe { std::slice::from_raw_parts(parent._2.type_hierarchy, parent._2.type_hierarchy_depth as usize) }.to_vec(),
+        };
+
+        hierarchy.push(class_ref);
+
+        class._2.type_hierarchy_depth = hierarchy.len() as u8;
+        class._2.type_hierarchy = Vec::leak(hierarchy).as_ptr();
+
+        class._2.instance_size = instance_size as u32;
+        class._2.actual_size = instance_size as u32;
+        class._2.static_fields_size = 0;
+        class._2.thread_static_fields_size = 0;
+        class._2.flags = TYPE_ATTRIBUTE_PUBLIC;
+        class._2.token = 0;
+        class._2.property_count = 0;
+        class._2.field_count = 0;
+        class._2.event_count = 0;
+        class._2.nested_type_count = 0;
+        class._2.bitflags1 = (parent._2.bitflags1 & !(CLASS_ENUMTYPE | CLASS_IS_GENERIC)) | CLASS_HAS_REFERENCES;
+        class._2.bitflags2 = parent._2.bitflags2 & !CLASS_HAS_CCTOR;
+
+        let methods = methods.into_iter().map(|method| method.build(class_ref)).collect::<Il2CppResult<Vec<_>>>()?;
+
+        let mut vtable = inherited.to_vec();
+
+        for method in methods.iter().filter(|method| method.slot != INVALID_SLOT) {
+            let entry = VirtualInvoke {
+                method_ptr: method.method_ptr,
+                method_info: method,
+            };
+
+            match vtable.get(method.slot as usize).map(|overridden| overridden.method_info) {
+                // The interfaces implemented by the overridden method are now implemented by this one
+                Some(overridden) => vtable
+                    .iter_mut()
+                    .filter(|existing| std::ptr::eq(existing.method_info, overridden))
+                    .for_each(|existing| *existing = entry),
+                None => vtable.push(entry),
+            }
+        }
+
+        for pair in &interface_offsets[first_new_interface..] {
+            for interface_entry in pair.interface_type.get_vtable() {
+                let name = interface_entry.get_name();
+                let count = interface_entry.method_info.parameters_count;
+
+                let Some(implementation) = methods
+                    .iter()
+                    .find(|method| !method.is_static() && method.get_name() == name && method.parameters_count == count)
+                else {
+                    return fail("it does not implement every method of its interfaces");
+                };
+
+                vtable.push(VirtualInvoke {
+                    method_ptr: implementation.method_ptr,
+                    method_info: implementation,
+                });
+            }
+        }
+
+        class._2.interfaces_count = self.interfaces.len() as u16;
+        class._1.implemented_interfaces = Vec::leak(self.interfaces).as_ptr();
+
+        class._2.interface_offsets_count = interface_offsets.len() as u16;
+        class._1.interface_offsets = Vec::leak(interface_offsets).as_ptr();
+
+        class._2.vtable_count = vtable.len() as u16;
+        class.get_vtable_mut().copy_from_slice(&vtable);
+
+        class._2.method_count = methods.len() as u16;
+        class._1.methods = Vec::leak(methods).as_ptr();
+
+        INJECTED.write().unwrap().push(dest as usize);
+        super::runtime::get().inject_class(class_ref);
+
+        Ok(class)
+    }
+}
+
+/// Declares a method of an injected class, implemented by a closure.
+pub struct InjectedMethod {
+    name: String,
+    flags: u16,
+    parameters: Vec<(String, &'static Il2CppClass)>,
+    return_type: Option<&'static Il2CppClass>,
+    method_ptr: *mut u8,
+    invoker: *const u8,
+    function: Arc<dyn Any + Send + Sync>,
+    argument_sizes: &'static [usize],
+    return_size: usize,
+    slot: u16,
+}
+
+impl InjectedMethod {
+    /// Declare a public instance method, implemented by `function`.
+    ///
+    /// The closure receives every argument of the method, starting with `this` for instance methods, but not the trailing `MethodInfo`.
+    /// References are received as such, while value types are received by value. A parameter has to be declared for each argument other than `this`,
+    /// and [`ClassInjector::inject`] fails if the arguments do not have the size of their parameters.
+    ///
+    /// Panicking in the closure aborts, as a panic cannot unwind through the runtime.
+    pub fn new<Args, F: MethodFunction<Args>>(name: impl Into<String>, function: F) -> Self {
+        Self {
+            name: name.into(),
+            flags: METHOD_ATTRIBUTE_PUBLIC | METHOD_ATTRIBUTE_HIDE_BY_SIG,
+            parameters: Vec::new(),
+            return_type: None,
+            method_ptr: F::method_ptr(),
+            invoker: F::invoker(),
+            function: Arc::new(function),
+            argument_sizes: F::ARGUMENT_SIZES,
+            return_size: F::RETURN_SIZE,
+            slot: INVALID_SLOT,
+        }
+    }
+
+    /// Make the method static, meaning its closure does not receive `this`.
+    pub fn static_method(mut self) -> Self {
+        self.flags |= METHOD_ATTRIBUTE_STATIC;
+        self
+    }
+
+    /// Make the method virtual, giving it a new slot in the vtable unless it overrides a method of the parent.
+    pub fn virtual_method(mut self) -> Self {
+        self.flags |= METHOD_ATTRIBUTE_VIRTUAL;
+        self
+    }
+
+    /// Declare the next parameter of the method.
+    pub fn parameter(mut self, name: impl Into<String>, class: &'static Il2CppClass) -> Self {
+        self.parameters.push((name.into(), class));
+        self
+    }
+
+    /// Set the class of the value returned, `System.Void` by default.
+    pub fn returns(mut self, class: &'static Il2CppClass) -> Self {
+        self.return_type = Some(class);
+        self
+    }
+
+    fn is_static(&self) -> bool {
+        self.flags & METHOD_ATTRIBUTE_STATIC != 0
+    }
+
+    fn build(self, class: &'static Il2CppClass) -> Il2CppResult<&'static MethodInfo> {
+        let return_type: &'static Il2CppClass = match self.return_type {
+            Some(return_type) => return_type,
+            None => Il2CppClass::from_name("System", "Void")?,
+        };
+
+        let parameters: &'static [ParameterInfo] = Vec::leak(
+            self.parameters
+                .iter()
+                .enumerate()
+                .map(|(position, (name, class))| ParameterInfo {
+                    name: leak_str(name),
+                    position: position as i32,
+                    token: 0,
+                    parameter_type: &class._1.byval_arg,
+                })
+                .collect(),
+        );
+
+        let method: &'static MethodInfo = Box::leak(Box::new(MethodInfo {
+            method_ptr: self.method_ptr,
+            invoker_method: self.invoker,
+            name: leak_str(&self.name),
+            class: Some(class),
+            return_type: &return_type._1.byval_arg as *const Il2CppType as _,
+            parameters: parameters.as_ptr(),
+            flags: self.flags,
+            slot: self.slot,
+            parameters_count: parameters.len() as u8,
+            ..MethodInfo::new()
+        }));
+
+        register_function(method, self.function);
+
+        Ok(method)
+    }
+}
+
+/// Find an injected class by name.
+pub(crate) fn find(namespace: &str, name: &str) -> Option<&'static mut Il2CppClass> {
+    INJECTED
+        .read()
+        .unwrap()
+        .iter()
+        .map(|address| unsafe { &mut *(*address as *mut Il2CppClass) })
+        .find(|class| class.get_namespace() == namespace && class.get_name() == name)
+}
+
+/// Find the injected class a type refers to, which has the class itself as its data.
+pub(crate) fn class_from_type(ty: &Il2CppType) -> Option<&'static mut Il2CppClass> {
+    if ty.get_type_enum() != Some(Il2CppTypeEnum::Class) {
+        return None;
+    }
+
+    let data = unsafe { ty.data.data } as usize;
+
+    INJECTED
+        .read()
+        .unwrap()
+        .iter()
+        .find(|address| **address == data)
+        .map(|address| unsafe { &mut *(*address as *mut Il2CppClass) })
+}
+
+/// Whether the class was made by a [`ClassInjector`].
+pub fn is_injected(class: &Il2CppClass) -> bool {
+    let address = class.get_original() as *const Il2CppClass as usize;
+    INJECTED.read().unwrap().contains(&address)
+}
+
+pub(crate) fn register_function(method: &'static MethodInfo, function: Arc<dyn Any + Send + Sync>) {
+    FUNCTIONS.write().unwrap().insert(method as *const MethodInfo as usize, function);
+}
+
+pub(crate) fn function_for(method: OptionalMethod) -> Option<Arc<dyn Any + Send + Sync>> {
+    let method = method? as *const MethodInfo as usize;
+
+    FUNCTIONS.read().unwrap().get(&method).cloned()
+}
+
+// Arguments are given to invokers as the object itself for references, and as a pointer to the value for value types.
+fn is_given_by_value(ty: &Il2CppType) -> bool {
+    !ty.is_byref() && unsafe { api::class_from_il2cpptype(ty) }.is_some_and(|class| class.is_valuetype())
+}
+
+// Size a function has to use for a value of this type: the value itself for value types, a pointer otherwise.
+fn size_of_type(ty: &Il2CppType) -> usize {
+    match unsafe { api::class_from_il2cpptype(ty) }.filter(|class| class.is_valuetype() && !ty.is_byref()) {
+        Some(class) => {
+            unsafe { api::class_init(class) };
+            (class._2.instance_size as usize).saturating_sub(OBJECT_HEADER_SIZE)
+        },
+        None => std::mem::size_of::<*const u8>(),
+    }
+}
+
+/// Make sure a function taking arguments of these sizes can implement a method, before anything calls it.
+///
+/// The invoker reads each argument with the size the function takes and has no way to report a mismatch, as it is called by the runtime.
+pub(crate) fn check_signature<'a>(
+    is_static: bool,
+    parameters: impl ExactSizeIterator<Item = &'a Il2CppType>,
+    return_type: Option<&Il2CppType>,
+    argument_sizes: &[usize],
+    return_size: usize,
+) -> Result<(), &'static str> {
+    if argument_sizes.len() != parameters.len() + usize::from(!is_static) {
+        return Err("a method does not declare a parameter for every argument of its function");
+    }
+
+    // `this` is always given as a pointer, even to a value type
+    let this = (!is_static).then_some(std::mem::size_of::<*const u8>());
+
+    if !this.into_iter().chain(parameters.map(size_of_type)).eq(argument_sizes.iter().copied()) {
+        return Err("an argument of a function does not have the size of its parameter");
+    }
+
+    let expected = match return_type {
+        Some(ty) if ty.get_type_enum() != Some(Il2CppTypeEnum::Void) => size_of_type(ty),
+        _ => 0,
+    };
+
+    match expected == return_size {
+        true => Ok(()),
+        false => Err("a function does not return a value of the size of its return type"),
+    }
+}
+
+/// Throw a managed exception from a trampoline that cannot find its function, as it was not given the MethodInfo made for it.
+#[cold]
+fn missing_function() -> ! {
+    match Il2CppException::new_invalid_operation("a method implemented in Rust was called without its MethodInfo") {
+        Ok(exception) => super::raise(exception),
+        Err(_) => std::process::abort(),
+    }
+}
+
+/// Call the function of a trampoline, aborting if it panics as the runtime cannot be unwound through by a panic.
+fn call_or_abort<R>(function: impl FnOnce() -> R) -> R {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(function)).unwrap_or_else(|_| std::process::abort())
+}
+
+/// Reads the arguments an invoker receives, in the order the closure takes them.
+struct InvokerArgs {
+    method: &'static MethodInfo,
+    this: Option<*const u8>,
+    args: *const *const u8,
+    index: usize,
+}
+
+impl InvokerArgs {
+    fn new(method: &'static MethodInfo, this: *const u8, args: *const *const u8) -> Self {
+        Self {
+            method,
+            this: (!method.is_static()).then_some(this),
+            args,
+            index: 0,
+        }
+    }
+
+    unsafe fn next<A>(&mut self) -> A {
+        let (arg, by_value) = match self.this.take() {
+            Some(this) => (this, false),
+            None => {
+                let parameter = &self.method.get_parameters()[self.index];
+                let arg = *self.args.add(self.index);
+                self.index += 1;
+
+                (arg, is_given_by_value(parameter.parameter_type))
+            },
+        };
+
+        // The size of `A` was checked against the parameter by `check_signature` when the method was made
+        match by_value {
+            true => std::ptr::read_unaligned(arg as *const A),
+            false => std::mem::transmute_copy(&arg),
+        }
+    }
+
+    // Invokers return value types boxed.
+    unsafe fn box_return<R>(&self, value: R) -> *mut u8 {
+        let Some(ty) = self.method.get_return_type() else {
+            return std::ptr::null_mut();
+        };
+
+        if std::mem::size_of::<R>() == 0 || ty.get_type_enum() == Some(Il2CppTypeEnum::Void) {
+            return std::ptr::null_mut();
+        }
+
+        match api::class_from_il2cpptype(ty).filter(|class| class.is_valuetype() && !ty.is_byref()) {
+            Some(class) => api::value_box(class, &value as *const R as *const u8).map_or(std::ptr::null_mut(), |object| object as *mut Il2CppObject<()> as *mut u8),
+            None => std::mem::transmute_copy(&value),
+        }
+    }
+}
+
+/// Closures that can implement a method, see [`InjectedMethod::new`].
+///
+/// Implemented for closures of up to 8 arguments.
+pub trait MethodFunction<Args>: Send + Sync + 'static {
+    /// Amount of arguments taken by the closure.
+    const ARITY: usize;
+
+    /// Size of each argument taken by the closure.
+    const ARGUMENT_SIZES: &'static [usize];
+
+    /// Size of the value returned by the closure.
+    const RETURN_SIZE: usize;
+
+    /// Function that calls the closure of the MethodInfo it receives as its last argument.
+    fn method_ptr() -> *mut u8;
+
+    /// Invoker that calls [`method_ptr`](Self::method_ptr) with the arguments it receives in an array, like the ones generated by Il2Cpp do.
+    fn invoker() -> *const u8;
+}
+
+/// Functions that also take the `MethodInfo` as their last argument, like the ones generated by Il2Cpp and hooks.
+///
+/// Implemented for functions and closures of up to 8 arguments besides the `MethodInfo`.
+pub trait NativeFunction<Args>: Send + Sync + 'static {
+    /// Amount of arguments taken by the function, not counting the `MethodInfo`.
+    const ARITY: usize;
+
+    /// Size of each argument taken by the function, not counting the `MethodInfo`.
+    const ARGUMENT_SIZES: &'static [usize];
+
+    /// Size of the value returned by the function.
+    const RETURN_SIZE: usize;
+
+    /// Function that calls the function of the MethodInfo it receives as its last argument, passing it along.
+    fn method_ptr() -> *mut u8;
+
+    /// Invoker that calls [`method_ptr`](Self::method_ptr) with the arguments it receives in an array, like the ones generated by Il2Cpp do.
+    fn invoker() -> *const u8;
+}
+
+// Holds the invoker of the methods taking the arguments `Args` and returning `Ret`.
+struct Invoker<Ret, Args>(std::marker::PhantomData<(Ret, Args)>);
+
+macro_rules! impl_method_function {
+    ($arity:literal $(, $arg:ident)*) => {
+        impl<Ret, $($arg),*> Invoker<Ret, ($($arg,)*)> {
+            #[allow(unused_mut)]
+            extern "C-unwind" fn invoke(method_ptr: *mut u8, method: &'static MethodInfo, this: *const u8, args: *const *const u8) -> *mut u8 {
+                let function = unsafe { std::mem::transmute::<*mut u8, extern "C-unwind" fn($($arg,)* OptionalMethod) -> Ret>(method_ptr) };
+                let mut args = InvokerArgs::new(method, this, args);
+
+                let result = function($(unsafe { args.next::<$arg>() },)* Some(method));
+
+                unsafe { args.box_return(result) }
+            }
+        }
+
+        impl<Func, Ret, $($arg),*> MethodFunction<($($arg,)*)> for Func
+        where
+            Func: Fn($($arg),*) -> Ret + Send + Sync + 'static,
+        {
+            const ARITY: usize = $arity;
+            const ARGUMENT_SIZES: &'static [usize] = &[$(std::mem::size_of::<$arg>()),*];
+            const RETURN_SIZE: usize = std::mem::size_of::<Ret>();
+
+            fn method_ptr() -> *mut u8 {
+                #[allow(non_snake_case)]
+                extern "C-unwind" fn trampoline<Func, Ret, $($arg),*>($($arg: $arg,)* method: OptionalMethod) -> Ret
+                where
+                    Func: Fn($($arg),*) -> Ret + 'static,
+                {
+                    let Some(function) = function_for(method) else { missing_function() };
+                    let Some(function) = function.downcast_ref::<Func>() else { missing_function() };
+
+                    call_or_abort(|| function($($arg),*))
+                }
+
+                trampoline::<Func, Ret, $($arg),*> as *mut u8
+            }
+
+            fn invoker() -> *const u8 {
+                Invoker::<Ret, ($($arg,)*)>::invoke as *const u8
+            }
+        }
+
+        impl<Func, Ret, $($arg),*> NativeFunction<($($arg,)*)> for Func
+        where
+            Func: Fn($($arg,)* OptionalMethod) -> Ret + Send + Sync + 'static,
+        {
+            const ARITY: usize = $arity;
+            const ARGUMENT_SIZES: &'static [usize] = &[$(std::mem::size_of::<$arg>()),*];
+            const RETURN_SIZE: usize = std::mem::size_of::<Ret>();
+
+            fn method_ptr() -> *mut u8 {
+                #[allow(non_snake_case)]
+                extern "C-unwind" fn trampoline<Func, Ret, $($arg),*>($($arg: $arg,)* method: OptionalMethod) -> Ret
+                where
+                    Func: Fn($($arg,)* OptionalMethod) -> Ret + 'static,
+                {
+                    let Some(function) = function_for(method) else { missing_function() };
+                    let Some(function) = function.downcast_ref::<Func>() else { missing_function() };
+
+                    call_or_abort(|| function($($arg,)* method))
+                }
+
+                trampoline::<Func, Ret, $($arg),*> as *mut u8
+            }
+
+            fn invoker() -> *const u8 {
+                Invoker::<Ret, ($($arg,)*)>::invoke as *const u8
+            }
+        }
+    };
+}
+
+impl_method_function!(0);
+impl_method_function!(1, A);
+impl_method_function!(2, A, B);
+impl_method_function!(3, A, B, C);
+impl_method_function!(4, A, B, C, D);
+impl_method_function!(5, A, B, C, D, E);
+impl_method_function!(6, A, B, C, D, E, F);
+impl_method_function!(7, A, B, C, D, E, F, G);
+impl_method_function!(8, A, B, C, D, E, F, G, H);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        il2cpp::{class::Il2CppClassData, method::Il2CppValue},
+        mock::{ClassBuilder, MethodBuilder, Mock},
+    };
+
+    #[crate::class("Tests.Injection", "Counter", inject)]
+    struct Counter {
+        count: i32,
+    }
+
+    #[test]
+    fn inject_class() {
+        let mock = Mock::install();
+        let int = mock.class("System", "Int32").unwrap();
+
+        let base = ClassBuilder::new(mock.image("InjectionTests"), "Tests.Injection", "Base")
+            .virtual_method(MethodBuilder::new("Step").parameter("amount", int).function(|_this: &Il2CppObject<()>, _amount: i32| ()))
+            .build();
+
+        let class = Counter::injector()
+            .parent(base)
+            .method(InjectedMethod::new("Step", |this: &mut Counter, amount: i32| this.count += amount).parameter("amount", int))
+            .method(InjectedMethod::new("Get", |this: &Counter| this.count).returns(int))
+            .inject()
+            .unwrap();
+
+        assert!(is_injected(class));
+        assert!(std::ptr::eq(Counter::class(), class));
+        assert!(std::ptr::eq(Il2CppClass::from_il2cpptype(&class._1.byval_arg).unwrap(), class));
+        assert!(class.is_subclass_of(base));
+
+        let counter = Counter::instantiate().unwrap();
+        assert!(std::ptr::eq(counter.get_class(), class));
+        let this = unsafe { &*(counter as *const Counter as *const Il2CppObject<()>) };
+
+        // The override is called through the method of the parent
+        base.get_method_from_name("Step", 1).unwrap().invoke(Some(this), &[&3i32]).unwrap();
+        assert_eq!(counter.count, 3);
+
+        let get = class.get_method_from_name("Get", 0).unwrap();
+        assert!(matches!(get.invoke(Some(this), &[]), Ok(Il2CppValue::I4(3))));
+
+        // Called without its MethodInfo, the method cannot find its closure and throws
+        let get_fn = unsafe { std::mem::transmute::<*mut u8, extern "C-unwind" fn(&Counter, OptionalMethod) -> i32>(get.method_ptr) };
+        let error = std::panic::catch_unwind(|| get_fn(counter, None)).unwrap_err();
+        assert!(error.downcast_ref::<String>().is_some_and(|message| message.contains("InvalidOperationException")));
+    }
+
+    #[test]
+    fn reject_mismatched_functions() {
+        let mock = Mock::install();
+        let int = mock.class("System", "Int32").unwrap();
+
+        let inject = |method: InjectedMethod| ClassInjector::new("Tests.Injection", "Mismatched").method(method).inject();
+
+        let missing = InjectedMethod::new("Add", |_this: &Il2CppObject<()>, _amount: i32| ());
+        assert!(matches!(inject(missing), Err(Il2CppError::InvalidInjection(_, reason)) if reason.contains("every argument")));
+
+        let wide = InjectedMethod::new("Add", |_this: &Il2CppObject<()>, _amount: i64| ()).parameter("amount", int);
+        assert!(matches!(inject(wide), Err(Il2CppError::InvalidInjection(_, reason)) if reason.contains("size of its parameter")));
+
+        let by_value = InjectedMethod::new("Add", |_this: Counter| ());
+        assert!(matches!(inject(by_value), Err(Il2CppError::InvalidInjection(_, reason)) if reason.contains("size of its parameter")));
+
+        let returns = InjectedMethod::new("Get", |_this: &Il2CppObject<()>| 0u8).returns(int);
+        assert!(matches!(inject(returns), Err(Il2CppError::InvalidInjection(_, reason)) if reason.contains("return type")));
+
+        let void = InjectedMethod::new("Get", |_this: &Il2CppObject<()>| 0i32);
+        assert!(matches!(inject(void), Err(Il2CppError::InvalidInjection(_, reason)) if reason.contains("return type")));
+        assert!(find("Tests.Injection", "Mismatched").is_none());
+    }
+}
@@ -0,0 +1,222 @@
+//! Checking the layout of `#[unity::class]` structures against the one of the classes in the running game.
+//!
+//! Structures mirroring a class are written by hand and silently go out of date when an update adds, removes or resizes a field.
+//! Every structure declared with `#[unity::class]` records the name, offset and size of its fields, which [`Il2CppClassData::verify_layout`] compares with the fields the runtime knows about.
+//!
+//! Fields are matched by name, ignoring case, underscores and the `m_`, `k_` and `s_` prefixes, so `default_shader` matches `m_DefaultShader`.
+//! Byte arrays are considered padding and are not checked. A field named differently from the class can be given its actual name with `#[field_name("...")]`.
+//!
+//! Example:
+//!
+//! ```ignore
+//! #[unity::class("UnityEngine", "ScriptableObject")]
+//! pub struct ScriptableObject {
+//!     #[field_name("m_CachedPtr")]
+//!     pub cached_ptr: *const u8,
+//! }
+//!
+//! // At the start of the plugin, before touching any instance
+//! ScriptableObject::verify_layout()?;
+//! ```
+
+use std::fmt;
+
+use unity_core::types::Il2CppTypeEnum;
+
+use super::{class::{Il2CppClass, Il2CppClassData}, object::OBJECT_HEADER_SIZE, Il2CppType};
+use crate::{Il2CppError, Il2CppResult};
+
+/// Layout of the Rust structure of a class, as recorded by `#[unity::class]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassLayout {
+    /// Size of an instance, including the object header.
+    pub size: usize,
+    pub fields: Vec<FieldLayout>,
+}
+
+/// A field of the Rust structure of a class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldLayout {
+    /// Name of the field in the class, which is the name of the Rust field unless renamed with `#[field_name]`.
+    pub name: &'static str,
+    /// Offset from the start of the object, including the object header.
+    pub offset: usize,
+    pub size: usize,
+    /// Byte arrays standing for fields that are not mirrored.
+    pub padding: bool,
+}
+
+/// A difference between the Rust structure of a class and the class in the game.
+///
+/// `expected` values come from the Rust structure, `actual` values from the runtime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutMismatch {
+    /// No field of the class or its parents matches the name of the Rust field.
+    MissingField { field: &'static str },
+    Offset { field: &'static str, expected: usize, actual: usize },
+    Size { field: &'static str, expected: usize, actual: usize },
+    /// The Rust structure is larger than an instance of the class.
+    InstanceSize { expected: usize, actual: usize },
+}
+
+impl fmt::Display for LayoutMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingField { field } => write!(f, "`{}` is not a field of the class", field),
+            Self::Offset { field, expected, actual } => write!(f, "`{}` is at {:#x} instead of {:#x}", field, actual, expected),
+            Self::Size { field, expected, actual } => write!(f, "`{}` is {:#x} bytes instead of {:#x}", field, actual, expected),
+            Self::InstanceSize { expected, actual } => write!(f, "instances are {:#x} bytes instead of at least {:#x}", actual, expected),
+        }
+    }
+}
+
+pub(crate) fn format_mismatches(mismatches: &[LayoutMismatch]) -> String {
+    mismatches.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+}
+
+/// Compare a layout with the fields of a class and its parents, returning every difference found.
+pub fn check(class: &Il2CppClass, layout: &ClassLayout) -> Vec<LayoutMismatch> {
+    let mut mismatches = Vec::new();
+
+    let instance_size = class._2.instance_size as usize;
+
+    if layout.size > instance_size {
+        mismatches.push(LayoutMismatch::InstanceSize { expected: layout.size, actual: instance_size });
+    }
+
+    let live_fields = class
+        .get_fields()
+        .into_iter()
+        .filter(|field| !field.is_static())
+        .map(|field| (normalize(&field.get_name()), field))
+        .collect::<Vec<_>>();
+
+    for field in layout.fields.iter().filter(|field| !field.padding && field.size != 0) {
+        let name = normalize(field.name);
+
+        let Some((_, live)) = live_fields.iter().find(|(live_name, _)| *live_name == name) else {
+            mismatches.push(LayoutMismatch::MissingField { field: field.name });
+            continue;
+        };
+
+        let offset = live.get_offset() as usize;
+
+        if offset != field.offset {
+            mismatches.push(LayoutMismatch::Offset { field: field.name, expected: field.offset, actual: offset });
+        }
+
+        match type_size(live.get_type()) {
+            Some(size) if size != field.size => mismatches.push(LayoutMismatch::Size { field: field.name, expected: field.size, actual: size }),
+            _ => (),
+        }
+    }
+
+    mismatches
+}
+
+/// Check the layout of a `#[unity::class]` structure, failing with every difference found.
+pub fn verify<T: Il2CppClassData>() -> Il2CppResult<()> {
+    let Some(layout) = T::layout() else { return Ok(()) };
+
+    let class = T::class();
+    let mismatches = check(class, &layout);
+
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(Il2CppError::LayoutMismatch(format!("{}.{}", class.get_namespace(), class.get_name()), mismatches))
+    }
+}
+
+/// Size a field of this type takes in an object.
+fn type_size(ty: &Il2CppType) -> Option<usize> {
+    match ty.get_type_enum()? {
+        Il2CppTypeEnum::ValueType | Il2CppTypeEnum::GenericInst => {
+            let class = Il2CppClass::from_il2cpptype(ty).ok()?;
+
+            match class.is_valuetype() {
+                true => (class._2.instance_size as usize).checked_sub(OBJECT_HEADER_SIZE),
+                false => Some(std::mem::size_of::<usize>()),
+            }
+        },
+        type_enum => type_enum.size(),
+    }
+}
+
+/// Reduce a field name to what Rust and C# names have in common, so `m_DefaultShader`, `<DefaultShader>k__BackingField` and `default_shader` are equal.
+fn normalize(name: &str) -> String {
+    let name = name.strip_prefix("r#").unwrap_or(name);
+
+    let name = name
+        .strip_prefix('<')
+        .and_then(|name| name.strip_suffix(">k__BackingField"))
+        .unwrap_or(name);
+
+    let name = ["m_", "k_", "s_", "_"]
+        .iter()
+        .find_map(|prefix| name.strip_prefix(prefix))
+        .unwrap_or(name);
+
+    name.chars().filter(char::is_ascii_alphanumeric).map(|c| c.to_ascii_lowercase()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mock::{ClassBuilder, Mock}, system::Il2CppString};
+
+    #[crate::class("Tests.Layout", "Unit")]
+    struct Unit {
+        hp: i32,
+        name: &'static Il2CppString,
+        speed: f32,
+        padding: [u8; 4],
+    }
+
+    #[test]
+    fn mismatches() {
+        let mock = Mock::install();
+
+        ClassBuilder::new(mock.image("LayoutTests"), "Tests.Layout", "Unit")
+            .fields::<UnitFields>()
+            .field("m_Hp", mock.class("System", "Int32").unwrap(), 0x10)
+            .field("<Name>k__BackingField", Il2CppString::class(), 0x18)
+            .field("speed", mock.class("System", "Double").unwrap(), 0x28)
+            .build();
+
+        assert_eq!(
+            check(Unit::class(), &Unit::layout().unwrap()),
+            [
+                LayoutMismatch::Offset { field: "speed", expected: 0x20, actual: 0x28 },
+                LayoutMismatch::Size { field: "speed", expected: 4, actual: 8 },
+            ]
+        );
+
+        let err = Unit::verify_layout().unwrap_err();
+
+        assert_eq!(err.to_string(), "the layout of `Tests.Layout.Unit` does not match the game: `speed` is at 0x28 instead of 0x20, `speed` is 0x8 bytes instead of 0x4");
+    }
+
+    #[test]
+    fn missing_fields() {
+        let mock = Mock::install();
+
+        let class = ClassBuilder::new(mock.image("LayoutTests"), "Tests.Layout", "Renamed")
+            .instance_size(0x14)
+            .field("m_Hp", mock.class("System", "Int32").unwrap(), 0x10)
+            .build();
+
+        let layout = ClassLayout {
+            size: 0x18,
+            fields: vec![
+                FieldLayout { name: "hp", offset: 0x10, size: 4, padding: false },
+                FieldLayout { name: "mp", offset: 0x14, size: 4, padding: false },
+            ],
+        };
+
+        assert_eq!(
+            check(class, &layout),
+            [LayoutMismatch::InstanceSize { expected: 0x18, actual: 0x14 }, LayoutMismatch::MissingField { field: "mp" }]
+        );
+    }
+}
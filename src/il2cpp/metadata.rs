@@ -0,0 +1,45 @@
+//! Reading `global-metadata.dat`, to inspect a game offline or cross-check the structures of the runtime.
+//!
+//! The parser itself lives in `unity-core` so host-side tools can use it as well.
+//!
+//! Example:
+//!
+//! ```no_run
+//! # use unity::{il2cpp::metadata::Metadata, prelude::*};
+//! # struct Unit;
+//! # impl Unit { fn class() -> &'static Il2CppClass { unimplemented!() } }
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let metadata = Metadata::from_file("sd:/global-metadata.dat")?;
+//! let definition = Unit::class().get_definition(&metadata).unwrap();
+//!
+//! assert_eq!(definition.method_count, Unit::class()._2.method_count);
+//! # Ok(())
+//! # }
+//! ```
+
+pub use unity_core::metadata::*;
+
+use super::{assembly::Il2CppAssembly, class::Il2CppClass, method::MethodInfo};
+
+impl Il2CppClass {
+    /// Find the type definition of the class, using its image and token.
+    pub fn get_definition<'a>(&self, metadata: &'a Metadata) -> Option<&'a TypeDefinition> {
+        let image = metadata.find_image(self._1.image.get_name())?;
+        metadata.type_by_token(image, self._2.token)
+    }
+}
+
+impl MethodInfo {
+    /// Find the method definition of the method, using its class and token.
+    pub fn get_definition<'a>(&self, metadata: &'a Metadata) -> Option<&'a MethodDefinition> {
+        let ty = self.class?.get_definition(metadata)?;
+        metadata.method_by_token(ty, self.token)
+    }
+}
+
+impl Il2CppAssembly {
+    pub fn get_definition<'a>(&self, metadata: &'a Metadata) -> Option<&'a AssemblyDefinition> {
+        let image = metadata.find_image(self.image.get_name())?;
+        metadata.assembly_by_token(image, self.token)
+    }
+}
@@ -1,6 +1,23 @@
 use std::ffi::CStr;
 
-use super::{class::Il2CppClass, Il2CppType};
+use unity_core::types::Il2CppTypeEnum;
+
+use super::{
+    api,
+    class::Il2CppClass,
+    field::{self, FieldValue},
+    object::{Il2CppObject, OBJECT_HEADER_SIZE},
+    Il2CppType,
+};
+use crate::{Il2CppError, Il2CppResult};
+
+pub(crate) const METHOD_ATTRIBUTE_PUBLIC: u16 = 0x6;
+pub(crate) const METHOD_ATTRIBUTE_STATIC: u16 = 0x10;
+pub(crate) const METHOD_ATTRIBUTE_VIRTUAL: u16 = 0x40;
+pub(crate) const METHOD_ATTRIBUTE_HIDE_BY_SIG: u16 = 0x80;
+
+// Slot of the methods that are not in the vtable.
+pub(crate) const INVALID_SLOT: u16 = 0xffff;
 
 /// A type alias for `Option<&MethodInfo>`. Useful when hooking Il2Cpp methods.
 pub type OptionalMethod = Option<&'static MethodInfo>;
@@ -84,7 +101,270 @@ impl MethodInfo {
 
     /// Get the parameters expected by the method.
     pub fn get_parameters(&self) -> &[ParameterInfo] {
-        unsafe { std::slice::from_raw_parts(self.parameters, self.parameters_count as _) }
+        match self.parameters.is_null() {
+            true => &[],
+            false => unsafe { std::slice::from_raw_parts(self.parameters, self.parameters_count as _) },
+        }
+    }
+
+    /// Get the full names of the types of the parameters, such as `(System.String,System.Int32&)`.
+    pub fn get_signature(&self) -> String {
+        let parameters = self
+            .get_parameters()
+            .iter()
+            .map(|parameter| parameter.parameter_type.to_string())
+            .collect::<Vec<_>>();
+
+        format!("({})", parameters.join(","))
+    }
+
+    pub fn get_return_type(&self) -> Option<&'static Il2CppType> {
+        unsafe { (self.return_type as *const Il2CppType).as_ref() }
+    }
+
+    pub fn is_static(&self) -> bool {
+        self.flags & METHOD_ATTRIBUTE_STATIC != 0
+    }
+
+    /// Call the method through its invoker, checking the arguments against its parameters.
+    ///
+    /// A managed exception thrown by the method is returned as [`Il2CppError::Exception`].  
+    /// Fails with [`Il2CppError::UnresolvedSymbol`] if the runtime function used to catch it cannot be found.
+    ///
+    /// `this` must be `None` for static methods. For instance methods, the override of its class is called if the method is virtual, and structs are given as their boxed object.  
+    /// Value types are boxed when given to a parameter expecting a reference, such as `object`, and the value returned is unboxed.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use unity::prelude::*;
+    /// # fn main() -> Il2CppResult<()> {
+    /// let method = Il2CppClass::from_name("System", "Math")?.get_method_from_name("Max", 2)?;
+    ///
+    /// if let Il2CppValue::I4(max) = method.invoke(None, &[&3i32, &5i32])? {
+    ///     println!("{}", max);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn invoke(&self, this: Option<&Il2CppObject<()>>, args: &[&dyn Il2CppArg]) -> Il2CppResult<Il2CppValue> {
+        let parameters = self.get_parameters();
+
+        if args.len() != parameters.len() {
+            return Err(Il2CppError::ArgumentCountMismatch(self.to_string(), parameters.len(), args.len()));
+        }
+
+        let args = args
+            .iter()
+            .zip(parameters)
+            .enumerate()
+            .map(|(index, (arg, parameter))| {
+                arg.to_arg(parameter.parameter_type)
+                    .ok_or_else(|| Il2CppError::ArgumentTypeMismatch(self.to_string(), index, arg.type_name()))
+            })
+            .collect::<Il2CppResult<Vec<_>>>()?;
+
+        let (method, this) = self.resolve(this)?;
+
+        let mut exception = None;
+        let result = unsafe { api::runtime_invoke(method, this, args.as_ptr(), &mut exception) }?;
+
+        if let Some(exception) = exception {
+            return Err(exception.to_error());
+        }
+
+        Ok(Il2CppValue::unbox(method.get_return_type(), result))
+    }
+
+    /// Call the invoker of the method directly, which lets managed exceptions unwind through the caller.
+    ///
+    /// The invoker returns value types boxed.
+    ///
+    /// # Safety
+    ///
+    /// See [`Il2CppRuntime::runtime_invoke`](super::runtime::Il2CppRuntime::runtime_invoke).
+    pub(crate) unsafe fn call_invoker(&self, this: *const u8, args: *const *const u8) -> Option<&'static mut Il2CppObject<()>> {
+        let invoker = std::mem::transmute::<*const u8, extern "C" fn(*mut u8, &MethodInfo, *const u8, *const *const u8) -> Option<&'static mut Il2CppObject<()>>>(
+            self.invoker_method,
+        );
+
+        invoker(self.method_ptr, self, this, args)
+    }
+
+    /// Find the implementation to call and the `this` to give it, which is the override of the class of `this` for virtual methods.
+    fn resolve<'a>(&'a self, this: Option<&'a Il2CppObject<()>>) -> Il2CppResult<(&'a MethodInfo, *const u8)> {
+        let resolved = match this {
+            Some(_) if self.is_static() => Err("it is static"),
+            None if self.is_static() => Ok((self, std::ptr::null())),
+            None => Err("it is not static"),
+            Some(this) if !self.class.is_some_and(|class| this.get_class().derives_from(class)) => Err("the object is not an instance of its class"),
+            Some(this) => {
+                let method = this.get_class().resolve_virtual(self).map_or(self, |entry| entry.method_info);
+                let this = this as *const Il2CppObject<()> as *const u8;
+
+                // Instance methods of structs take a pointer to the value rather than to the boxed object
+                match method.class.is_some_and(|class| class.is_valuetype()) {
+                    true => Ok((method, unsafe { this.add(OBJECT_HEADER_SIZE) })),
+                    false => Ok((method, this)),
+                }
+            },
+        };
+
+        let reason = match resolved {
+            Ok((method, _)) if method.method_ptr.is_null() => "it has no implementation",
+            Ok((method, _)) if method.invoker_method.is_null() => "it has no invoker",
+            Ok(resolved) => return Ok(resolved),
+            Err(reason) => reason,
+        };
+
+        Err(Il2CppError::InvalidMethodCall(self.to_string(), reason))
+    }
+}
+
+/// Formats the method as its class, name and parameter types, such as `UnityEngine.AssetBundle::LoadFromMemoryAsync_Internal(System.Byte[],System.UInt32)`.
+///
+/// This is the name expected by [`method_from_name`](super::method_from_name) for internal calls.
+impl std::fmt::Display for MethodInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(class) = self.class {
+            write!(f, "{}::", class)?;
+        }
+
+        write!(f, "{}{}", self.get_name().unwrap_or_default(), self.get_signature())
+    }
+}
+
+/// A value that can be given as an argument to [`MethodInfo::invoke`].
+///
+/// Implemented for every [`FieldValue`], which covers the primitive types and references to classes.
+pub trait Il2CppArg {
+    /// The pointer to give the invoker for a parameter of this type, or `None` if the value cannot be given for it.
+    ///
+    /// References are given as the object itself, while value types are given as a pointer to the value.
+    fn to_arg(&self, ty: &Il2CppType) -> Option<*const u8>;
+
+    /// The name of the Rust type of the argument, for error messages.
+    fn type_name(&self) -> &'static str;
+}
+
+impl<V: FieldValue> Il2CppArg for V {
+    fn to_arg(&self, ty: &Il2CppType) -> Option<*const u8> {
+        if self.can_write(ty) {
+            return match V::IS_REFERENCE {
+                true => Some(unsafe { std::mem::transmute_copy(self) }),
+                false => Some(self as *const V as *const u8),
+            };
+        }
+
+        // A value type given where a reference is expected, such as `object` or an interface
+        let class = V::boxed_class().filter(|class| !V::IS_REFERENCE && field::reference_class(ty).is_some_and(|param| param.is_assignable_from(class)))?;
+
+        unsafe { api::value_box(class, self as *const V as *const u8) }.map(|object| object as *const Il2CppObject<()> as *const u8)
+    }
+
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<V>()
+    }
+}
+
+/// The value returned by [`MethodInfo::invoke`], unboxed according to the return type of the method.
+///
+/// Enums are returned as their underlying type.
+pub enum Il2CppValue {
+    Void,
+    Boolean(bool),
+    Char(u16),
+    I1(i8),
+    U1(u8),
+    I2(i16),
+    U2(u16),
+    I4(i32),
+    U4(u32),
+    I8(i64),
+    U8(u64),
+    R4(f32),
+    R8(f64),
+    I(isize),
+    U(usize),
+    /// A reference to a managed object, `None` if the method returned `null`.
+    Object(Option<&'static mut Il2CppObject<()>>),
+    /// A struct, still boxed as the runtime returned it.
+    ValueType(&'static mut Il2CppObject<()>),
+}
+
+impl std::fmt::Debug for Il2CppValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let class_name = |object: &Il2CppObject<()>| format!("{}.{}", object.get_class().get_namespace(), object.get_class().get_name());
+
+        match self {
+            Self::Void => write!(f, "Void"),
+            Self::Boolean(value) => f.debug_tuple("Boolean").field(value).finish(),
+            Self::Char(value) => f.debug_tuple("Char").field(value).finish(),
+            Self::I1(value) => f.debug_tuple("I1").field(value).finish(),
+            Self::U1(value) => f.debug_tuple("U1").field(value).finish(),
+            Self::I2(value) => f.debug_tuple("I2").field(value).finish(),
+            Self::U2(value) => f.debug_tuple("U2").field(value).finish(),
+            Self::I4(value) => f.debug_tuple("I4").field(value).finish(),
+            Self::U4(value) => f.debug_tuple("U4").field(value).finish(),
+            Self::I8(value) => f.debug_tuple("I8").field(value).finish(),
+            Self::U8(value) => f.debug_tuple("U8").field(value).finish(),
+            Self::R4(value) => f.debug_tuple("R4").field(value).finish(),
+            Self::R8(value) => f.debug_tuple("R8").field(value).finish(),
+            Self::I(value) => f.debug_tuple("I").field(value).finish(),
+            Self::U(value) => f.debug_tuple("U").field(value).finish(),
+            // Objects are shown as the name of their class
+            Self::Object(object) => f.debug_tuple("Object").field(&object.as_deref().map(class_name)).finish(),
+            Self::ValueType(object) => f.debug_tuple("ValueType").field(&class_name(object)).finish(),
+        }
+    }
+}
+
+impl Il2CppValue {
+    pub fn is_void(&self) -> bool {
+        matches!(self, Self::Void)
+    }
+
+    /// The object returned by the method, or the boxed struct, if any.
+    pub fn into_object(self) -> Option<&'static mut Il2CppObject<()>> {
+        match self {
+            Self::Object(object) => object,
+            Self::ValueType(object) => Some(object),
+            _ => None,
+        }
+    }
+
+    fn unbox(ty: Option<&Il2CppType>, object: Option<&'static mut Il2CppObject<()>>) -> Self {
+        let type_enum = ty.and_then(field::underlying_type_enum);
+
+        let Some(object) = object else {
+            return match type_enum {
+                None | Some(Il2CppTypeEnum::Void) => Self::Void,
+                Some(_) => Self::Object(None),
+            };
+        };
+
+        let value = unsafe { (object as *const Il2CppObject<()> as *const u8).add(OBJECT_HEADER_SIZE) };
+
+        unsafe {
+            match type_enum {
+                Some(Il2CppTypeEnum::Boolean) => Self::Boolean(*value != 0),
+                Some(Il2CppTypeEnum::Char) => Self::Char(std::ptr::read_unaligned(value as *const u16)),
+                Some(Il2CppTypeEnum::I1) => Self::I1(std::ptr::read_unaligned(value as *const i8)),
+                Some(Il2CppTypeEnum::U1) => Self::U1(*value),
+                Some(Il2CppTypeEnum::I2) => Self::I2(std::ptr::read_unaligned(value as *const i16)),
+                Some(Il2CppTypeEnum::U2) => Self::U2(std::ptr::read_unaligned(value as *const u16)),
+                Some(Il2CppTypeEnum::I4) => Self::I4(std::ptr::read_unaligned(value as *const i32)),
+                Some(Il2CppTypeEnum::U4) => Self::U4(std::ptr::read_unaligned(value as *const u32)),
+                Some(Il2CppTypeEnum::I8) => Self::I8(std::ptr::read_unaligned(value as *const i64)),
+                Some(Il2CppTypeEnum::U8) => Self::U8(std::ptr::read_unaligned(value as *const u64)),
+                Some(Il2CppTypeEnum::R4) => Self::R4(std::ptr::read_unaligned(value as *const f32)),
+                Some(Il2CppTypeEnum::R8) => Self::R8(std::ptr::read_unaligned(value as *const f64)),
+                Some(Il2CppTypeEnum::I) => Self::I(std::ptr::read_unaligned(value as *const isize)),
+                Some(Il2CppTypeEnum::U) => Self::U(std::ptr::read_unaligned(value as *const usize)),
+                _ if object.get_class().is_valuetype() => Self::ValueType(object),
+                _ => Self::Object(Some(object)),
+            }
+        }
     }
 }
 
@@ -98,3 +378,103 @@ impl ParameterInfo {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{ClassBuilder, MethodBuilder, Mock};
+
+    extern "C" fn add(a: i32, b: i32, _method: &MethodInfo) -> i32 {
+        a + b
+    }
+
+    /// Invoker of `Add`, calling its `method_ptr` and boxing the result like the invokers generated by Il2Cpp do.
+    extern "C" fn add_invoker(method_ptr: *const u8, method: &MethodInfo, _this: *const u8, args: *const *const u8) -> Option<&'static mut Il2CppObject<()>> {
+        let add = unsafe { std::mem::transmute::<*const u8, extern "C" fn(i32, i32, &MethodInfo) -> i32>(method_ptr) };
+        let result = unsafe { add(*(*args as *const i32), *(*args.add(1) as *const i32), method) };
+
+        unsafe { api::value_box(Mock::get()?.class("System", "Int32")?, &result as *const i32 as *const u8) }
+    }
+
+    extern "C" fn identity(value: *mut Il2CppObject<()>, _method: &MethodInfo) -> *mut Il2CppObject<()> {
+        value
+    }
+
+    /// Invoker of `Identity`, returning the object it is given.
+    extern "C" fn identity_invoker(method_ptr: *const u8, method: &MethodInfo, _this: *const u8, args: *const *const u8) -> Option<&'static mut Il2CppObject<()>> {
+        let identity = unsafe { std::mem::transmute::<*const u8, extern "C" fn(*mut Il2CppObject<()>, &MethodInfo) -> *mut Il2CppObject<()>>(method_ptr) };
+
+        unsafe { identity(*args as *mut Il2CppObject<()>, method).as_mut() }
+    }
+
+    #[test]
+    fn invoke_arguments() {
+        let mock = Mock::install();
+        let (int, object) = (mock.class("System", "Int32").unwrap(), mock.class("System", "Object").unwrap());
+
+        let class = ClassBuilder::new(mock.image("MethodTests"), "Tests.Method", "Calculator")
+            .method(
+                MethodBuilder::new("Add")
+                    .static_method()
+                    .parameter("a", int)
+                    .parameter("b", int)
+                    .returns(int)
+                    .pointer(add as *mut u8)
+                    .invoker(add_invoker as *const u8),
+            )
+            .method(
+                MethodBuilder::new("Identity")
+                    .static_method()
+                    .parameter("value", object)
+                    .returns(object)
+                    .pointer(identity as *mut u8)
+                    .invoker(identity_invoker as *const u8),
+            )
+            .build();
+
+        let add = class.get_method_from_name("Add", 2).unwrap();
+
+        assert!(matches!(add.invoke(None, &[&1i32, &2i32]), Ok(Il2CppValue::I4(3))));
+        assert!(matches!(add.invoke(None, &[&1i32]), Err(Il2CppError::ArgumentCountMismatch(_, 2, 1))));
+        assert!(matches!(add.invoke(None, &[&1i32, &2i32, &3i32]), Err(Il2CppError::ArgumentCountMismatch(_, 2, 3))));
+        assert!(matches!(add.invoke(None, &[&1i32, &2.5f32]), Err(Il2CppError::ArgumentTypeMismatch(_, 1, "f32"))));
+        assert!(matches!(add.invoke(None, &[&1i64, &2i32]), Err(Il2CppError::ArgumentTypeMismatch(_, 0, "i64"))));
+
+        let this = unsafe { api::object_new::<Il2CppObject<()>>(class) }.unwrap();
+        assert!(matches!(add.invoke(Some(this), &[&1i32, &2i32]), Err(Il2CppError::InvalidMethodCall(_, "it is static"))));
+
+        // Value types given for a reference are boxed
+        let identity = class.get_method_from_name("Identity", 1).unwrap();
+
+        let boxed = identity.invoke(None, &[&7i32]).unwrap().into_object().unwrap();
+        assert!(std::ptr::eq(boxed.get_class(), int));
+    }
+
+    #[test]
+    fn display_names() {
+        let mock = Mock::install();
+        let image = mock.image("UnityEngine.AssetBundleModule");
+        let (bytes, uint) = (mock.class("System", "Byte[]").unwrap(), mock.class("System", "UInt32").unwrap());
+        let ulong = mock.class("System", "UInt64").unwrap();
+
+        let bundle = ClassBuilder::new(image, "UnityEngine", "AssetBundle")
+            .method(MethodBuilder::new("LoadFromMemoryAsync_Internal").static_method().parameter("binary", bytes).parameter("crc", uint))
+            .method(MethodBuilder::new("LoadFromMemoryAsync_Internal").static_method().parameter("binary", bytes).parameter("crc", ulong))
+            .build();
+
+        let overloads = bundle.get_methods().iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            overloads,
+            [
+                "UnityEngine.AssetBundle::LoadFromMemoryAsync_Internal(System.Byte[],System.UInt32)",
+                "UnityEngine.AssetBundle::LoadFromMemoryAsync_Internal(System.Byte[],System.UInt64)",
+            ]
+        );
+
+        // Nested classes are separated from their declaring class by a slash
+        let unit = ClassBuilder::new(image, "App", "Unit").build();
+        let skill = ClassBuilder::new(image, "", "Skill").nested_in(unit).method(MethodBuilder::new("Use").parameter("target", unit)).build();
+        assert_eq!(skill.to_string(), "App.Unit/Skill");
+        assert_eq!(skill.get_methods()[0].to_string(), "App.Unit/Skill::Use(App.Unit)");
+    }
+}
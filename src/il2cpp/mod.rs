@@ -1,7 +1,5 @@
 #![allow(dead_code)]
 
-use std::sync::LazyLock;
-
 pub mod api;
 pub mod assembly;
 use assembly::*;
@@ -11,6 +9,16 @@ pub mod object;
 use object::*;
 pub mod method;
 use method::*;
+pub mod exception;
+pub mod field;
+pub mod gchandle;
+pub mod injection;
+pub mod layout;
+pub mod metadata;
+pub mod property;
+pub mod runtime;
+
+pub use unity_core::types::Il2CppTypeEnum;
 
 use crate::{Il2CppResult, Il2CppError};
 mod ffi;
@@ -18,37 +26,219 @@ mod ffi;
 pub fn method_from_name(name: impl AsRef<str>) -> *const u8 {
     let name = std::ffi::CString::new(name.as_ref()).unwrap();
 
-    unsafe { method_name(name.as_ptr() as _) }
+    unsafe { api::method_name(name.as_ptr() as _) }
 }
-// ddlc offset
-#[skyline::from_offset(0x1a7fa0)]
-fn method_name(name: *const u8) -> *const u8;
 
 #[repr(C)]
 pub struct Il2CppDomain;
 
 #[repr(C)]
 pub union Il2CppTypeData {
-    data: *const u8,
+    pub(crate) data: *const u8,
     class_index: i32,
     ty: &'static Il2CppType,
-    array: *const u8, // &'static Il2CppArrayType
+    array: &'static Il2CppArrayType,
     generic_parameter_index: i32,
     generic_class: &'static Il2CppGenericClass,
 }
 
+// Layout of the bitfield following the data of an Il2CppType, before 2021.2 took a bit of num_mods for valuetype
+const TYPE_ATTRS_MASK: u32 = 0xffff;
+const TYPE_ENUM_SHIFT: u32 = 16;
+const TYPE_NUM_MODS_SHIFT: u32 = 24;
+const TYPE_NUM_MODS_MASK: u32 = 0x3f;
+const TYPE_BYREF: u32 = 1 << 30;
+const TYPE_PINNED: u32 = 1 << 31;
+
+/// Type representing a type as used by a field, parameter, return value or class.
+///
+/// What the data points to depends on the type enum, and is read through the accessor matching it, such as [`Il2CppType::get_element_type`] for `T[]`.
+///
+/// Example:
+///
+/// ```no_run
+/// # use unity::prelude::*;
+/// # use unity::il2cpp::Il2CppTypeEnum;
+/// # fn main() -> Il2CppResult<()> {
+/// let field = Il2CppClass::from_name("App", "Unit")?.get_field_from_name("m_Items")?;
+///
+/// if field.get_type().get_type_enum() == Some(Il2CppTypeEnum::SzArray) {
+///     let element = field.get_type().get_element_type();
+/// }
+/// # Ok(())
+/// # }
+/// ```
 #[repr(C)]
 pub struct Il2CppType {
     pub data: Il2CppTypeData,
-    bits: u32,
+    pub(crate) bits: u32,
 }
 
 impl Il2CppType {
+    /// Create a type of this enum, with `data` being what the union holds for it, such as the element type of a `SzArray`.
+    pub fn new(type_enum: Il2CppTypeEnum, data: *const u8) -> Self {
+        Self {
+            data: Il2CppTypeData { data },
+            bits: (type_enum as u32 & 0xff) << TYPE_ENUM_SHIFT,
+        }
+    }
+
+    /// The `FieldAttributes` or `ParameterAttributes` of the field or parameter the type was used for.
+    pub fn get_attributes(&self) -> u16 {
+        (self.bits & TYPE_ATTRS_MASK) as u16
+    }
+
+    pub fn set_attributes(&mut self, attributes: u16) {
+        self.bits = (self.bits & !TYPE_ATTRS_MASK) | attributes as u32;
+    }
+
+    /// The kind of type, or `None` if the runtime uses a value unknown to [`Il2CppTypeEnum`].
+    pub fn get_type_enum(&self) -> Option<Il2CppTypeEnum> {
+        Il2CppTypeEnum::from_u8((self.bits >> TYPE_ENUM_SHIFT) as u8)
+    }
+
+    /// Amount of custom modifiers, such as `volatile`, applied to the type.
+    pub fn get_num_mods(&self) -> u8 {
+        ((self.bits >> TYPE_NUM_MODS_SHIFT) & TYPE_NUM_MODS_MASK) as u8
+    }
+
+    /// Whether the type is passed by reference, like `ref` and `out` parameters.
+    pub fn is_byref(&self) -> bool {
+        self.bits & TYPE_BYREF != 0
+    }
+
+    pub fn set_byref(&mut self, byref: bool) {
+        match byref {
+            true => self.bits |= TYPE_BYREF,
+            false => self.bits &= !TYPE_BYREF,
+        }
+    }
+
+    pub fn is_pinned(&self) -> bool {
+        self.bits & TYPE_PINNED != 0
+    }
+
+    /// The index of the type definition in the metadata, for `Class` and `ValueType`.
+    pub fn get_class_index(&self) -> Option<i32> {
+        match self.get_type_enum()? {
+            Il2CppTypeEnum::Class | Il2CppTypeEnum::ValueType => Some(unsafe { self.data.class_index }),
+            _ => None,
+        }
+    }
+
+    /// The type of the elements of a `T[]`, or the type pointed to by a `T*`.
+    pub fn get_element_type(&self) -> Option<&'static Il2CppType> {
+        match self.get_type_enum()? {
+            Il2CppTypeEnum::SzArray | Il2CppTypeEnum::Ptr => unsafe { (self.data.data as *const Il2CppType).as_ref() },
+            _ => None,
+        }
+    }
+
+    /// The element type and rank of a multidimensional array, like `T[,]`.
+    pub fn get_array_type(&self) -> Option<&'static Il2CppArrayType> {
+        match self.get_type_enum()? {
+            Il2CppTypeEnum::Array => unsafe { (self.data.data as *const Il2CppArrayType).as_ref() },
+            _ => None,
+        }
+    }
+
+    /// The generic definition and type arguments of an instance of a generic class, like `List<int>`.
+    pub fn get_generic_class(&self) -> Option<&'static Il2CppGenericClass> {
+        match self.get_type_enum()? {
+            Il2CppTypeEnum::GenericInst => unsafe { (self.data.data as *const Il2CppGenericClass).as_ref() },
+            _ => None,
+        }
+    }
+
+    /// The index of the generic parameter in the metadata, for the `T` of a generic class (`Var`) or method (`MVar`).
+    pub fn get_generic_parameter_index(&self) -> Option<i32> {
+        match self.get_type_enum()? {
+            Il2CppTypeEnum::Var | Il2CppTypeEnum::MVar => Some(unsafe { self.data.generic_parameter_index }),
+            _ => None,
+        }
+    }
+
     pub fn get_object(ty: &Self) -> Il2CppResult<&'static mut Il2CppReflectionType> {
         unsafe { api::type_get_object(ty) }.ok_or(Il2CppError::FailedReflectionQuerying)
     }
 }
 
+/// Formats the type the way the runtime names it, as expected by [`method_from_name`], such as `System.Byte[]` or `System.Int32&`.
+impl std::fmt::Display for Il2CppType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(element) = self.get_element_type() {
+            let suffix = if self.get_type_enum() == Some(Il2CppTypeEnum::Ptr) { "*" } else { "[]" };
+            write!(f, "{}{}", element, suffix)?;
+        } else if let Some(array) = self.get_array_type() {
+            write!(f, "{}[{}]", array.element_type, ",".repeat(array.rank.saturating_sub(1) as usize))?;
+        } else if let Some(generic_class) = self.get_generic_class() {
+            match unsafe { generic_class.cached_class.as_ref() }.or_else(|| unsafe { api::class_from_il2cpptype(self) }.map(|class| &*class)) {
+                Some(class) => class.write_name(f)?,
+                None => f.write_str("?")?,
+            }
+
+            f.write_str("<")?;
+
+            for (index, argument) in generic_class.get_type_arguments().iter().enumerate() {
+                if index != 0 {
+                    f.write_str(",")?;
+                }
+
+                write!(f, "{}", argument)?;
+            }
+
+            f.write_str(">")?;
+        } else {
+            match unsafe { api::class_from_il2cpptype(self) } {
+                Some(class) => class.write_name(f)?,
+                // Generic parameters without a class are written like in IL
+                None => match (self.get_type_enum(), self.get_generic_parameter_index()) {
+                    (Some(Il2CppTypeEnum::Var), Some(index)) => write!(f, "!{}", index)?,
+                    (Some(Il2CppTypeEnum::MVar), Some(index)) => write!(f, "!!{}", index)?,
+                    (type_enum, _) => write!(f, "{:?}", type_enum)?,
+                },
+            }
+        }
+
+        if self.is_byref() {
+            f.write_str("&")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The type of a multidimensional array, as held by an [`Il2CppType`] of `Array`.
+#[repr(C)]
+pub struct Il2CppArrayType {
+    pub element_type: &'static Il2CppType,
+    pub rank: u8,
+    pub num_sizes: u8,
+    pub num_lower_bounds: u8,
+    pub sizes: *const i32,
+    pub lower_bounds: *const i32,
+}
+
+/// Throw a managed exception, such as one made with [`Il2CppException::new_argument`](exception::Il2CppException::new_argument).
+///
+/// This unwinds through the runtime like a `throw` in C# does, up to the closest `catch` in managed code, so it should only be called from hooks and functions called by the game.  
+/// Rust values still alive in the frames being unwound are not dropped.
+///
+/// Only hooks declared `extern "C-unwind"` may call it, directly or not: unwinding out of an `extern "C"` function aborts the process.  
+/// [`vhook`](crate::vhook) keeps the ABI given to the function, while the other hooks are made `extern "C"`.
+///
+/// # Panics
+///
+/// If the runtime function cannot be found, as there is nothing else to do in its place. Check it beforehand with `api::resolve(Symbol::RaiseException)` to avoid this.
+pub fn raise(exception: &exception::Il2CppException) -> ! {
+    api::raise_exception(exception)
+}
+
+/// Leak a copy of the string with a null terminator, for the names of the classes and methods made by the crate.
+pub(crate) fn leak_str(string: impl AsRef<str>) -> *const u8 {
+    std::ffi::CString::new(string.as_ref()).unwrap().into_raw() as *const u8
+}
+
 pub fn instantiate_class<T: 'static>(class: &Il2CppClass) -> Il2CppResult<&'static mut T> {
     unsafe { api::object_new(class) }.ok_or(Il2CppError::FailedInstantiation(class.get_name()))
 }
@@ -58,13 +248,72 @@ pub fn instantiate_class_by_name<T: 'static>(namespace: impl AsRef<str>, name: i
     instantiate_class(class)
 }
 
+#[cfg(target_os = "horizon")]
 pub fn il2cpp_init_scan() -> usize {
-    static OFFSETS: LazyLock<usize> = LazyLock::new(|| {
-        // let text = lazysimd::scan::get_text();
-        // lazysimd::get_offset_neon(&text, "fd 7b be a9 f3 0b 00 f9 fd 03 00 91 f3 03 00 aa ?? ?? ?? ?? ?? ?? ?? ?? c0 00 80 52 ?? ?? ?? ?? e0 03 13 aa ?? ?? ?? ?? f3 0b 40 f9 00 00 00 12 fd 7b c2 a8 c0 03 5f d6").unwrap()
-        // ddlc offset
-        0x1329a0
-    });
-
-    *OFFSETS
+    api::offset(api::Symbol::Il2CppInit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_bits() {
+        let mut ty = Il2CppType::new(Il2CppTypeEnum::SzArray, std::ptr::null());
+        // Attributes, then the type enum, 6 bits of num_mods, byref and pinned
+        ty.bits |= 0x0006 | (3 << TYPE_NUM_MODS_SHIFT) | TYPE_PINNED;
+
+        assert_eq!(ty.get_attributes(), 0x0006);
+        assert_eq!(ty.get_type_enum(), Some(Il2CppTypeEnum::SzArray));
+        assert_eq!(ty.get_num_mods(), 3);
+        assert!(!ty.is_byref());
+        assert!(ty.is_pinned());
+
+        ty.set_byref(true);
+        ty.set_attributes(0x0001);
+
+        assert_eq!(ty.bits, 0x0001 | (0x1d << TYPE_ENUM_SHIFT) | (3 << TYPE_NUM_MODS_SHIFT) | (1 << 30) | (1 << 31));
+        assert!(ty.is_byref());
+
+        ty.set_byref(false);
+
+        assert!(!ty.is_byref() && ty.is_pinned());
+        assert_eq!(ty.get_type_enum(), Some(Il2CppTypeEnum::SzArray));
+    }
+
+    #[test]
+    fn num_mods_use_six_bits() {
+        let mut ty = Il2CppType::new(Il2CppTypeEnum::I4, std::ptr::null());
+        ty.bits |= 0x3f << TYPE_NUM_MODS_SHIFT;
+
+        assert_eq!(ty.get_num_mods(), 0x3f);
+        assert!(!ty.is_byref() && !ty.is_pinned());
+        assert_eq!(ty.get_type_enum(), Some(Il2CppTypeEnum::I4));
+    }
+
+    #[test]
+    fn union_arms() {
+        let element = Il2CppType::new(Il2CppTypeEnum::I4, std::ptr::null());
+        let array = Il2CppType::new(Il2CppTypeEnum::SzArray, &element as *const Il2CppType as *const u8);
+
+        assert!(array.get_element_type().is_some_and(|ty| std::ptr::eq(ty, &element)));
+        assert!(array.get_class_index().is_none());
+        assert!(element.get_element_type().is_none());
+
+        let mut class = Il2CppType::new(Il2CppTypeEnum::Class, std::ptr::null());
+        class.data.class_index = 42;
+
+        assert_eq!(class.get_class_index(), Some(42));
+        assert!(class.get_generic_parameter_index().is_none());
+
+        let mut parameter = Il2CppType::new(Il2CppTypeEnum::MVar, std::ptr::null());
+        parameter.data.generic_parameter_index = 2;
+
+        assert_eq!(parameter.get_generic_parameter_index(), Some(2));
+        assert!(parameter.get_generic_class().is_none());
+
+        let unknown = Il2CppType { data: Il2CppTypeData { data: std::ptr::null() }, bits: 0x7f << TYPE_ENUM_SHIFT };
+
+        assert_eq!(unknown.get_type_enum(), None);
+    }
 }
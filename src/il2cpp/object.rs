@@ -2,7 +2,10 @@ use std::ops::{Deref, DerefMut};
 
 use crate::{Il2CppResult, Il2CppError};
 
-use super::{api, class::{Il2CppClass, Il2CppClassData}};
+use super::{api, class::{Il2CppClass, Il2CppClassData}, field::{FieldValue, ValueType}, method::MethodInfo};
+
+/// Size of the `klass` and `monitor` header at the start of every object, which the fields and the value of a boxed struct come right after.
+pub(crate) const OBJECT_HEADER_SIZE: usize = std::mem::size_of::<Il2CppObject<()>>();
 
 /// A type alias for `Il2CppObject<Array<T>>`.
 pub type Il2CppArray<T> = Array<T>;
@@ -16,7 +19,9 @@ pub type Il2CppArray<T> = Array<T>;
 /// 
 /// Example:
 ///
-/// ```
+/// ```no_run
+/// # use unity::prelude::*;
+/// # pub struct ProcInst;
 /// pub fn hooked_method(proc: &Il2CppObject<ProcInst>) {
 /// // ...
 /// }
@@ -57,7 +62,55 @@ impl<T> AsMut<T> for Il2CppObject<T> {
     }
 }
 
+impl Il2CppObject<()> {
+    /// Box a value type into a new object of its class, like storing it as an `object` in C# does.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use unity::prelude::*;
+    /// # use unity::engine::Vector3;
+    /// # fn main() -> Il2CppResult<()> {
+    /// let boxed = Il2CppObject::box_value(Vector3::new(1.0, 2.0, 3.0))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn box_value<V: ValueType>(value: V) -> Il2CppResult<&'static mut Il2CppObject<()>> {
+        let class = V::value_class();
+
+        unsafe { api::value_box(class, &value as *const V as *const u8) }
+            .ok_or_else(|| Il2CppError::FailedInstantiation(class.get_name()))
+    }
+}
+
 impl<T> Il2CppObject<T> {
+    /// Read the value of a boxed value type, failing if the object is not a boxed `V`.
+    ///
+    /// Boxed enums can be unboxed as their underlying type.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use unity::prelude::*;
+    /// # fn main() -> Il2CppResult<()> {
+    /// pub fn hooked_method(this: &Il2CppObject<()>, value: &Il2CppObject<()>, method_info: OptionalMethod) {
+    ///     if let Ok(hp) = value.unbox::<i32>() {
+    ///         // ...
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn unbox<V: ValueType>(&self) -> Il2CppResult<V> {
+        let class = self.get_class();
+
+        if !class.is_valuetype() || !V::can_read(class.get_type()) {
+            return Err(Il2CppError::InvalidUnbox(format!("{}.{}", class.get_namespace(), class.get_name()), std::any::type_name::<V>()));
+        }
+
+        Ok(unsafe { std::ptr::read_unaligned((self as *const Self as *const u8).add(OBJECT_HEADER_SIZE) as *const V) })
+    }
+
     pub fn get_class(&self) -> &Il2CppClass {
         self.klass
     }
@@ -66,7 +119,195 @@ impl<T> Il2CppObject<T> {
         self.klass
     }
 
+    /// Give the object a copy of its class, so changes to it like [`replace_virtual_method`](Self::replace_virtual_method) only affect this instance.
+    ///
+    /// Objects already using a copy keep it. The object is still an instance of the original class for the casts and type checks of this crate, but not for the ones of the runtime.
+    pub fn make_class_unique(&mut self) -> Il2CppResult<&mut Il2CppClass> {
+        if !self.klass.is_clone() {
+            self.klass = self.klass.clone()?;
+        }
+
+        Ok(self.klass)
+    }
+
+    /// Replace the implementation of a virtual method for this object only, returning the one it replaced so it can still be called.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use unity::prelude::*;
+    /// # use std::sync::atomic::{AtomicPtr, Ordering};
+    /// # static ORIGINAL: AtomicPtr<u8> = AtomicPtr::new(std::ptr::null_mut());
+    /// # extern "C" fn cursed_sword_get_damage(this: &Il2CppObject<()>, method_info: OptionalMethod) -> i32 { 0 }
+    /// # fn main() -> Il2CppResult<()> {
+    /// # let sword: &mut Il2CppObject<()> = unimplemented!();
+    /// let get_damage = sword.get_class().get_method_from_name("GetDamage", 0)?;
+    /// ORIGINAL.store(sword.replace_virtual_method(get_damage, cursed_sword_get_damage as _)?, Ordering::Relaxed);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn replace_virtual_method(&mut self, method: &MethodInfo, method_ptr: *mut u8) -> Il2CppResult<*mut u8> {
+        self.make_class_unique()?.replace_virtual_method(method, method_ptr)
+    }
+
+    /// Make the object use the class its copy was made from again, undoing [`make_class_unique`](Self::make_class_unique).
+    pub fn restore_class(&mut self) {
+        if let Some(original) = self.klass.cloned_from() {
+            self.klass = original;
+        }
+    }
+
+    /// Get the object as an instance of `C`, failing if its class cannot be stored as `C::class()`.
+    ///
+    /// The Rust type of a generic class only names its definition, so instances of generic classes cannot be cast with this.  
+    /// Use [`cast_generic`](Self::cast_generic) to provide their type arguments instead.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use unity::prelude::*;
+    /// # fn main() -> Il2CppResult<()> {
+    /// pub fn hooked_method(this: &Il2CppObject<()>, method_info: OptionalMethod) {
+    ///     if let Ok(string) = this.cast::<Il2CppString>() {
+    ///         // ...
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn cast<C: Il2CppClassData>(&self) -> Il2CppResult<&C> {
+        self.check_cast::<C>(None)?;
+        Ok(unsafe { &*(self as *const Self as *const C) })
+    }
+
+    /// Get the object as a mutable instance of `C`, failing if its class cannot be stored as `C::class()`.
+    pub fn cast_mut<C: Il2CppClassData>(&mut self) -> Il2CppResult<&mut C> {
+        self.check_cast::<C>(None)?;
+        Ok(unsafe { &mut *(self as *mut Self as *mut C) })
+    }
+
+    /// Get the object as an instance of the generic class `C`, failing unless it is one with these type arguments.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use unity::{prelude::*, system::List};
+    /// # fn main() -> Il2CppResult<()> {
+    /// # let object: &Il2CppObject<()> = unimplemented!();
+    /// let strings = object.cast_generic::<List<Il2CppString>>(&[Il2CppString::class()])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn cast_generic<C: Il2CppClassData>(&self, arguments: &[&Il2CppClass]) -> Il2CppResult<&C> {
+        self.check_cast::<C>(Some(arguments))?;
+        Ok(unsafe { &*(self as *const Self as *const C) })
+    }
+
+    /// Get the object as a mutable instance of the generic class `C`, failing unless it is one with these type arguments.
+    pub fn cast_generic_mut<C: Il2CppClassData>(&mut self, arguments: &[&Il2CppClass]) -> Il2CppResult<&mut C> {
+        self.check_cast::<C>(Some(arguments))?;
+        Ok(unsafe { &mut *(self as *mut Self as *mut C) })
+    }
+
+    fn check_cast<C: Il2CppClassData>(&self, arguments: Option<&[&Il2CppClass]>) -> Il2CppResult<()> {
+        let (class, target) = (self.get_class(), C::class());
+
+        let valid = match arguments {
+            Some(arguments) => class.is_generic_instance_of(target, arguments),
+            None => target.is_assignable_from(class),
+        };
+
+        match valid {
+            true => Ok(()),
+            false => Err(Il2CppError::InvalidCast(
+                format!("{}.{}", class.get_namespace(), class.get_name()),
+                format!("{}.{}", target.get_namespace(), target.get_name()),
+            )),
+        }
+    }
+
+    /// Read a field of the object by name, without needing a structure mirroring the class.
+    ///
+    /// Fails if the field does not exist, is static or cannot be read as `V`, which includes `null` for `&T`.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use unity::prelude::*;
+    /// # fn main() -> Il2CppResult<()> {
+    /// # let unit: &Il2CppObject<()> = unimplemented!();
+    /// let hp: i32 = unit.get_field("m_Hp")?;
+    /// let name: &Il2CppString = unit.get_field("<Name>k__BackingField")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_field<V: FieldValue>(&self, name: impl AsRef<str>) -> Il2CppResult<V> {
+        self.klass.get_field_from_name(name)?.get_value(Some(self as *const Self as *const u8))
+    }
+
+    /// Write a field of the object by name, without needing a structure mirroring the class.
+    ///
+    /// Fails if the field does not exist, is static or cannot hold `value`.  
+    /// References are stored through the write barrier of the garbage collector.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use unity::prelude::*;
+    /// # fn main() -> Il2CppResult<()> {
+    /// # let unit: &mut Il2CppObject<()> = unimplemented!();
+    /// unit.set_field("m_Hp", 99i32)?;
+    /// unit.set_field("m_Name", Some(Il2CppString::new("Marth")))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_field<V: FieldValue>(&mut self, name: impl AsRef<str>, value: V) -> Il2CppResult<()> {
+        let object = self as *mut Self as *mut u8;
+        self.klass.get_field_from_name(name)?.set_value(Some(object), value)
+    }
+
+    /// Call the getter of a property of the object by name, going through the vtable for virtual properties.
+    ///
+    /// Fails if the property does not exist, is static, has no getter or does not return a `V`.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use unity::prelude::*;
+    /// # use unity::engine::{Material, Shader};
+    /// # fn main() -> Il2CppResult<()> {
+    /// # let material: &Material = unimplemented!();
+    /// let shader: Option<&Shader> = material.get_property("shader")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_property<V: FieldValue>(&self, name: impl AsRef<str>) -> Il2CppResult<V> {
+        self.klass.get_property_from_name(name)?.get_value(Some(self as *const Self as *const u8))
+    }
+
+    /// Call the setter of a property of the object by name, going through the vtable for virtual properties.
+    ///
+    /// Fails if the property does not exist, is static, has no setter or does not take a `V`.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use unity::prelude::*;
+    /// # use unity::engine::{FilterMode, Texture2D};
+    /// # fn main() -> Il2CppResult<()> {
+    /// # let texture: &mut Texture2D = unimplemented!();
+    /// texture.set_property("filterMode", FilterMode::Point as i32)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_property<V: FieldValue>(&mut self, name: impl AsRef<str>, value: V) -> Il2CppResult<()> {
+        let object = self as *mut Self as *mut u8;
+        self.klass.get_property_from_name(name)?.set_value(Some(object), value)
+    }
+
     /// Create a unique [`Il2CppObject`] instance of the [`Il2CppClass`](crate::il2cpp::class::Il2CppClass) provided.
+    ///
+    /// The garbage collector frees it once no managed object references it, see [`GcHandle`](crate::il2cpp::gchandle::GcHandle) to keep it from Rust.
     pub fn from_class(class: &Il2CppClass) -> Il2CppResult<&'static mut T> {
         unsafe { api::object_new(class) }.ok_or(Il2CppError::FailedInstantiation(class.get_name()))
     }
@@ -106,13 +347,13 @@ pub trait ArrayInstantiator<T> {
     /// 
     /// Arguments:
     ///
-    /// * `class`: The class of the elements that are going to be stored.
     /// * `capacity`: The maximum amount of element that can be stored.
     /// 
     /// Example:
     /// 
-    /// ```
-    /// let new_array: Il2CppArray<u8> = Il2CppArray::<u8>::new(SystemByte::get_class(), 69).unwrap();
+    /// ```no_run
+    /// # use unity::prelude::*;
+    /// let new_array: &mut Il2CppArray<u8> = Il2CppArray::<u8>::new(69).unwrap();
     /// ```
     fn new(capacity: usize) -> Il2CppResult<&'static mut Self>;
 
@@ -120,14 +361,14 @@ pub trait ArrayInstantiator<T> {
     /// 
     /// Arguments:
     ///
-    /// * `class`: The class of the elements that are going to be stored.
     /// * `slice`: The slice that'll be copied into the Il2CppArray.
     /// 
     /// Example:
     /// 
-    /// ```
-    /// let mut slice: &mut [u8] = &[0x1, 0x2, 0x3];
-    /// let new_array: Il2CppArray<u8> = Il2CppArray::<u8>::new_from(SystemByte::get_class(), slice).unwrap();
+    /// ```no_run
+    /// # use unity::prelude::*;
+    /// let slice: &mut [u8] = &mut [0x1, 0x2, 0x3];
+    /// let new_array: &mut Il2CppArray<u8> = Il2CppArray::<u8>::from_slice(slice).unwrap();
     /// ```
     /// 
     /// Note that this method takes ownership of the slice, so you won't be able to use it afterwards.
@@ -139,13 +380,13 @@ impl ArrayInstantiator<u8> for Array<u8> {
     /// 
     /// Arguments:
     ///
-    /// * `class`: The class of the elements that are going to be stored.
     /// * `capacity`: The maximum amount of element that can be stored.
     /// 
     /// Example:
     /// 
-    /// ```
-    /// let new_array: Il2CppArray<u8> = Il2CppArray::<u8>::new(SystemByte::get_class(), 69).unwrap();
+    /// ```no_run
+    /// # use unity::prelude::*;
+    /// let new_array: &mut Il2CppArray<u8> = Il2CppArray::<u8>::new(69).unwrap();
     /// ```
     fn new(capacity: usize) -> Il2CppResult<&'static mut Self> {
         array_new(u8::class(), capacity)
@@ -155,14 +396,14 @@ impl ArrayInstantiator<u8> for Array<u8> {
     /// 
     /// Arguments:
     ///
-    /// * `class`: The class of the elements that are going to be stored.
     /// * `slice`: The slice that'll be copied into the Il2CppArray.
     /// 
     /// Example:
     /// 
-    /// ```
-    /// let mut slice: &mut [u8] = &[0x1, 0x2, 0x3];
-    /// let new_array: Il2CppArray<u8> = Il2CppArray::<u8>::new_from(SystemByte::get_class(), slice).unwrap();
+    /// ```no_run
+    /// # use unity::prelude::*;
+    /// let slice: &mut [u8] = &mut [0x1, 0x2, 0x3];
+    /// let new_array: &mut Il2CppArray<u8> = Il2CppArray::<u8>::from_slice(slice).unwrap();
     /// ```
     /// 
     /// Note that this method takes ownership of the slice, so you won't be able to use it afterwards.
@@ -178,13 +419,13 @@ impl<T: Il2CppClassData> ArrayInstantiator<&'static mut T> for Array<&'static mu
     /// 
     /// Arguments:
     ///
-    /// * `class`: The class of the elements that are going to be stored.
     /// * `capacity`: The maximum amount of element that can be stored.
     /// 
     /// Example:
     /// 
-    /// ```
-    /// let new_array: Il2CppArray<u8> = Il2CppArray::<u8>::new(SystemByte::get_class(), 69).unwrap();
+    /// ```no_run
+    /// # use unity::prelude::*;
+    /// let new_array: &mut Il2CppArray<&'static mut Il2CppString> = Il2CppArray::<&'static mut Il2CppString>::new(69).unwrap();
     /// ```
     fn new(capacity: usize) -> Il2CppResult<&'static mut Self> {
         array_new(T::class(), capacity)
@@ -194,14 +435,14 @@ impl<T: Il2CppClassData> ArrayInstantiator<&'static mut T> for Array<&'static mu
     /// 
     /// Arguments:
     ///
-    /// * `class`: The class of the elements that are going to be stored.
     /// * `slice`: The slice that'll be copied into the Il2CppArray.
     /// 
     /// Example:
     /// 
-    /// ```
-    /// let mut slice: &mut [u8] = &[0x1, 0x2, 0x3];
-    /// let new_array: Il2CppArray<u8> = Il2CppArray::<u8>::new_from(SystemByte::get_class(), slice).unwrap();
+    /// ```no_run
+    /// # use unity::prelude::*;
+    /// let strings = vec![Il2CppString::new_static("a"), Il2CppString::new_static("b")];
+    /// let new_array: &mut Il2CppArray<&'static mut Il2CppString> = Il2CppArray::<&'static mut Il2CppString>::from_slice(strings).unwrap();
     /// ```
     /// 
     /// Note that this method takes ownership of the slice, so you won't be able to use it afterwards.
@@ -237,9 +478,12 @@ impl<T> Array<T> {
     /// This is partially needed because we do not implement Clone on Il2CppObject.
     pub fn to_vec(&mut self) -> Vec<T> {
         let mut vec = Vec::with_capacity(self.len());
-        unsafe { vec.set_len(self.len()) }
-        // TODO: Try to improve this with a memcpy, since we have the same size
-        self.swap_with_slice(vec.as_mut());
+
+        unsafe {
+            std::ptr::copy_nonoverlapping(self.as_ptr(), vec.as_mut_ptr(), self.len());
+            vec.set_len(self.len());
+        }
+
         vec
     }
 }
@@ -251,3 +495,178 @@ fn array_new_specific<T>(array_typeinfo: &Il2CppClass, length: usize) -> Il2CppR
 fn array_new<T>(element_typeinfo: &Il2CppClass, length: usize) -> Il2CppResult<&'static mut Il2CppArray<T>> {
     unsafe { api::array_new(element_typeinfo, length) }.ok_or(Il2CppError::FailedArrayInstantiation)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{engine::Vector3, mock::{ClassBuilder, MethodBuilder, Mock}};
+
+    #[crate::class("Tests.Object", "Item")]
+    struct Item {
+        value: i32,
+    }
+
+    // Tests share the mock, so the class is only declared once
+    fn item_class() -> &'static Il2CppClass {
+        static CLASS: std::sync::OnceLock<&'static Il2CppClass> = std::sync::OnceLock::new();
+
+        CLASS.get_or_init(|| {
+            let mock = Mock::install();
+            ClassBuilder::new(mock.image("ObjectTests"), "Tests.Object", "Item").fields::<ItemFields>().build()
+        })
+    }
+
+    #[crate::class("Tests.Object", "Container`1")]
+    struct Container<T: 'static> {
+        item: Option<&'static T>,
+    }
+
+    #[test]
+    fn casts() {
+        let mock = Mock::install();
+        let image = mock.image("ObjectTests");
+        let class = item_class();
+
+        let special = ClassBuilder::new(image, "Tests.Object", "SpecialItem").parent(class).fields::<ItemFields>().build();
+        let object: &mut Il2CppObject<()> = crate::il2cpp::instantiate_class(special).unwrap();
+        assert_eq!(object.cast::<Item>().unwrap().value, 0);
+
+        object.cast_mut::<Item>().unwrap().value = 3;
+        assert_eq!(object.cast::<Item>().unwrap().value, 3);
+        assert!(matches!(object.cast::<crate::system::Il2CppString>(), Err(Il2CppError::InvalidCast(from, to)) if from == "Tests.Object.SpecialItem" && to == "System.String"));
+
+        // Generic classes need their type arguments, which the Rust type cannot tell
+        let container = ClassBuilder::new(image, "Tests.Object", "Container`1").fields::<ContainerFields<Item>>().build();
+        let int = mock.class("System", "Int32").unwrap();
+        let ints = crate::il2cpp::class::make_generic(container, [&*int]).unwrap();
+        let object: &Il2CppObject<()> = crate::il2cpp::instantiate_class(ints).unwrap();
+
+        assert!(object.cast::<Container<Item>>().is_err());
+        assert!(object.cast_generic::<Container<Item>>(&[class]).is_err());
+        assert!(object.cast_generic::<Container<i32>>(&[int]).unwrap().item.is_none());
+    }
+
+    #[test]
+    fn boxing() {
+        let mock = Mock::install();
+        let int = mock.class("System", "Int32").unwrap();
+
+        let boxed = Il2CppObject::box_value(5i32).unwrap();
+        assert!(std::ptr::eq(boxed.get_class(), int));
+        assert_eq!(boxed.unbox::<i32>().unwrap(), 5);
+        assert!(matches!(boxed.unbox::<i64>(), Err(Il2CppError::InvalidUnbox(class, "i64")) if class == "System.Int32"));
+        assert!(boxed.unbox::<f32>().is_err());
+
+        let vector3 = ClassBuilder::new(mock.image("UnityEngine.CoreModule"), "UnityEngine", "Vector3")
+            .value_type()
+            .fields::<Vector3<f32>>()
+            .build();
+
+        let boxed = Il2CppObject::box_value(Vector3::new(1.0f32, 2.0, 3.0)).unwrap();
+        assert!(std::ptr::eq(boxed.get_class(), vector3));
+        assert_eq!(format!("{:?}", boxed.unbox::<Vector3<f32>>().unwrap()), "Vector3 { x: 1.0, y: 2.0, z: 3.0 }");
+        assert!(boxed.unbox::<i32>().is_err());
+
+        // References are not boxed values
+        let item: &Il2CppObject<()> = crate::il2cpp::instantiate_class(item_class()).unwrap();
+        assert!(matches!(item.unbox::<i32>(), Err(Il2CppError::InvalidUnbox(..))));
+    }
+
+    #[crate::class("Tests.Object", "Sword")]
+    struct Sword {
+        damage: i32,
+    }
+
+    extern "C" fn cursed_sword_get_damage(_this: &Sword, _method_info: Option<&MethodInfo>) -> i32 {
+        0
+    }
+
+    #[test]
+    fn unique_classes() {
+        let mock = Mock::install();
+
+        let class = ClassBuilder::new(mock.image("ObjectTests"), "Tests.Object", "Sword")
+            .fields::<SwordFields>()
+            .virtual_method(MethodBuilder::new("GetDamage").returns(mock.class("System", "Int32").unwrap()).function(|this: &Sword| this.damage))
+            .build();
+
+        let get_damage = class.get_method_from_name("GetDamage", 0).unwrap();
+        let damage_ptr = |object: &Il2CppObject<()>| object.get_class().get_virtual_method("GetDamage").unwrap().method_ptr;
+
+        let cursed: &mut Il2CppObject<()> = crate::il2cpp::instantiate_class(class).unwrap();
+        let other: &mut Il2CppObject<()> = crate::il2cpp::instantiate_class(class).unwrap();
+        let original_ptr = damage_ptr(cursed);
+
+        let copy = cursed.make_class_unique().unwrap() as *const Il2CppClass;
+        assert!(!std::ptr::eq(copy, class) && cursed.get_class().is_clone());
+        assert!(std::ptr::eq(cursed.get_class().get_original(), class));
+        assert!(std::ptr::eq(cursed.make_class_unique().unwrap(), copy));
+
+        // Only the object using the copy is affected
+        let replaced = cursed.replace_virtual_method(get_damage, cursed_sword_get_damage as *mut u8).unwrap();
+        assert_eq!(replaced, original_ptr);
+        assert_eq!(damage_ptr(cursed), cursed_sword_get_damage as *mut u8);
+        assert_eq!(damage_ptr(other), original_ptr);
+        assert!(std::ptr::eq(other.get_class(), class));
+
+        // The copy is still the same class for the checks of the crate
+        assert!(class.is_assignable_from(cursed.get_class()));
+        assert!(cursed.cast::<Sword>().is_ok());
+
+        cursed.restore_class();
+        assert!(std::ptr::eq(cursed.get_class(), class));
+        assert_eq!(damage_ptr(cursed), original_ptr);
+
+        // Restoring an object using its original class does nothing
+        other.restore_class();
+        assert!(std::ptr::eq(other.get_class(), class));
+    }
+
+    #[test]
+    fn allocate_objects() {
+        let class = item_class();
+
+        let item = Il2CppObject::<Item>::from_class(class).unwrap();
+        assert!(std::ptr::eq(item.get_class(), class));
+        assert_eq!(item.value, 0);
+
+        item.value = 5;
+        assert_eq!(item.fields.value, 5);
+    }
+
+    #[test]
+    fn value_arrays() {
+        Mock::install();
+
+        let array = Il2CppArray::<u8>::new(4).unwrap();
+        assert_eq!(array.len(), 4);
+        assert!(array.iter().all(|byte| *byte == 0));
+
+        let array = Il2CppArray::<u8>::from_slice([1, 2, 3]).unwrap();
+        assert_eq!(&array[..], &[1, 2, 3]);
+        assert_eq!(array.to_vec(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn reference_arrays() {
+        let class = item_class();
+
+        let items = (0..3)
+            .map(|value| {
+                let item = Item::instantiate().unwrap();
+                item.value = value;
+                item
+            })
+            .collect::<Vec<_>>();
+
+        let array = Il2CppArray::<&'static mut Item>::from_slice(items).unwrap();
+        assert_eq!(array.len(), 3);
+        assert!(array.iter().enumerate().all(|(index, item)| item.value == index as i32));
+        assert!(array.iter().all(|item| std::ptr::eq(item.get_class(), class)));
+
+        let array_class = array.get_class();
+        let copy = Il2CppArray::<&'static mut Item>::new_specific(array_class, 5).unwrap();
+        assert_eq!(copy.len(), 5);
+        assert!(std::ptr::eq(copy.get_class(), array_class));
+    }
+}
@@ -0,0 +1,141 @@
+use std::{ffi::CStr, mem::MaybeUninit};
+
+use super::{class::Il2CppClass, field::{self, FieldValue}, method::{MethodInfo, METHOD_ATTRIBUTE_STATIC}, object::{Il2CppObject, OBJECT_HEADER_SIZE}, Il2CppType};
+use crate::{Il2CppError, Il2CppResult};
+
+/// Type representing the reflection information of a C# property.
+///
+/// A property has no storage of its own, reading or writing it calls its `get_X` or `set_X` method.
+///
+/// Example:
+///
+/// ```no_run
+/// # use unity::prelude::*;
+/// # fn main() -> Il2CppResult<()> {
+/// let property = Il2CppClass::from_name("UnityEngine", "Material")?.get_property_from_name("shader")?;
+/// println!("{} has a setter: {}", property.get_name(), property.get_setter().is_some());
+/// # Ok(())
+/// # }
+/// ```
+#[repr(C)]
+pub struct PropertyInfo {
+    pub parent: &'static Il2CppClass,
+    pub(crate) name: *const u8,
+    pub get: Option<&'static MethodInfo>,
+    pub set: Option<&'static MethodInfo>,
+    pub attrs: u32,
+    pub token: u32,
+}
+
+unsafe impl Send for PropertyInfo {}
+unsafe impl Sync for PropertyInfo {}
+
+impl PropertyInfo {
+    pub fn get_name(&self) -> String {
+        unsafe { String::from_utf8_lossy(CStr::from_ptr(self.name as _).to_bytes()).to_string() }
+    }
+
+    pub fn get_getter(&self) -> Option<&'static MethodInfo> {
+        self.get
+    }
+
+    pub fn get_setter(&self) -> Option<&'static MethodInfo> {
+        self.set
+    }
+
+    /// The type of the property, as returned by its getter or taken by its setter.
+    pub fn get_type(&self) -> Option<&'static Il2CppType> {
+        match (self.get, self.set) {
+            (Some(get), _) => unsafe { (get.return_type as *const Il2CppType).as_ref() },
+            (None, Some(set)) => set.get_parameters().last().map(|parameter| parameter.parameter_type),
+            (None, None) => None,
+        }
+    }
+
+    pub fn is_static(&self) -> bool {
+        self.get.or(self.set).is_some_and(|method| method.flags & METHOD_ATTRIBUTE_STATIC != 0)
+    }
+
+    /// Whether the property takes parameters, like the `this[]` indexer of collections.
+    pub fn is_indexer(&self) -> bool {
+        match (self.get, self.set) {
+            (Some(get), _) => get.parameters_count != 0,
+            (None, Some(set)) => set.parameters_count > 1,
+            (None, None) => false,
+        }
+    }
+
+    /// Call the getter on `object`, or as a static method if `None`.
+    pub(crate) fn get_value<V: FieldValue>(&self, object: Option<*const u8>) -> Il2CppResult<V> {
+        self.check_access(object.is_some())?;
+
+        let getter = self.get.ok_or_else(|| Il2CppError::InvalidPropertyAccess(self.get_name(), "it has no getter"))?;
+
+        if !self.get_type().is_some_and(V::can_read) {
+            return Err(Il2CppError::PropertyTypeMismatch(self.get_name(), std::any::type_name::<V>()));
+        }
+
+        let (method_ptr, method, this) = self.resolve(getter, object)?;
+
+        unsafe {
+            let value = match this {
+                Some(this) => std::mem::transmute::<*mut u8, extern "C" fn(*const u8, &MethodInfo) -> MaybeUninit<V>>(method_ptr)(this, method),
+                None => std::mem::transmute::<*mut u8, extern "C" fn(&MethodInfo) -> MaybeUninit<V>>(method_ptr)(method),
+            };
+
+            field::read_value(value, || self.get_name())
+        }
+    }
+
+    /// Call the setter on `object`, or as a static method if `None`.
+    pub(crate) fn set_value<V: FieldValue>(&self, object: Option<*mut u8>, value: V) -> Il2CppResult<()> {
+        self.check_access(object.is_some())?;
+
+        let setter = self.set.ok_or_else(|| Il2CppError::InvalidPropertyAccess(self.get_name(), "it has no setter"))?;
+
+        if !self.get_type().is_some_and(|ty| value.can_write(ty)) {
+            return Err(Il2CppError::PropertyTypeMismatch(self.get_name(), std::any::type_name::<V>()));
+        }
+
+        let (method_ptr, method, this) = self.resolve(setter, object.map(|object| object as *const u8))?;
+
+        unsafe {
+            match this {
+                Some(this) => std::mem::transmute::<*mut u8, extern "C" fn(*const u8, V, &MethodInfo)>(method_ptr)(this, value, method),
+                None => std::mem::transmute::<*mut u8, extern "C" fn(V, &MethodInfo)>(method_ptr)(value, method),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_access(&self, has_object: bool) -> Il2CppResult<()> {
+        let reason = match has_object {
+            _ if self.is_indexer() => "it takes parameters",
+            true if self.is_static() => "it is static",
+            false if !self.is_static() => "it is not static",
+            _ => return Ok(()),
+        };
+
+        Err(Il2CppError::InvalidPropertyAccess(self.get_name(), reason))
+    }
+
+    /// Find the implementation to call and the `this` to give it, which is the override of the class of `object` for virtual accessors.
+    fn resolve(&self, method: &'static MethodInfo, object: Option<*const u8>) -> Il2CppResult<(*mut u8, &'static MethodInfo, Option<*const u8>)> {
+        let resolved = match object {
+            Some(object) => match unsafe { &*(object as *const Il2CppObject<()>) }.get_class().resolve_virtual(method) {
+                // The vtable of a struct points to thunks taking the boxed object
+                Some(entry) => (entry.method_ptr, entry.method_info, Some(object)),
+                // Instance methods of structs take a pointer to the value rather than to the boxed object
+                None if self.parent.is_valuetype() => (method.method_ptr, method, Some(unsafe { object.add(OBJECT_HEADER_SIZE) })),
+                None => (method.method_ptr, method, Some(object)),
+            },
+            None => (method.method_ptr, method, None),
+        };
+
+        match resolved {
+            (method_ptr, ..) if method_ptr.is_null() => Err(Il2CppError::InvalidPropertyAccess(self.get_name(), "its accessor has no implementation")),
+            resolved => Ok(resolved),
+        }
+    }
+}
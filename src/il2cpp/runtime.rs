@@ -0,0 +1,180 @@
+//! Abstraction over the Il2Cpp runtime the crate talks to.
+//!
+//! Every raw call into the runtime goes through the [`Il2CppRuntime`] currently in use.
+//! On the Switch, this defaults to [`SkylineRuntime`](skyline::SkylineRuntime), which calls the functions of the game executable directly.
+//! [`HostRuntime`](host::HostRuntime) implements the same operations in Rust, so the crate can be used outside of a game, such as in `cargo test`.
+
+use std::{ffi::CStr, sync::RwLock};
+
+use super::{
+    assembly::{Il2CppAssembly, Il2CppImage},
+    class::{Il2CppClass, Il2CppReflectionType},
+    exception::Il2CppException,
+    method::MethodInfo,
+    object::{Il2CppArray, Il2CppObject},
+    Il2CppType,
+};
+use crate::{
+    cppvector::CppVector,
+    engine::{Rect, Sprite, SpriteMeshType, Texture2D, Vector2},
+    system::Il2CppString,
+    Il2CppResult,
+    Il2CppError,
+};
+
+pub mod host;
+#[cfg(target_os = "horizon")]
+pub mod skyline;
+
+/// The raw operations the crate needs from an Il2Cpp runtime.
+///
+/// Methods mirror the `il2cpp_*` API exported by the runtime on other platforms.
+pub trait Il2CppRuntime: Send + Sync {
+    fn init(&self, domain_name: &CStr) -> i32;
+
+    fn get_assemblies(&self) -> &'static CppVector<&'static Il2CppAssembly>;
+
+    fn get_image_by_assembly_name(&self, name: &CStr) -> Option<&'static Il2CppImage>;
+
+    fn class_from_name(&self, image: &Il2CppImage, namespace: &CStr, name: &CStr) -> Option<&'static mut Il2CppClass>;
+
+    fn class_from_il2cpptype(&self, ty: &Il2CppType) -> Option<&'static mut Il2CppClass>;
+
+    fn class_init(&self, class: &Il2CppClass);
+
+    /// Allocate a new instance of the class. The fields are zeroed and the constructor is not called.
+    fn object_new(&self, class: &Il2CppClass) -> Option<&'static mut Il2CppObject<()>>;
+
+    /// Allocate an array of `length` elements of the provided class.
+    fn array_new(&self, element_class: &Il2CppClass, length: usize) -> Option<&'static mut Il2CppArray<()>>;
+
+    /// Allocate an array of `length` elements, using the class of the array itself (`T[]`) rather than the one of the elements.
+    fn array_new_specific(&self, array_class: &Il2CppClass, length: usize) -> Option<&'static mut Il2CppArray<()>>;
+
+    fn get_method_from_name_flags(&self, class: &Il2CppClass, name: &CStr, args_count: usize, flags: u32) -> Option<&'static mut MethodInfo>;
+
+    /// Get the `System.Type` instance representing the type.
+    fn type_get_object(&self, ty: &Il2CppType) -> Option<&'static mut Il2CppReflectionType>;
+
+    /// Box a value type into a new object of its class.
+    ///
+    /// # Safety
+    ///
+    /// `data` must point to a value of the class, as laid out in an unboxed field.
+    unsafe fn value_box(&self, class: &Il2CppClass, data: *const u8) -> Option<&'static mut Il2CppObject<()>>;
+
+    fn string_new(&self, string: &CStr) -> Option<&'static mut Il2CppString>;
+
+    /// Make a copy of the string with every occurrence of `old_value` replaced by `new_value`, like `System.String::Replace(System.String,System.String)`.
+    ///
+    /// Fails if the game function cannot be found, as there is no signature for it.
+    fn string_replace(&self, string: &Il2CppString, old_value: &Il2CppString, new_value: &Il2CppString) -> Il2CppResult<&'static mut Il2CppString>;
+
+    /// Create a sprite from an area of the texture, like the overload of `UnityEngine.Sprite::Create` taking an extrude and a mesh type.
+    ///
+    /// Fails if the game function cannot be found, as there is no signature for it.
+    fn sprite_create2(
+        &self,
+        texture: &Texture2D,
+        rect: Rect,
+        pivot: Vector2<f32>,
+        pixels_to_unit: f32,
+        extrude: u32,
+        mesh_type: SpriteMeshType,
+    ) -> Il2CppResult<&'static mut Sprite>;
+
+    /// Store a reference to a managed object in a field, letting the garbage collector know about it.
+    ///
+    /// `object` is the instance owning the field, or `None` for static fields.  
+    /// Nothing is written if the runtime function cannot be found, as the collector could free the value while it is still referenced.
+    ///
+    /// # Safety
+    ///
+    /// `field` must point to a reference-typed field of `object`, or to static field storage.
+    unsafe fn gc_wbarrier_set_field(&self, object: Option<&Il2CppObject<()>>, field: *mut *const u8, value: *const u8) -> Il2CppResult<()>;
+
+    /// Allocate zeroed memory that the garbage collector scans for references but never frees, like the runtime does for its own metadata.
+    ///
+    /// Fails if the runtime function cannot be found, as memory the collector does not scan could hold the only reference to an object.
+    fn gc_alloc_fixed(&self, size: usize) -> Il2CppResult<*mut u8>;
+
+    /// Create a handle keeping the object alive until it is freed with [`gchandle_free`](Self::gchandle_free).
+    ///
+    /// `pinned` also prevents the object from being moved, which the collectors used by Il2Cpp never do anyway.  
+    /// Like the other handle functions, it fails if the runtime function cannot be found.
+    fn gchandle_new(&self, object: &Il2CppObject<()>, pinned: bool) -> Il2CppResult<u32>;
+
+    /// Create a handle to the object that does not keep it alive. [`gchandle_get_target`](Self::gchandle_get_target) returns `None` once it is collected.
+    fn gchandle_new_weakref(&self, object: &Il2CppObject<()>, track_resurrection: bool) -> Il2CppResult<u32>;
+
+    fn gchandle_get_target(&self, handle: u32) -> Il2CppResult<Option<&'static mut Il2CppObject<()>>>;
+
+    fn gchandle_free(&self, handle: u32) -> Il2CppResult<()>;
+
+    /// Make a class made by [`ClassInjector`](super::injection::ClassInjector) known to the runtime, which cannot find it from its type on its own.
+    fn inject_class(&self, class: &'static Il2CppClass);
+
+    /// Call a method through its invoker, returning value types boxed.
+    ///
+    /// A managed exception thrown by the method is caught and stored in `exception` instead of unwinding through the caller.  
+    /// Fails if the runtime function cannot be found, rather than calling the invoker with nothing to catch exceptions.
+    ///
+    /// # Safety
+    ///
+    /// `this` must be what the method expects, and `args` must hold one pointer per parameter: the object for references, a pointer to the value otherwise.
+    unsafe fn runtime_invoke(
+        &self,
+        method: &MethodInfo,
+        this: *const u8,
+        args: *const *const u8,
+        exception: &mut Option<&'static mut Il2CppException>,
+    ) -> Il2CppResult<Option<&'static mut Il2CppObject<()>>>;
+
+    /// Throw a managed exception, unwinding through the runtime up to the closest managed `catch`.
+    ///
+    /// The runtime function has to be called as `extern "C-unwind"`, for the unwinding to go through Rust frames.
+    fn raise_exception(&self, exception: &Il2CppException) -> !;
+
+    /// Get the address of a method from its full name, such as `UnityEngine.AssetBundle::LoadFromMemoryAsync_Internal(System.Byte[],System.UInt32)`.
+    fn method_from_name(&self, _name: &CStr) -> Option<*const u8> {
+        None
+    }
+}
+
+static RUNTIME: RwLock<Option<&'static dyn Il2CppRuntime>> = RwLock::new(None);
+
+/// Replace the runtime used by the crate.
+///
+/// This is mostly useful to run code using the crate outside of a game, using a [`HostRuntime`](host::HostRuntime).
+pub fn set_runtime(runtime: &'static dyn Il2CppRuntime) {
+    *RUNTIME.write().unwrap() = Some(runtime);
+}
+
+/// Get the runtime currently in use, if any.
+///
+/// On the Switch, the default runtime is only returned once the functions it requires are found, see [`resolve_required`](super::api::resolve_required).
+pub fn try_get() -> Il2CppResult<&'static dyn Il2CppRuntime> {
+    if let Some(runtime) = *RUNTIME.read().unwrap() {
+        return Ok(runtime);
+    }
+
+    default_runtime()
+}
+
+/// Get the runtime currently in use.
+///
+/// Panics if no runtime has been set on platforms without a default one, or if the default one cannot find the functions it requires.
+pub fn get() -> &'static dyn Il2CppRuntime {
+    try_get().unwrap_or_else(|err| panic!("{}", err))
+}
+
+#[cfg(target_os = "horizon")]
+fn default_runtime() -> Il2CppResult<&'static dyn Il2CppRuntime> {
+    super::api::resolve_required()?;
+    Ok(&skyline::SkylineRuntime)
+}
+
+#[cfg(not(target_os = "horizon"))]
+fn default_runtime() -> Il2CppResult<&'static dyn Il2CppRuntime> {
+    Err(Il2CppError::MissingRuntime)
+}
@@ -0,0 +1,391 @@
+//! An Il2Cpp runtime implemented in Rust, for use outside of a game.
+//!
+//! It does not know about any class by itself: images, assemblies and classes have to be registered with it first.
+//! Objects are allocated with the global allocator and never freed, as there is no garbage collector.
+
+use std::{
+    alloc::Layout,
+    cell::Cell,
+    ffi::{CStr, CString},
+    sync::Mutex,
+};
+
+use super::Il2CppRuntime;
+use crate::{
+    cppvector::CppVector,
+    engine::{Rect, Sprite, SpriteMeshType, Texture2D, Vector2},
+    il2cpp::{
+        api::Symbol,
+        assembly::{Il2CppAssembly, Il2CppImage},
+        class::{Il2CppClass, Il2CppReflectionType},
+        exception::Il2CppException,
+        method::MethodInfo,
+        object::{Il2CppArray, Il2CppObject, OBJECT_HEADER_SIZE},
+        Il2CppType,
+    },
+    system::Il2CppString,
+    Il2CppError,
+    Il2CppResult,
+};
+use unity_core::scan::ScanError;
+
+/// Size of the header of arrays, which also holds the bounds and length.
+pub(crate) const ARRAY_HEADER_SIZE: usize = 0x20;
+
+thread_local! {
+    /// Exception thrown by the method being called through [`Il2CppRuntime::runtime_invoke`] on this thread, see [`HostRuntime::throw`].
+    static PENDING_EXCEPTION: Cell<Option<*mut Il2CppException>> = const { Cell::new(None) };
+}
+
+#[derive(Default)]
+struct HostState {
+    assemblies: Vec<&'static Il2CppAssembly>,
+    assembly_vector: Option<&'static CppVector<&'static Il2CppAssembly>>,
+    classes: Vec<*mut Il2CppClass>,
+    // (element class, array class)
+    array_classes: Vec<(*const Il2CppClass, *mut Il2CppClass)>,
+    type_objects: Vec<(*const Il2CppType, *mut Il2CppReflectionType)>,
+    // Targets of the GC handles, by handle minus one. Freed handles are `None`.
+    gc_handles: Vec<Option<*mut Il2CppObject<()>>>,
+}
+
+// Everything in there is leaked and never moves, so sharing the pointers across threads is fine.
+unsafe impl Send for HostState {}
+
+/// In-process implementation of [`Il2CppRuntime`].
+///
+/// Example:
+///
+/// ```no_run
+/// # use unity::{il2cpp::{assembly::Il2CppAssembly, runtime::host::HostRuntime}, prelude::*};
+/// # fn main() -> Il2CppResult<()> {
+/// # let (my_assembly, my_class): (&'static Il2CppAssembly, &'static Il2CppClass) = unimplemented!();
+/// let runtime = HostRuntime::install();
+/// runtime.register_assembly(my_assembly);
+/// runtime.register_class(my_class);
+///
+/// let instance = Il2CppClass::from_name("MyNamespace", "MyClass")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct HostRuntime {
+    state: Mutex<HostState>,
+}
+
+impl HostRuntime {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new runtime and make it the one used by the crate.
+    pub fn install() -> &'static HostRuntime {
+        let runtime: &'static HostRuntime = Box::leak(Box::new(Self::new()));
+        super::set_runtime(runtime);
+        runtime
+    }
+
+    pub fn register_assembly(&self, assembly: &'static Il2CppAssembly) {
+        let mut state = self.state.lock().unwrap();
+
+        state.assemblies.push(assembly);
+        // The previous vector is leaked, as references to it might still be alive.
+        state.assembly_vector = Some(Box::leak(Box::new(CppVector::from_slice(&state.assemblies))));
+    }
+
+    /// Make the class available to [`Il2CppRuntime::class_from_name`] and type lookups.
+    pub fn register_class(&self, class: &'static Il2CppClass) {
+        self.state.lock().unwrap().classes.push(class as *const Il2CppClass as *mut Il2CppClass);
+    }
+
+    /// Provide the class to use when creating arrays of `element_class` through [`Il2CppRuntime::array_new`].
+    pub fn register_array_class(&self, element_class: &'static Il2CppClass, array_class: &'static Il2CppClass) {
+        self.register_class(array_class);
+        self.state
+            .lock()
+            .unwrap()
+            .array_classes
+            .push((element_class, array_class as *const Il2CppClass as *mut Il2CppClass));
+    }
+
+    /// Find a registered class by name, regardless of the image it belongs to.
+    pub fn find_class(&self, namespace: impl AsRef<str>, name: impl AsRef<str>) -> Option<&'static mut Il2CppClass> {
+        self.state
+            .lock()
+            .unwrap()
+            .classes
+            .iter()
+            .map(|class| unsafe { &mut **class })
+            .find(|class| class.get_namespace() == namespace.as_ref() && class.get_name() == name.as_ref())
+    }
+
+    /// Throw a managed exception from a method called through [`Il2CppRuntime::runtime_invoke`].
+    ///
+    /// There is no unwinding on the host, so the method should return right after. The exception is reported to the caller once it does.
+    pub fn throw(exception: &'static mut Il2CppException) {
+        PENDING_EXCEPTION.with(|pending| pending.set(Some(exception)));
+    }
+
+    fn allocate(size: usize) -> *mut u8 {
+        let layout = Layout::from_size_align(size.max(OBJECT_HEADER_SIZE), 8).unwrap();
+        let ptr = unsafe { std::alloc::alloc_zeroed(layout) };
+
+        if ptr.is_null() {
+            std::alloc::handle_alloc_error(layout)
+        }
+
+        ptr
+    }
+
+    fn allocate_object(class: &Il2CppClass, size: usize) -> *mut u8 {
+        let ptr = Self::allocate(size);
+        unsafe { *(ptr as *mut *const Il2CppClass) = class };
+        ptr
+    }
+}
+
+impl Il2CppRuntime for HostRuntime {
+    fn init(&self, _domain_name: &CStr) -> i32 {
+        1
+    }
+
+    fn get_assemblies(&self) -> &'static CppVector<&'static Il2CppAssembly> {
+        let mut state = self.state.lock().unwrap();
+        state.assembly_vector.get_or_insert_with(|| Box::leak(Box::new(CppVector::new())))
+    }
+
+    fn get_image_by_assembly_name(&self, name: &CStr) -> Option<&'static Il2CppImage> {
+        let name = name.to_string_lossy();
+
+        self.state
+            .lock()
+            .unwrap()
+            .assemblies
+            .iter()
+            .map(|assembly| assembly.image)
+            .find(|image| {
+                let image_name = image.get_name();
+                image_name == name || image_name.strip_suffix(".dll") == Some(name.as_ref())
+            })
+    }
+
+    fn class_from_name(&self, image: &Il2CppImage, namespace: &CStr, name: &CStr) -> Option<&'static mut Il2CppClass> {
+        let namespace = namespace.to_string_lossy();
+        let name = name.to_string_lossy();
+
+        self.state
+            .lock()
+            .unwrap()
+            .classes
+            .iter()
+            .map(|class| unsafe { &mut **class })
+            .find(|class| std::ptr::eq(class._1.image, image) && class.get_namespace() == namespace && class.get_name() == name)
+    }
+
+    fn class_from_il2cpptype(&self, ty: &Il2CppType) -> Option<&'static mut Il2CppClass> {
+        self.state
+            .lock()
+            .unwrap()
+            .classes
+            .iter()
+            .map(|class| unsafe { &mut **class })
+            .find(|class| {
+                // Fields and parameters have their own copy of the type, which only differs by its attributes.
+                let same_type = |other: &Il2CppType| unsafe { other.data.data == ty.data.data } && other.get_type_enum() == ty.get_type_enum();
+
+                std::ptr::eq(&class._1.byval_arg, ty) || std::ptr::eq(&class._1.this_arg, ty) || same_type(&class._1.byval_arg)
+            })
+    }
+
+    fn class_init(&self, _class: &Il2CppClass) {}
+
+    fn object_new(&self, class: &Il2CppClass) -> Option<&'static mut Il2CppObject<()>> {
+        let ptr = Self::allocate_object(class, class._2.instance_size as usize);
+        Some(unsafe { &mut *(ptr as *mut Il2CppObject<()>) })
+    }
+
+    fn array_new(&self, element_class: &Il2CppClass, length: usize) -> Option<&'static mut Il2CppArray<()>> {
+        let array_class = self
+            .state
+            .lock()
+            .unwrap()
+            .array_classes
+            .iter()
+            .find(|(element, _)| std::ptr::eq(*element, element_class))
+            .map(|(_, array)| unsafe { &**array })?;
+
+        self.array_new_specific(array_class, length)
+    }
+
+    fn array_new_specific(&self, array_class: &Il2CppClass, length: usize) -> Option<&'static mut Il2CppArray<()>> {
+        let element_size = match array_class._2.element_size as usize {
+            0 => std::mem::size_of::<usize>(),
+            size => size,
+        };
+
+        let ptr = Self::allocate_object(array_class, ARRAY_HEADER_SIZE + element_size * length);
+
+        unsafe {
+            // max_length comes right after the pointer to the bounds
+            *(ptr.add(0x18) as *mut usize) = length;
+            Some(&mut *(ptr as *mut Il2CppArray<()>))
+        }
+    }
+
+    fn get_method_from_name_flags(&self, class: &Il2CppClass, name: &CStr, args_count: usize, flags: u32) -> Option<&'static mut MethodInfo> {
+        let name = name.to_string_lossy();
+        let mut current = Some(unsafe { &*(class as *const Il2CppClass) });
+
+        // Like the actual runtime, look in the parents too and accept any argument count for -1.
+        while let Some(class) = current {
+            let index = class.get_methods().iter().position(|method| {
+                method.get_name().as_deref() == Some(name.as_ref())
+                    && (args_count == usize::MAX || method.parameters_count as usize == args_count)
+                    && (method.flags as u32 & flags) == flags
+            });
+
+            if let Some(index) = index {
+                return Some(unsafe { &mut **(class._1.methods as *const *mut MethodInfo).add(index) });
+            }
+
+            current = class.parent();
+        }
+
+        None
+    }
+
+    fn type_get_object(&self, ty: &Il2CppType) -> Option<&'static mut Il2CppReflectionType> {
+        if let Some((_, object)) = self.state.lock().unwrap().type_objects.iter().find(|(entry, _)| std::ptr::eq(*entry, ty)) {
+            return Some(unsafe { &mut **object });
+        }
+
+        let class = self.find_class("System", "RuntimeType").or_else(|| self.find_class("System", "Type"))?;
+        let ptr = Self::allocate_object(class, (class._2.instance_size as usize).max(OBJECT_HEADER_SIZE + 8)) as *mut Il2CppReflectionType;
+
+        unsafe { (*ptr).fields.ty = &*(ty as *const Il2CppType) };
+
+        self.state.lock().unwrap().type_objects.push((ty, ptr));
+
+        Some(unsafe { &mut *ptr })
+    }
+
+    unsafe fn value_box(&self, class: &Il2CppClass, data: *const u8) -> Option<&'static mut Il2CppObject<()>> {
+        let object = self.object_new(class)?;
+        let size = (class._2.instance_size as usize).saturating_sub(OBJECT_HEADER_SIZE);
+
+        std::ptr::copy_nonoverlapping(data, (object as *mut Il2CppObject<()> as *mut u8).add(OBJECT_HEADER_SIZE), size);
+
+        Some(object)
+    }
+
+    fn string_new(&self, string: &CStr) -> Option<&'static mut Il2CppString> {
+        let class = self.find_class("System", "String")?;
+        let chars = string.to_string_lossy().encode_utf16().collect::<Vec<_>>();
+
+        // Header, length and a null terminator, like the actual runtime
+        let ptr = Self::allocate_object(class, OBJECT_HEADER_SIZE + 4 + (chars.len() + 1) * 2);
+
+        unsafe {
+            *(ptr.add(OBJECT_HEADER_SIZE) as *mut i32) = chars.len() as i32;
+            std::ptr::copy_nonoverlapping(chars.as_ptr(), ptr.add(OBJECT_HEADER_SIZE + 4) as *mut u16, chars.len());
+            Some(&mut *(ptr as *mut Il2CppString))
+        }
+    }
+
+    fn string_replace(&self, string: &Il2CppString, old_value: &Il2CppString, new_value: &Il2CppString) -> Il2CppResult<&'static mut Il2CppString> {
+        let old_value = old_value.to_string();
+
+        // Thrown by the actual method, as there is nothing to look for
+        if old_value.is_empty() {
+            return Err(Il2CppException::new_argument("String cannot be of zero length.")?.to_error());
+        }
+
+        let replaced = string.to_string().replace(&old_value, &new_value.to_string());
+
+        CString::new(replaced)
+            .ok()
+            .and_then(|replaced| self.string_new(&replaced))
+            .ok_or_else(|| Il2CppError::FailedInstantiation(String::from("System.String")))
+    }
+
+    // There is no Unity engine on the host.
+    fn sprite_create2(
+        &self,
+        _texture: &Texture2D,
+        _rect: Rect,
+        _pivot: Vector2<f32>,
+        _pixels_to_unit: f32,
+        _extrude: u32,
+        _mesh_type: SpriteMeshType,
+    ) -> Il2CppResult<&'static mut Sprite> {
+        Err(ScanError::NoSignature(Symbol::SpriteCreate2.name()).into())
+    }
+
+    // Allocations are never collected, so there is no collector to notify.
+    unsafe fn gc_wbarrier_set_field(&self, _object: Option<&Il2CppObject<()>>, field: *mut *const u8, value: *const u8) -> Il2CppResult<()> {
+        *field = value;
+        Ok(())
+    }
+
+    fn gc_alloc_fixed(&self, size: usize) -> Il2CppResult<*mut u8> {
+        Ok(Self::allocate(size))
+    }
+
+    // Nothing is ever collected, so every handle is a strong one.
+    fn gchandle_new(&self, object: &Il2CppObject<()>, _pinned: bool) -> Il2CppResult<u32> {
+        let mut state = self.state.lock().unwrap();
+
+        state.gc_handles.push(Some(object as *const Il2CppObject<()> as *mut Il2CppObject<()>));
+        Ok(state.gc_handles.len() as u32)
+    }
+
+    fn gchandle_new_weakref(&self, object: &Il2CppObject<()>, _track_resurrection: bool) -> Il2CppResult<u32> {
+        self.gchandle_new(object, false)
+    }
+
+    fn gchandle_get_target(&self, handle: u32) -> Il2CppResult<Option<&'static mut Il2CppObject<()>>> {
+        let state = self.state.lock().unwrap();
+        let target = (handle as usize).checked_sub(1).and_then(|index| state.gc_handles.get(index).copied().flatten());
+
+        Ok(target.map(|target| unsafe { &mut *target }))
+    }
+
+    fn gchandle_free(&self, handle: u32) -> Il2CppResult<()> {
+        let mut state = self.state.lock().unwrap();
+
+        if let Some(target) = (handle as usize).checked_sub(1).and_then(|index| state.gc_handles.get_mut(index)) {
+            *target = None;
+        }
+
+        Ok(())
+    }
+
+    fn inject_class(&self, class: &'static Il2CppClass) {
+        self.register_class(class);
+    }
+
+    unsafe fn runtime_invoke(
+        &self,
+        method: &MethodInfo,
+        this: *const u8,
+        args: *const *const u8,
+        exception: &mut Option<&'static mut Il2CppException>,
+    ) -> Il2CppResult<Option<&'static mut Il2CppObject<()>>> {
+        // Exceptions left over by a method called without going through here
+        PENDING_EXCEPTION.with(|pending| pending.set(None));
+
+        let result = method.call_invoker(this, args);
+
+        *exception = PENDING_EXCEPTION.with(|pending| pending.take()).map(|thrown| &mut *thrown);
+
+        match exception {
+            Some(_) => Ok(None),
+            None => Ok(result),
+        }
+    }
+
+    /// There is no managed code to unwind to on the host, so raising an exception panics with it instead.
+    fn raise_exception(&self, exception: &Il2CppException) -> ! {
+        panic!("{}", exception.to_error())
+    }
+}
@@ -0,0 +1,261 @@
+//! The runtime of the running game, reached through the offsets provided by [`api`](crate::il2cpp::api).
+
+use std::{ffi::CStr, sync::Once};
+
+use super::Il2CppRuntime;
+use crate::{
+    cppvector::CppVector,
+    engine::{Rect, Sprite, SpriteMeshType, Texture2D, Vector2},
+    il2cpp::{
+        api::{offset, resolve, Symbol},
+        assembly::{Il2CppAssembly, Il2CppImage},
+        class::{Il2CppClass, Il2CppReflectionType},
+        exception::Il2CppException,
+        injection,
+        method::{MethodInfo, OptionalMethod},
+        object::{Il2CppArray, Il2CppObject, OBJECT_HEADER_SIZE},
+        Il2CppType,
+    },
+    system::Il2CppString,
+    Il2CppResult,
+};
+
+/// Calls directly into the Il2Cpp runtime embedded in the game executable.
+pub struct SkylineRuntime;
+
+impl Il2CppRuntime for SkylineRuntime {
+    fn init(&self, domain_name: &CStr) -> i32 {
+        unsafe { init(domain_name.as_ptr()) }
+    }
+
+    fn get_assemblies(&self) -> &'static CppVector<&'static Il2CppAssembly> {
+        unsafe { assembly_getallassemblies() }
+    }
+
+    fn get_image_by_assembly_name(&self, name: &CStr) -> Option<&'static Il2CppImage> {
+        unsafe { get_image_by_assembly_name(name.as_ptr() as _) }
+    }
+
+    fn class_from_name(&self, image: &Il2CppImage, namespace: &CStr, name: &CStr) -> Option<&'static mut Il2CppClass> {
+        unsafe { class_from_name(image, namespace.as_ptr() as _, name.as_ptr() as _) }
+    }
+
+    fn class_from_il2cpptype(&self, ty: &Il2CppType) -> Option<&'static mut Il2CppClass> {
+        unsafe { class_from_il2cpptype(ty) }
+    }
+
+    fn class_init(&self, class: &Il2CppClass) {
+        unsafe { class_init(class) }
+    }
+
+    fn object_new(&self, class: &Il2CppClass) -> Option<&'static mut Il2CppObject<()>> {
+        unsafe { object_new(class) }
+    }
+
+    fn array_new(&self, element_class: &Il2CppClass, length: usize) -> Option<&'static mut Il2CppArray<()>> {
+        unsafe { array_new(element_class, length) }
+    }
+
+    fn array_new_specific(&self, array_class: &Il2CppClass, length: usize) -> Option<&'static mut Il2CppArray<()>> {
+        unsafe { array_new_specific(array_class, length) }
+    }
+
+    fn get_method_from_name_flags(&self, class: &Il2CppClass, name: &CStr, args_count: usize, flags: u32) -> Option<&'static mut MethodInfo> {
+        unsafe { get_method_from_name_flags(class, name.as_ptr() as _, args_count, flags) }
+    }
+
+    fn type_get_object(&self, ty: &Il2CppType) -> Option<&'static mut Il2CppReflectionType> {
+        unsafe { type_get_object(ty) }
+    }
+
+    unsafe fn value_box(&self, class: &Il2CppClass, data: *const u8) -> Option<&'static mut Il2CppObject<()>> {
+        match resolve(Symbol::ValueBox) {
+            Ok(_) => value_box(class, data),
+            // Boxing anything but a Nullable<T> is a copy of the value into a new object
+            Err(_) => {
+                let object = object_new(class)?;
+                let size = (class._2.instance_size as usize).saturating_sub(OBJECT_HEADER_SIZE);
+                std::ptr::copy_nonoverlapping(data, (object as *mut Il2CppObject<()> as *mut u8).add(OBJECT_HEADER_SIZE), size);
+                Some(object)
+            },
+        }
+    }
+
+    fn string_new(&self, string: &CStr) -> Option<&'static mut Il2CppString> {
+        unsafe { string_new(string.as_ptr() as _) }
+    }
+
+    fn string_replace(&self, string: &Il2CppString, old_value: &Il2CppString, new_value: &Il2CppString) -> Il2CppResult<&'static mut Il2CppString> {
+        resolve(Symbol::StringReplace)?;
+        Ok(unsafe { string_replace(string, old_value, new_value, None) })
+    }
+
+    fn sprite_create2(
+        &self,
+        texture: &Texture2D,
+        rect: Rect,
+        pivot: Vector2<f32>,
+        pixels_to_unit: f32,
+        extrude: u32,
+        mesh_type: SpriteMeshType,
+    ) -> Il2CppResult<&'static mut Sprite> {
+        resolve(Symbol::SpriteCreate2)?;
+        Ok(unsafe { sprite_create2(texture, rect, pivot, pixels_to_unit, extrude, mesh_type, None) })
+    }
+
+    unsafe fn gc_wbarrier_set_field(&self, object: Option<&Il2CppObject<()>>, field: *mut *const u8, value: *const u8) -> Il2CppResult<()> {
+        resolve(Symbol::GcWbarrierSetField)?;
+        gc_wbarrier_set_field(object, field, value);
+        Ok(())
+    }
+
+    fn gc_alloc_fixed(&self, size: usize) -> Il2CppResult<*mut u8> {
+        resolve(Symbol::GcAllocFixed)?;
+        Ok(unsafe { gc_alloc_fixed(size, std::ptr::null()) })
+    }
+
+    fn gchandle_new(&self, object: &Il2CppObject<()>, pinned: bool) -> Il2CppResult<u32> {
+        resolve(Symbol::GcHandleNew)?;
+        Ok(unsafe { gchandle_new(object, pinned) })
+    }
+
+    fn gchandle_new_weakref(&self, object: &Il2CppObject<()>, track_resurrection: bool) -> Il2CppResult<u32> {
+        resolve(Symbol::GcHandleNewWeakref)?;
+        Ok(unsafe { gchandle_new_weakref(object, track_resurrection) })
+    }
+
+    fn gchandle_get_target(&self, handle: u32) -> Il2CppResult<Option<&'static mut Il2CppObject<()>>> {
+        resolve(Symbol::GcHandleGetTarget)?;
+        Ok(unsafe { gchandle_get_target(handle) })
+    }
+
+    fn gchandle_free(&self, handle: u32) -> Il2CppResult<()> {
+        resolve(Symbol::GcHandleFree)?;
+        unsafe { gchandle_free(handle) };
+        Ok(())
+    }
+
+    fn inject_class(&self, _class: &'static Il2CppClass) {
+        static HOOK: Once = Once::new();
+        HOOK.call_once(|| skyline::install_hook!(class_from_il2cpptype_hook));
+    }
+
+    unsafe fn runtime_invoke(
+        &self,
+        method: &MethodInfo,
+        this: *const u8,
+        args: *const *const u8,
+        exception: &mut Option<&'static mut Il2CppException>,
+    ) -> Il2CppResult<Option<&'static mut Il2CppObject<()>>> {
+        resolve(Symbol::RuntimeInvoke)?;
+        Ok(runtime_invoke(method, this, args, exception))
+    }
+
+    fn raise_exception(&self, exception: &Il2CppException) -> ! {
+        // Declared by hand, as `from_offset` only makes `extern "C"` functions which abort when unwound through
+        let raise_exception = unsafe {
+            let address = skyline::hooks::getRegionAddress(skyline::hooks::Region::Text) as usize + offset(Symbol::RaiseException);
+            std::mem::transmute::<usize, extern "C-unwind" fn(&Il2CppException, Option<&MethodInfo>) -> !>(address)
+        };
+
+        raise_exception(exception, None)
+    }
+
+    fn method_from_name(&self, name: &CStr) -> Option<*const u8> {
+        let method = unsafe { method_name(name.as_ptr() as _) };
+        (!method.is_null()).then_some(method)
+    }
+}
+
+#[skyline::from_offset(offset(Symbol::Init))]
+fn init(domain_name: *const i8) -> i32;
+
+#[skyline::from_offset(offset(Symbol::GetImageByAssemblyName))]
+fn get_image_by_assembly_name(c_str: *const u8) -> Option<&'static Il2CppImage>;
+
+#[skyline::from_offset(offset(Symbol::ClassFromName))]
+fn class_from_name(image: &Il2CppImage, namespace: *const u8, name: *const u8) -> Option<&'static mut Il2CppClass>;
+
+#[skyline::from_offset(offset(Symbol::ObjectNew))]
+fn object_new(klass: &Il2CppClass) -> Option<&'static mut Il2CppObject<()>>;
+
+#[skyline::from_offset(offset(Symbol::GetMethodFromNameFlags))]
+fn get_method_from_name_flags(
+    class: &Il2CppClass,
+    method_name: *const u8,
+    args_count: usize,
+    flags: u32,
+) -> Option<&'static mut MethodInfo>;
+
+#[skyline::from_offset(offset(Symbol::AssemblyGetAllAssemblies))]
+fn assembly_getallassemblies() -> &'static CppVector<&'static Il2CppAssembly>;
+
+#[skyline::from_offset(offset(Symbol::ArrayNewSpecific))]
+fn array_new_specific(array_typeinfo: &Il2CppClass, length: usize) -> Option<&'static mut Il2CppArray<()>>;
+
+#[skyline::from_offset(offset(Symbol::ArrayNew))]
+fn array_new(element_typeinfo: &Il2CppClass, length: usize) -> Option<&'static mut Il2CppArray<()>>;
+
+#[skyline::from_offset(offset(Symbol::TypeGetObject))]
+fn type_get_object(ty: &Il2CppType) -> Option<&'static mut Il2CppReflectionType>;
+
+#[skyline::from_offset(offset(Symbol::ClassFromIl2CppType))]
+fn class_from_il2cpptype(ty: &Il2CppType) -> Option<&'static mut Il2CppClass>;
+
+// The type of an injected class points to the class itself, which the runtime would look up in the metadata instead.
+#[skyline::hook(offset = offset(Symbol::ClassFromIl2CppType))]
+fn class_from_il2cpptype_hook(ty: &Il2CppType) -> Option<&'static mut Il2CppClass> {
+    injection::class_from_type(ty).or_else(|| call_original!(ty))
+}
+
+#[skyline::from_offset(offset(Symbol::ClassInit))]
+fn class_init(class: &Il2CppClass);
+
+#[skyline::from_offset(offset(Symbol::ValueBox))]
+fn value_box(class: &Il2CppClass, data: *const u8) -> Option<&'static mut Il2CppObject<()>>;
+
+#[skyline::from_offset(offset(Symbol::StringNew))]
+fn string_new(c_str: *const u8) -> Option<&'static mut Il2CppString>;
+
+#[skyline::from_offset(offset(Symbol::StringReplace))]
+fn string_replace(this: &Il2CppString, old_value: &Il2CppString, new_value: &Il2CppString, method_info: OptionalMethod) -> &'static mut Il2CppString;
+
+#[skyline::from_offset(offset(Symbol::SpriteCreate2))]
+fn sprite_create2(
+    texture: &Texture2D,
+    rect: Rect,
+    pivot: Vector2<f32>,
+    pixels_to_unit: f32,
+    extrude: u32,
+    mesh_type: SpriteMeshType,
+    method_info: OptionalMethod,
+) -> &'static mut Sprite;
+
+#[skyline::from_offset(offset(Symbol::GcWbarrierSetField))]
+fn gc_wbarrier_set_field(object: Option<&Il2CppObject<()>>, field: *mut *const u8, value: *const u8);
+
+#[skyline::from_offset(offset(Symbol::GcAllocFixed))]
+fn gc_alloc_fixed(size: usize, descriptor: *const u8) -> *mut u8;
+
+#[skyline::from_offset(offset(Symbol::GcHandleNew))]
+fn gchandle_new(object: &Il2CppObject<()>, pinned: bool) -> u32;
+
+#[skyline::from_offset(offset(Symbol::GcHandleNewWeakref))]
+fn gchandle_new_weakref(object: &Il2CppObject<()>, track_resurrection: bool) -> u32;
+
+#[skyline::from_offset(offset(Symbol::GcHandleGetTarget))]
+fn gchandle_get_target(handle: u32) -> Option<&'static mut Il2CppObject<()>>;
+
+#[skyline::from_offset(offset(Symbol::GcHandleFree))]
+fn gchandle_free(handle: u32);
+
+#[skyline::from_offset(offset(Symbol::RuntimeInvoke))]
+fn runtime_invoke(
+    method: &MethodInfo,
+    this: *const u8,
+    args: *const *const u8,
+    exception: &mut Option<&'static mut Il2CppException>,
+) -> Option<&'static mut Il2CppObject<()>>;
+
+#[skyline::from_offset(offset(Symbol::MethodFromName))]
+fn method_name(name: *const u8) -> *const u8;
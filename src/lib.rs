@@ -1,4 +1,9 @@
 #![feature(lazy_cell, ptr_sub_ptr)]
+// The features are still required by the toolchain used for the Switch.
+#![allow(stable_features)]
+// Methods named after their C# counterpart, and references to objects owned by the garbage collector rather than by their caller.
+#![allow(clippy::should_implement_trait, clippy::inherent_to_string_shadow_display, clippy::mut_from_ref)]
+#![allow(clippy::len_without_is_empty, clippy::new_without_default)]
 
 pub use unity_macro::*;
 
@@ -8,6 +13,7 @@ pub mod cppvector;
 pub mod engine;
 /// The core of this library. Contains the structures to interface with Il2Cpp and its internals.
 pub mod il2cpp;
+pub mod mock;
 pub mod system;
 
 extern crate memoffset;
@@ -25,14 +31,61 @@ pub enum Il2CppError {
     MissingClassForType,
     #[error("could not find the method")]
     MissingMethod,
+    #[error("could not find the field `{0}`")]
+    MissingField(String),
+    #[error("the field `{0}` cannot be used as `{1}`")]
+    FieldTypeMismatch(String, &'static str),
+    #[error("`{0}` is null, which `{1}` cannot hold")]
+    NullReference(String, &'static str),
+    #[error("could not access the field `{0}` as {1}")]
+    InvalidFieldAccess(String, &'static str),
+    #[error("could not unbox an instance of `{0}` as `{1}`")]
+    InvalidUnbox(String, &'static str),
+    #[error("could not cast an instance of `{0}` to `{1}`")]
+    InvalidCast(String, String),
+    #[error("`{0}` does not implement `{1}`")]
+    MissingInterface(String, String),
+    #[error("the method `{0}` is not declared by an interface")]
+    NotAnInterfaceMethod(String),
+    #[error("the method `{0}` has no entry in the vtable of `{1}`")]
+    MissingVtableEntry(String, String),
+    #[error("could not find the property `{0}`")]
+    MissingProperty(String),
+    #[error("the property `{0}` cannot be used as `{1}`")]
+    PropertyTypeMismatch(String, &'static str),
+    #[error("could not access the property `{0}` as {1}")]
+    InvalidPropertyAccess(String, &'static str),
     #[error("could not instantiate the class `{0}`")]
     FailedInstantiation(String),
+    #[error("could not inject the class `{0}`: {1}")]
+    InvalidInjection(String, &'static str),
+    #[error("could not create a delegate of `{0}`: {1}")]
+    InvalidDelegate(String, &'static str),
     #[error("could not instantiate the array")]
     FailedArrayInstantiation,
     #[error("could not invoke the method")]
     FailedMethodInvocation,
+    #[error("could not call the method `{0}` as {1}")]
+    InvalidMethodCall(String, &'static str),
+    #[error("the method `{0}` takes {1} argument(s) but {2} were given")]
+    ArgumentCountMismatch(String, usize, usize),
+    #[error("argument {1} of the method `{0}` cannot be given as `{2}`")]
+    ArgumentTypeMismatch(String, usize, &'static str),
+    /// A managed exception thrown by a method called from Rust, with its class name, message and stack trace.
+    #[error("`{0}` was thrown: {1}")]
+    Exception(String, String, String),
     #[error("could not get a ReflectionType for the type")]
     FailedReflectionQuerying,
+    #[error("could not resolve a runtime function: {0}")]
+    UnresolvedSymbol(#[from] unity_core::scan::ScanError),
+    #[error("could not load the offset profile: {0}")]
+    InvalidProfile(#[from] unity_core::profile::ProfileError),
+    #[error("the offset profile must be set before any function is resolved")]
+    ProfileAlreadyApplied,
+    #[error("no Il2Cpp runtime is available, one must be provided with `il2cpp::runtime::set_runtime`")]
+    MissingRuntime,
+    #[error("the layout of `{0}` does not match the game: {}", il2cpp::layout::format_mismatches(.1))]
+    LayoutMismatch(String, Vec<il2cpp::layout::LayoutMismatch>),
 }
 
 pub mod prelude {
@@ -52,8 +105,12 @@ pub mod prelude {
         Il2CppError,
         il2cpp::{
             class::{Il2CppClass, Il2CppClassData},
-            method::{MethodInfo, OptionalMethod},
+            exception::Il2CppException,
+            field::{FieldInfo, FieldValue, ValueType},
+            gchandle::{GcHandle, WeakGcHandle},
+            method::{Il2CppArg, Il2CppValue, MethodInfo, OptionalMethod},
             object::{Il2CppArray, Il2CppObject, ArrayInstantiator},
+            property::PropertyInfo,
         },
         system::Il2CppString,
     };
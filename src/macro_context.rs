@@ -1,11 +1,21 @@
-pub use std::sync::LazyLock;
-pub use crate::
+pub use std::sync::{atomic::{AtomicPtr, Ordering}, LazyLock, Mutex};
+pub use crate::{
     il2cpp::{
         class::{
             Il2CppClass,
             Il2CppClassData
         },
-        method::MethodInfo
-    }
-;
+        field::FieldValue,
+        injection::ClassInjector,
+        layout::{
+            ClassLayout,
+            FieldLayout
+        },
+        method::MethodInfo,
+        object::Il2CppObject
+    },
+    Il2CppResult
+};
+pub use memoffset::span_of;
+#[cfg(target_os = "horizon")]
 pub use lazysimd::scan;
\ No newline at end of file
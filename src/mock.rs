@@ -0,0 +1,1034 @@
+//! Fake Il2Cpp metadata, to run code using the crate outside of a game.
+//!
+//! [`Mock::install`] sets up a [`HostRuntime`] holding the handful of `mscorlib` classes the crate relies on (`System.Object`, `System.String`, `System.Type`, `System.Exception`, `System.Action`, ...).
+//! Additional images and classes are then declared with [`ClassBuilder`], using the same `#[repr(C)]` layouts as the actual runtime.
+//! Methods can be backed by Rust closures through [`MethodBuilder::function`]: calling the method through its `method_ptr`, like `#[unity::from_offset]` does, ends up in the closure.
+//!
+//! Everything built here is leaked, as Il2Cpp expects its metadata to live for the whole program.
+//!
+//! Example:
+//!
+//! ```ignore
+//! #[unity::class("App", "Counter")]
+//! pub struct Counter {
+//!     pub value: i32,
+//! }
+//!
+//! #[unity::from_offset("App", "Counter", "Increment")]
+//! fn counter_increment(this: &mut Counter, amount: i32, method_info: OptionalMethod) -> i32;
+//!
+//! // In a test
+//! let mock = unity::mock::Mock::install();
+//!
+//! ClassBuilder::new(mock.image("Assembly-CSharp"), "App", "Counter")
+//!     .fields::<CounterFields>()
+//!     .method(MethodBuilder::new("Increment").function(|this: &mut Counter, amount: i32| {
+//!         this.value += amount;
+//!         this.value
+//!     }))
+//!     .build();
+//!
+//! let counter = Counter::instantiate()?;
+//! assert_eq!(unsafe { counter_increment(counter, 2, None) }, 2);
+//! ```
+
+use std::{
+    alloc::Layout,
+    any::Any,
+    ffi::CString,
+    sync::{Arc, Mutex, Once, OnceLock},
+};
+
+use crate::il2cpp::{
+    assembly::{Il2CppAssembly, Il2CppImage},
+    class::{
+        Il2CppClass,
+        Il2CppGenericClass,
+        Il2CppGenericInst,
+        Il2CppReflectionType,
+        Il2CppRuntimeInterfaceOffsetPair,
+        VirtualInvoke,
+        TYPE_ATTRIBUTE_INTERFACE,
+        TYPE_ATTRIBUTE_PUBLIC,
+    },
+    exception::{Il2CppException, Il2CppExceptionFields},
+    field::{FieldInfo, FIELD_ATTRIBUTE_STATIC},
+    injection::{self, MethodFunction},
+    leak_str,
+    method::{MethodInfo, OptionalMethod, ParameterInfo, METHOD_ATTRIBUTE_STATIC, METHOD_ATTRIBUTE_VIRTUAL},
+    object::{Il2CppArray, OBJECT_HEADER_SIZE},
+    property::PropertyInfo,
+    runtime::{
+        host::{HostRuntime, ARRAY_HEADER_SIZE},
+        Il2CppRuntime,
+    },
+    Il2CppType,
+};
+use crate::system::{delegate::{Delegate, DelegateFields}, Il2CppString};
+use unity_core::types::Il2CppTypeEnum;
+
+/// Size of an Il2CppClass without its vtable.
+const CLASS_SIZE: usize = std::mem::size_of::<Il2CppClass>();
+
+// Bitflags of Il2CppClass
+const CLASS_INITIALIZED_AND_NO_ERROR: u8 = 0x1;
+const CLASS_VALUETYPE: u8 = 0x2;
+const CLASS_INITIALIZED: u8 = 0x4;
+const CLASS_SIZE_INITED: u8 = 0x80;
+
+struct GenericInstance {
+    definition: *const Il2CppClass,
+    arguments: Vec<*const Il2CppClass>,
+    class: *mut Il2CppClass,
+}
+
+/// The mock runtime, shared by every test of the process.
+pub struct Mock {
+    runtime: &'static HostRuntime,
+    generic_instances: Mutex<Vec<GenericInstance>>,
+}
+
+// Everything in there is leaked and never moves, so sharing the pointers across threads is fine.
+unsafe impl Send for Mock {}
+unsafe impl Sync for Mock {}
+
+static MOCK: OnceLock<Mock> = OnceLock::new();
+static CORLIB: Once = Once::new();
+
+impl Mock {
+    /// Make the mock the runtime used by the crate, creating it on the first call.
+    ///
+    /// Tests run in parallel share the same mock, so classes declared by different tests should have different names.
+    pub fn install() -> &'static Mock {
+        let mock = MOCK.get_or_init(|| Mock {
+            runtime: HostRuntime::install(),
+            generic_instances: Mutex::new(Vec::new()),
+        });
+
+        CORLIB.call_once(|| mock.build_corlib());
+        mock
+    }
+
+    /// Get the mock, if it was installed.
+    pub fn get() -> Option<&'static Mock> {
+        MOCK.get()
+    }
+
+    fn expect() -> &'static Mock {
+        Self::get().expect("the mock must be installed with `Mock::install` first")
+    }
+
+    pub fn runtime(&self) -> &'static HostRuntime {
+        self.runtime
+    }
+
+    /// The image holding the classes of the `System` namespace.
+    pub fn corlib(&self) -> &'static Il2CppImage {
+        self.image("mscorlib")
+    }
+
+    /// Get the image of an assembly by name, creating the assembly if it does not exist yet.
+    pub fn image(&self, name: impl AsRef<str>) -> &'static Il2CppImage {
+        let name_no_ext = name.as_ref().strip_suffix(".dll").unwrap_or(name.as_ref());
+        let c_name = CString::new(name_no_ext).unwrap();
+
+        if let Some(image) = self.runtime.get_image_by_assembly_name(&c_name) {
+            return image;
+        }
+
+        unsafe {
+            let assembly = allocate(std::mem::size_of::<Il2CppAssembly>()) as *mut Il2CppAssembly;
+            let image = allocate(std::mem::size_of::<Il2CppImage>()) as *mut Il2CppImage;
+
+            (*image).name = leak_str(format!("{}.dll", name_no_ext));
+            (*image).name_no_ext = leak_str(name_no_ext);
+            (*image).assembly = &*assembly;
+
+            (*assembly).image = &*image;
+            (*assembly).referenced_assembly_start = -1;
+
+            self.runtime.register_assembly(&*assembly);
+
+            &*image
+        }
+    }
+
+    /// Find a class built by the mock.
+    pub fn class(&self, namespace: impl AsRef<str>, name: impl AsRef<str>) -> Option<&'static mut Il2CppClass> {
+        self.runtime.find_class(namespace, name)
+    }
+
+    fn corlib_class(&self, name: &str) -> &'static Il2CppClass {
+        self.class("System", name).unwrap_or_else(|| panic!("the mock is missing System.{}", name))
+    }
+
+    /// Find the instance of a generic class built for these arguments.
+    pub fn generic_instance(&self, definition: &Il2CppClass, arguments: &[&Il2CppClass]) -> Option<&'static mut Il2CppClass> {
+        self.generic_instances
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|instance| {
+                std::ptr::eq(instance.definition, definition)
+                    && instance.arguments.len() == arguments.len()
+                    && instance.arguments.iter().zip(arguments).all(|(a, b)| std::ptr::eq(*a, *b))
+            })
+            .map(|instance| unsafe { &mut *instance.class })
+    }
+
+    fn build_corlib(&self) {
+        let corlib = self.corlib();
+
+        // System.Object and System.Array are needed by every other class and array class, so they come first.
+        let object = ClassBuilder::new(corlib, "System", "Object").root().type_enum(Il2CppTypeEnum::Object).without_array().build();
+        let array = ClassBuilder::new(corlib, "System", "Array").without_array().build();
+        self.build_array_class(object);
+        self.build_array_class(array);
+
+        ClassBuilder::new(corlib, "System", "ValueType").build();
+        ClassBuilder::new(corlib, "System", "Void").value_type().type_enum(Il2CppTypeEnum::Void).without_array().build();
+
+        // Each primitive type with its Il2CppTypeEnum and size
+        let primitives = [
+            ("Boolean", Il2CppTypeEnum::Boolean, 1),
+            ("Char", Il2CppTypeEnum::Char, 2),
+            ("SByte", Il2CppTypeEnum::I1, 1),
+            ("Byte", Il2CppTypeEnum::U1, 1),
+            ("Int16", Il2CppTypeEnum::I2, 2),
+            ("UInt16", Il2CppTypeEnum::U2, 2),
+            ("Int32", Il2CppTypeEnum::I4, 4),
+            ("UInt32", Il2CppTypeEnum::U4, 4),
+            ("Int64", Il2CppTypeEnum::I8, 8),
+            ("UInt64", Il2CppTypeEnum::U8, 8),
+            ("Single", Il2CppTypeEnum::R4, 4),
+            ("Double", Il2CppTypeEnum::R8, 8),
+            ("IntPtr", Il2CppTypeEnum::I, 8),
+            ("UIntPtr", Il2CppTypeEnum::U, 8),
+        ];
+
+        for (name, type_enum, size) in primitives {
+            ClassBuilder::new(corlib, "System", name)
+                .value_type()
+                .type_enum(type_enum)
+                .instance_size(OBJECT_HEADER_SIZE + size)
+                .build();
+        }
+
+        // Length and the first character
+        let string = ClassBuilder::new(corlib, "System", "String")
+            .type_enum(Il2CppTypeEnum::String)
+            .instance_size(OBJECT_HEADER_SIZE + 4 + 2)
+            .build();
+
+        let exception = ClassBuilder::new(corlib, "System", "Exception")
+            .fields::<Il2CppExceptionFields>()
+            .method(
+                MethodBuilder::new(".ctor")
+                    .parameter("message", string)
+                    .function(|this: &mut Il2CppException, message: Option<&'static Il2CppString>| this.message = message)
+                    .invoker(exception_ctor_invoker as *const u8),
+            )
+            .build();
+
+        // Exceptions built by `Il2CppException`, which only need the constructor of System.Exception
+        for name in ["SystemException", "ArgumentException", "InvalidOperationException", "NullReferenceException"] {
+            ClassBuilder::new(corlib, "System", name).parent(exception).fields::<Il2CppExceptionFields>().build();
+        }
+
+        let ty = ClassBuilder::new(corlib, "System", "Type").fields::<&'static Il2CppType>().build();
+
+        ClassBuilder::new(corlib, "System", "RuntimeType")
+            .parent(ty)
+            .fields::<&'static Il2CppType>()
+            .method(
+                MethodBuilder::new("MakeGenericType")
+                    .static_method()
+                    .parameter("gt", ty)
+                    .parameter("typeArguments", self.array_class_of(ty))
+                    .returns(ty)
+                    .function(|generic: &'static Il2CppReflectionType, arguments: &'static Il2CppArray<&'static Il2CppReflectionType>| {
+                        make_generic_type(generic, arguments)
+                    })
+                    .invoker(make_generic_type_invoker as *const u8),
+            )
+            .build();
+
+        // Invoke calls the function of static delegates like the code generated by Il2Cpp does
+        let delegate = ClassBuilder::new(corlib, "System", "Delegate").fields::<DelegateFields>().build();
+        let multicast_delegate = ClassBuilder::new(corlib, "System", "MulticastDelegate").parent(delegate).fields::<DelegateFields>().build();
+
+        ClassBuilder::new(corlib, "System", "Action")
+            .parent(multicast_delegate)
+            .fields::<DelegateFields>()
+            .virtual_method(MethodBuilder::new("Invoke").function(|this: &Delegate| {
+                let function = unsafe { std::mem::transmute::<*mut u8, extern "C" fn(OptionalMethod)>(this.method_ptr) };
+                function(this.method)
+            }))
+            .build();
+    }
+
+    fn array_class_of(&self, element: &Il2CppClass) -> &'static Il2CppClass {
+        self.class(element.get_namespace(), format!("{}[]", element.get_name()))
+            .expect("the array class should have been built along with the element class")
+    }
+
+    fn build_array_class(&self, element: &'static Il2CppClass) -> &'static Il2CppClass {
+        let element_size = if element.is_valuetype() {
+            element._2.instance_size as usize - OBJECT_HEADER_SIZE
+        } else {
+            std::mem::size_of::<usize>()
+        };
+
+        let array = ClassBuilder::new(element._1.image, element.get_namespace(), format!("{}[]", element.get_name()))
+            .parent(self.corlib_class("Array"))
+            .instance_size(ARRAY_HEADER_SIZE)
+            .without_array()
+            .build_class();
+
+        array._1.element_class = element;
+        array._1.byval_arg = type_of(Il2CppTypeEnum::SzArray, &element._1.byval_arg as *const Il2CppType as _, false);
+        array._1.this_arg = type_of(Il2CppTypeEnum::SzArray, &element._1.byval_arg as *const Il2CppType as _, true);
+        array._2.element_size = element_size as u32;
+        array._2.rank = 1;
+
+        self.runtime.register_array_class(element, unsafe { &*(array as *const Il2CppClass) });
+        array
+    }
+}
+
+/// Declares a fake class and registers it with the [`Mock`].
+///
+/// Classes inherit from `System.Object` (or `System.ValueType`) unless told otherwise, and their `T[]` array class is built along with them.
+pub struct ClassBuilder {
+    image: &'static Il2CppImage,
+    namespace: String,
+    name: String,
+    parent: Option<&'static Il2CppClass>,
+    declaring_type: Option<&'static Il2CppClass>,
+    root: bool,
+    instance_size: Option<usize>,
+    value_type: bool,
+    interface: bool,
+    interfaces: Vec<&'static Il2CppClass>,
+    type_enum: Option<Il2CppTypeEnum>,
+    with_array: bool,
+    methods: Vec<MethodBuilder>,
+    // (name, class, offset, attributes)
+    fields: Vec<(String, &'static Il2CppClass, i32, u16)>,
+    properties: Vec<String>,
+    static_fields: *mut (),
+    generic: Option<(&'static Il2CppClass, Vec<&'static Il2CppClass>)>,
+}
+
+impl ClassBuilder {
+    pub fn new(image: &'static Il2CppImage, namespace: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            image,
+            namespace: namespace.into(),
+            name: name.into(),
+            parent: None,
+            declaring_type: None,
+            root: false,
+            instance_size: None,
+            value_type: false,
+            interface: false,
+            interfaces: Vec::new(),
+            type_enum: None,
+            with_array: true,
+            methods: Vec::new(),
+            fields: Vec::new(),
+            properties: Vec::new(),
+            static_fields: std::ptr::null_mut(),
+            generic: None,
+        }
+    }
+
+    /// Declare the instance of a generic class for the provided arguments, such as `List<Unit>` for `List`1`.
+    ///
+    /// The instance starts out with the parent, size and methods of the definition.
+    /// This is done automatically by `System.RuntimeType.MakeGenericType`, and therefore `get_generic_class!`, so declaring instances is only needed to give them different methods.
+    pub fn generic_instance(definition: &'static Il2CppClass, arguments: &[&'static Il2CppClass]) -> Self {
+        let mut builder = Self::new(definition._1.image, definition.get_namespace(), definition.get_name());
+
+        builder.parent = definition.parent();
+        builder.declaring_type = definition._1.declaring_type;
+        builder.instance_size = Some(definition._2.instance_size as usize);
+        builder.value_type = definition.is_valuetype();
+        builder.interfaces = definition.get_interfaces().to_vec();
+        builder.static_fields = definition.static_fields;
+        builder.methods = definition.get_methods().iter().map(|method| MethodBuilder::from_method(method)).collect();
+        builder.generic = Some((definition, arguments.to_vec()));
+        builder
+    }
+
+    pub fn parent(mut self, parent: &'static Il2CppClass) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    /// Nest the class in another, like `Skill` in `App.Unit/Skill`.
+    ///
+    /// Nested classes are looked up by their own namespace and name, which is usually an empty namespace.
+    pub fn nested_in(mut self, declaring_type: &'static Il2CppClass) -> Self {
+        self.declaring_type = Some(declaring_type);
+        self
+    }
+
+    /// Use the size of the structure holding the fields of the class, such as the one generated by `#[unity::class]`.
+    pub fn fields<T>(self) -> Self {
+        self.instance_size(OBJECT_HEADER_SIZE + std::mem::size_of::<T>())
+    }
+
+    /// Size of an instance, including the object header.
+    pub fn instance_size(mut self, size: usize) -> Self {
+        self.instance_size = Some(size);
+        self
+    }
+
+    /// Make the class a struct, inheriting from `System.ValueType`.
+    pub fn value_type(mut self) -> Self {
+        self.value_type = true;
+        self
+    }
+
+    /// Make the class an interface, which has no parent.
+    pub fn interface(mut self) -> Self {
+        self.interface = true;
+        self.root = true;
+        self
+    }
+
+    /// Declare an interface implemented by the class, as returned by [`Il2CppClass::get_interfaces`].
+    pub fn implements(mut self, interface: &'static Il2CppClass) -> Self {
+        self.interfaces.push(interface);
+        self
+    }
+
+    /// Provide the static fields of the class, as returned by [`Il2CppClass::get_static_fields`].
+    pub fn static_fields<T>(mut self, fields: T) -> Self {
+        self.static_fields = Box::leak(Box::new(fields)) as *mut T as *mut ();
+        self
+    }
+
+    /// Declare a field for reflection, at the offset Il2Cpp gives it. Like in the actual runtime, offsets include the object header for value types too.
+    pub fn field(mut self, name: impl Into<String>, class: &'static Il2CppClass, offset: usize) -> Self {
+        self.fields.push((name.into(), class, offset as i32, 0));
+        self
+    }
+
+    /// Declare a static field for reflection, at its offset in the structure given to [`static_fields`](Self::static_fields).
+    pub fn static_field(mut self, name: impl Into<String>, class: &'static Il2CppClass, offset: usize) -> Self {
+        self.fields.push((name.into(), class, offset as i32, FIELD_ATTRIBUTE_STATIC));
+        self
+    }
+
+    /// Declare a property for reflection, using the `get_{name}` and `set_{name}` methods of the class as its accessors.
+    ///
+    /// The accessors have to be added with [`method`](Self::method) or [`virtual_method`](Self::virtual_method), either of them can be missing.
+    pub fn property(mut self, name: impl Into<String>) -> Self {
+        self.properties.push(name.into());
+        self
+    }
+
+    /// Add a method to the class, replacing any method with the same name and parameters.
+    ///
+    /// Parameters are compared by count, and by class when both methods declare them, so overloads can be declared with [`MethodBuilder::parameter`].
+    pub fn method(mut self, method: MethodBuilder) -> Self {
+        self.methods.retain(|existing| !existing.overrides(&method));
+        self.methods.push(method);
+        self
+    }
+
+    /// Add a method to the class and its vtable, overriding the entry of the same name inherited from the parent.
+    pub fn virtual_method(self, method: MethodBuilder) -> Self {
+        self.method(method.flags(METHOD_ATTRIBUTE_VIRTUAL))
+    }
+
+    fn root(mut self) -> Self {
+        self.root = true;
+        self
+    }
+
+    fn type_enum(mut self, type_enum: Il2CppTypeEnum) -> Self {
+        self.type_enum = Some(type_enum);
+        self
+    }
+
+    fn without_array(mut self) -> Self {
+        self.with_array = false;
+        self
+    }
+
+    /// Create the class and make it available to the runtime.
+    pub fn build(self) -> &'static mut Il2CppClass {
+        let mock = Mock::expect();
+        let with_array = self.with_array;
+        let generic = self.generic.clone();
+
+        let class = self.build_class();
+
+        if with_array {
+            mock.build_array_class(unsafe { &*(class as *const Il2CppClass) });
+        }
+
+        if let Some((definition, arguments)) = generic {
+            mock.generic_instances.lock().unwrap().push(GenericInstance {
+                definition,
+                arguments: arguments.into_iter().map(|argument| argument as *const Il2CppClass).collect(),
+                class,
+            });
+        }
+
+        class
+    }
+
+    fn build_class(mut self) -> &'static mut Il2CppClass {
+        let mock = Mock::expect();
+
+        let parent = match (self.root, self.parent) {
+            (true, _) => None,
+            (false, Some(parent)) => Some(parent),
+            (false, None) if self.value_type => Some(mock.corlib_class("ValueType")),
+            (false, None) => Some(mock.corlib_class("Object")),
+        };
+
+        let instance_size = self
+            .instance_size
+            .or(parent.map(|parent| parent._2.instance_size as usize))
+            .unwrap_or(OBJECT_HEADER_SIZE);
+
+        let inherited = parent.map(|parent| parent.get_vtable()).unwrap_or_default();
+        let mut interface_offsets = parent.map(|parent| parent.get_interface_offsets().to_vec()).unwrap_or_default();
+
+        // Virtual methods take the slot of the inherited method they override, or a new one after the others.
+        // Slots holding the methods of an interface have no name, so they are never overridden directly.
+        let mut slots = inherited.iter().map(|entry| entry.get_name()).collect::<Vec<_>>();
+
+        for pair in &interface_offsets {
+            let start = pair.offset as usize;
+            slots[start..start + pair.interface_type.get_vtable().len()].fill(None);
+        }
+
+        for method in self.methods.iter_mut().filter(|method| method.flags & METHOD_ATTRIBUTE_VIRTUAL != 0) {
+            method.slot = match slots.iter().position(|name| name.as_deref() == Some(method.name.as_str())) {
+                Some(slot) => slot as u16,
+                None => {
+                    slots.push(Some(method.name.clone()));
+                    (slots.len() - 1) as u16
+                },
+            };
+        }
+
+        // Each interface gets a range of the vtable after the virtual methods, including the ones it inherits.
+        // Interfaces themselves only have their own methods.
+        let mut interfaces = match self.interface {
+            true => Vec::new(),
+            false => self.interfaces.clone(),
+        };
+
+        while let Some(interface) = interfaces.pop() {
+            if !interface_offsets.iter().any(|pair| std::ptr::eq(pair.interface_type, interface)) {
+                interface_offsets.push(Il2CppRuntimeInterfaceOffsetPair { interface_type: interface, offset: slots.len() as i32 });
+                slots.resize(slots.len() + interface.get_vtable().len(), None);
+                interfaces.extend(interface.get_interfaces());
+            }
+        }
+
+        let class = unsafe { &mut *(allocate(CLASS_SIZE + std::mem::size_of::<VirtualInvoke>() * slots.len()) as *mut Il2CppClass) };
+        let class_ref: &'static Il2CppClass = unsafe { &*(class as *const Il2CppClass) };
+
+        let type_enum = self
+            .type_enum
+            .unwrap_or(if self.value_type { Il2CppTypeEnum::ValueType } else { Il2CppTypeEnum::Class });
+
+        class._1.image = self.image;
+        class._1.name = leak_str(&self.name);
+        class._1.namespace = leak_str(&self.namespace);
+
+        // Outside of the game, the data of a class type points to the class itself rather than its definition.
+        match &self.generic {
+            Some((definition, arguments)) => {
+                let argv: &'static [&'static Il2CppType] = Vec::leak(arguments.iter().map(|argument| &argument._1.byval_arg).collect());
+                let inst = Box::leak(Box::new(Il2CppGenericInst {
+                    type_argc: argv.len() as u32,
+                    type_argv: argv.as_ptr(),
+                }));
+
+                let generic_class = Box::leak(Box::new(Il2CppGenericClass {
+                    type_definition_idx: -1,
+                    class_inst: inst,
+                    method_inst: std::ptr::null(),
+                    cached_class: class,
+                }));
+
+                class._1.generic_class = Some(generic_class);
+                class._1.byval_arg = type_of(Il2CppTypeEnum::GenericInst, generic_class as *const Il2CppGenericClass as _, false);
+                class._1.this_arg = type_of(Il2CppTypeEnum::GenericInst, generic_class as *const Il2CppGenericClass as _, true);
+                class._1.gc_desc = definition._1.gc_desc;
+            },
+            None => {
+                class._1.byval_arg = type_of(type_enum, class as *const Il2CppClass as _, false);
+                class._1.this_arg = type_of(type_enum, class as *const Il2CppClass as _, true);
+            },
+        }
+
+        class._1.element_class = class_ref;
+
+        if let Some(parent) = parent {
+            class._1.parent = parent;
+        }
+
+        class._1.declaring_type = self.declaring_type;
+
+        class.static_fields = self.static_fields;
+
+        class._2.instance_size = instance_size as u32;
+        class._2.actual_size = instance_size as u32;
+        class._2.flags = TYPE_ATTRIBUTE_PUBLIC;
+        class._2.bitflags1 = CLASS_INITIALIZED_AND_NO_ERROR | CLASS_INITIALIZED | CLASS_SIZE_INITED;
+
+        if self.value_type {
+            class._2.bitflags1 |= CLASS_VALUETYPE;
+        }
+
+        if self.interface {
+            class._2.flags |= TYPE_ATTRIBUTE_INTERFACE;
+        }
+
+        class._2.interfaces_count = self.interfaces.len() as u16;
+        class._1.implemented_interfaces = Vec::leak(std::mem::take(&mut self.interfaces)).as_ptr();
+
+        let fields = self
+            .fields
+            .iter()
+            .map(|(name, field_class, offset, attrs)| {
+                // Every field has its own type, holding its attributes.
+                let mut ty = Il2CppType::new(Il2CppTypeEnum::End, unsafe { field_class._1.byval_arg.data.data });
+                ty.bits = field_class._1.byval_arg.bits | *attrs as u32;
+
+                FieldInfo {
+                    name: leak_str(name),
+                    ty: Box::leak(Box::new(ty)),
+                    parent: class_ref,
+                    offset: *offset,
+                    token: 0,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        class._2.field_count = fields.len() as u16;
+        class._1.fields = Vec::leak(fields).as_ptr();
+
+        let methods: Vec<&'static MethodInfo> = self.methods.into_iter().map(|method| method.build(class_ref)).collect();
+
+        let mut vtable = inherited.to_vec();
+
+        for method in methods.iter().filter(|method| method.flags & METHOD_ATTRIBUTE_VIRTUAL != 0) {
+            let entry = VirtualInvoke {
+                method_ptr: method.method_ptr,
+                method_info: method,
+            };
+
+            match vtable.get_mut(method.slot as usize) {
+                Some(existing) => *existing = entry,
+                None => vtable.push(entry),
+            }
+        }
+
+        // Interface methods are implemented by the method of the same name, if any.
+        // Otherwise, they keep the implementation inherited from the parent or are left abstract.
+        for pair in &interface_offsets {
+            for (slot, interface_entry) in pair.interface_type.get_vtable().iter().enumerate() {
+                let name = interface_entry.get_name();
+                let index = pair.offset as usize + slot;
+
+                let implementation = slots
+                    .iter()
+                    .position(|slot_name| slot_name.is_some() && *slot_name == name)
+                    .map(|slot| vtable[slot])
+                    .or_else(|| {
+                        methods
+                            .iter()
+                            .find(|method| method.get_name() == name)
+                            .map(|method| VirtualInvoke { method_ptr: method.method_ptr, method_info: method })
+                    })
+                    .or_else(|| vtable.get(index).copied())
+                    .unwrap_or(*interface_entry);
+
+                match vtable.get_mut(index) {
+                    Some(existing) => *existing = implementation,
+                    None => vtable.push(implementation),
+                }
+            }
+        }
+
+        class._2.interface_offsets_count = interface_offsets.len() as u16;
+        class._1.interface_offsets = Vec::leak(interface_offsets).as_ptr();
+
+        class._2.vtable_count = vtable.len() as u16;
+        class.get_vtable_mut().copy_from_slice(&vtable);
+
+        let properties = self
+            .properties
+            .iter()
+            .map(|name| {
+                let accessor = |prefix: &str| methods.iter().copied().find(|method| method.get_name().as_deref() == Some(&format!("{}_{}", prefix, name)));
+
+                PropertyInfo {
+                    parent: class_ref,
+                    name: leak_str(name),
+                    get: accessor("get"),
+                    set: accessor("set"),
+                    attrs: 0,
+                    token: 0,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        class._2.property_count = properties.len() as u16;
+        class._1.properties = Vec::leak(properties).as_ptr();
+
+        class._2.method_count = methods.len() as u16;
+        class._1.methods = Vec::leak(methods).as_ptr();
+
+        mock.runtime.register_class(class_ref);
+
+        class
+    }
+}
+
+/// Declares a method of a fake class.
+///
+/// The method does nothing unless it is given a [`function`](MethodBuilder::function) or a [`pointer`](MethodBuilder::pointer).
+pub struct MethodBuilder {
+    name: String,
+    flags: u16,
+    parameters: Vec<(String, &'static Il2CppClass)>,
+    parameters_count: Option<usize>,
+    return_type: Option<&'static Il2CppClass>,
+    method_ptr: *mut u8,
+    invoker: *const u8,
+    function: Option<(Arc<dyn Any + Send + Sync>, usize)>,
+    slot: u16,
+    base: Option<&'static MethodInfo>,
+}
+
+impl MethodBuilder {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            flags: 0,
+            parameters: Vec::new(),
+            parameters_count: None,
+            return_type: None,
+            method_ptr: std::ptr::null_mut(),
+            invoker: std::ptr::null(),
+            function: None,
+            slot: 0,
+            base: None,
+        }
+    }
+
+    // Copy of a method of a generic definition, for one of its instances.
+    fn from_method(method: &'static MethodInfo) -> Self {
+        let mut builder = Self::new(method.get_name().unwrap_or_default());
+
+        builder.flags = method.flags;
+        builder.parameters_count = Some(method.parameters_count as usize);
+        builder.base = Some(method);
+        builder
+    }
+
+    /// Add the `METHOD_ATTRIBUTE_*` flags to the method.
+    pub fn flags(mut self, flags: u16) -> Self {
+        self.flags |= flags;
+        self
+    }
+
+    /// Make the method static, meaning it does not take a `this` argument.
+    pub fn static_method(self) -> Self {
+        self.flags(METHOD_ATTRIBUTE_STATIC)
+    }
+
+    /// Declare the next parameter of the method.
+    pub fn parameter(mut self, name: impl Into<String>, class: &'static Il2CppClass) -> Self {
+        self.parameters.push((name.into(), class));
+        self
+    }
+
+    /// Set the amount of parameters when they are not declared one by one.
+    ///
+    /// By default, it is deduced from the declared parameters, or the arguments of the [`function`](MethodBuilder::function) minus `this`.
+    pub fn parameters_count(mut self, count: usize) -> Self {
+        self.parameters_count = Some(count);
+        self
+    }
+
+    pub fn returns(mut self, class: &'static Il2CppClass) -> Self {
+        self.return_type = Some(class);
+        self
+    }
+
+    /// Call this closure when the method is called through its `method_ptr`.
+    ///
+    /// The closure receives every argument of the method, starting with `this` for instance methods, but not the trailing `MethodInfo`.
+    /// The MethodInfo has to be provided by the caller for the closure to be found, which `#[unity::from_offset]` takes care of if it is `None`.
+    /// The method also gets an invoker calling the closure, for [`MethodInfo::invoke`].
+    ///
+    /// Panicking in the closure aborts, as a panic cannot unwind through the runtime.
+    pub fn function<Args, F: MethodFunction<Args>>(mut self, function: F) -> Self {
+        self.method_ptr = F::method_ptr();
+        self.invoker = F::invoker();
+        self.function = Some((Arc::new(function), F::ARITY));
+        self
+    }
+
+    /// Use an existing function as the `method_ptr`.
+    pub fn pointer(mut self, method_ptr: *mut u8) -> Self {
+        self.method_ptr = method_ptr;
+        self
+    }
+
+    /// Set the `invoker_method`, used by the runtime to call the method with its arguments in an array.
+    pub fn invoker(mut self, invoker: *const u8) -> Self {
+        self.invoker = invoker;
+        self
+    }
+
+    fn overrides(&self, other: &MethodBuilder) -> bool {
+        let same_classes = self.parameters.is_empty()
+            || other.parameters.is_empty()
+            || self.parameters.iter().zip(&other.parameters).all(|((_, a), (_, b))| std::ptr::eq(*a, *b));
+
+        self.name == other.name && self.count_parameters() == other.count_parameters() && same_classes
+    }
+
+    fn count_parameters(&self) -> usize {
+        if let Some(count) = self.parameters_count {
+            return count;
+        }
+
+        if !self.parameters.is_empty() {
+            return self.parameters.len();
+        }
+
+        match &self.function {
+            Some((_, arity)) if self.flags & METHOD_ATTRIBUTE_STATIC != 0 => *arity,
+            Some((_, arity)) => arity.saturating_sub(1),
+            None => 0,
+        }
+    }
+
+    fn build(self, class: &'static Il2CppClass) -> &'static MethodInfo {
+        let mock = Mock::expect();
+        let count = self.count_parameters();
+
+        // Parameters without a declared type are considered to be objects.
+        let parameters: &'static [ParameterInfo] = Vec::leak(
+            (0..count)
+                .map(|position| {
+                    let (name, class) = self
+                        .parameters
+                        .get(position)
+                        .map(|(name, class)| (leak_str(name), *class))
+                        .unwrap_or_else(|| (std::ptr::null(), mock.corlib_class("Object")));
+
+                    ParameterInfo {
+                        name,
+                        position: position as i32,
+                        token: 0,
+                        parameter_type: &class._1.byval_arg,
+                    }
+                })
+                .collect(),
+        );
+
+        let mut method = match self.base {
+            Some(base) => MethodInfo { class: Some(class), ..*base },
+            None => MethodInfo::new(),
+        };
+
+        method.name = leak_str(&self.name);
+        method.class = Some(class);
+        method.flags = self.flags;
+        method.slot = self.slot;
+        method.parameters_count = count as u8;
+
+        if self.base.is_none() || !self.parameters.is_empty() {
+            method.parameters = parameters.as_ptr();
+        }
+
+        if self.base.is_none() || self.return_type.is_some() {
+            let return_type = self.return_type.unwrap_or_else(|| mock.corlib_class("Void"));
+            method.return_type = &return_type._1.byval_arg as *const Il2CppType as _;
+        }
+
+        if self.base.is_none() || !self.method_ptr.is_null() {
+            method.method_ptr = self.method_ptr;
+        }
+
+        if self.base.is_none() || !self.invoker.is_null() {
+            method.invoker_method = self.invoker;
+        }
+
+        let method: &'static MethodInfo = Box::leak(Box::new(method));
+
+        let function = match (self.function, self.base) {
+            (Some((function, _)), _) => Some(function),
+            (None, Some(base)) if std::ptr::eq(method.method_ptr, base.method_ptr) => injection::function_for(Some(base)),
+            (None, _) => None,
+        };
+
+        if let Some(function) = function {
+            injection::register_function(method, function);
+        }
+
+        method
+    }
+}
+
+#[repr(C)]
+struct MakeGenericTypeArgs<'a> {
+    generic: &'a Il2CppReflectionType,
+    arguments: &'a Il2CppArray<&'a Il2CppReflectionType>,
+}
+
+/// Invoker of `System.RuntimeType.MakeGenericType`, calling its `method_ptr` like the invokers generated by Il2Cpp do.
+extern "C" fn make_generic_type_invoker(
+    method_ptr: *const u8,
+    method: &MethodInfo,
+    _this: Option<&()>,
+    args: &MakeGenericTypeArgs,
+) -> Option<&'static mut Il2CppReflectionType> {
+    let make_generic_type = unsafe {
+        std::mem::transmute::<*const u8, extern "C" fn(&Il2CppReflectionType, &Il2CppArray<&Il2CppReflectionType>, &MethodInfo) -> Option<&'static mut Il2CppReflectionType>>(
+            method_ptr,
+        )
+    };
+
+    make_generic_type(args.generic, args.arguments, method)
+}
+
+/// Invoker of the `System.Exception` constructor taking a message.
+extern "C" fn exception_ctor_invoker(method_ptr: *const u8, method: &MethodInfo, this: &mut Il2CppException, args: *const *const u8) -> *const u8 {
+    let constructor = unsafe {
+        std::mem::transmute::<*const u8, extern "C" fn(&mut Il2CppException, *const u8, &MethodInfo)>(method_ptr)
+    };
+
+    constructor(this, unsafe { *args }, method);
+    std::ptr::null()
+}
+
+/// Instantiate generic classes like the actual `System.RuntimeType.MakeGenericType`, which is what [`make_generic`](crate::il2cpp::class::make_generic) calls.
+fn make_generic_type(generic: &Il2CppReflectionType, arguments: &Il2CppArray<&Il2CppReflectionType>) -> Option<&'static mut Il2CppReflectionType> {
+    let mock = Mock::get()?;
+
+    let definition: &'static Il2CppClass = mock.runtime.class_from_il2cpptype(generic.ty)?;
+
+    // Like the runtime, only generic definitions can be instantiated
+    if definition._1.generic_class.is_some() || !definition.get_name().contains('`') {
+        if let Ok(exception) = Il2CppException::from_name("System", "InvalidOperationException", "the type is not a generic definition") {
+            HostRuntime::throw(exception);
+        }
+
+        return None;
+    }
+
+    let arguments = arguments
+        .iter()
+        .map(|argument| mock.runtime.class_from_il2cpptype(argument.ty).map(|class| &*class))
+        .collect::<Option<Vec<&'static Il2CppClass>>>()?;
+
+    let class = match mock.generic_instance(definition, &arguments) {
+        Some(class) => class,
+        None => ClassBuilder::generic_instance(definition, &arguments).build(),
+    };
+
+    mock.runtime.type_get_object(&class._1.byval_arg)
+}
+
+fn type_of(type_enum: Il2CppTypeEnum, data: *const u8, byref: bool) -> Il2CppType {
+    let mut ty = Il2CppType::new(type_enum, data);
+
+    ty.set_byref(byref);
+    ty
+}
+
+fn allocate(size: usize) -> *mut u8 {
+    let layout = Layout::from_size_align(size, 8).unwrap();
+    let ptr = unsafe { std::alloc::alloc_zeroed(layout) };
+
+    if ptr.is_null() {
+        std::alloc::handle_alloc_error(layout)
+    }
+
+    ptr
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::il2cpp::class::Il2CppClassData;
+
+    #[crate::class("Tests.Mock", "Counter")]
+    struct Counter {
+        value: i32,
+    }
+
+    #[repr(C)]
+    struct CounterStaticFields {
+        instances: i32,
+    }
+
+    #[crate::from_offset("Tests.Mock", "Counter", "Increment")]
+    fn counter_increment(this: &mut Counter, amount: i32, method_info: OptionalMethod) -> i32;
+
+    #[test]
+    fn build_class() {
+        let mock = Mock::install();
+        let int = mock.class("System", "Int32").unwrap();
+
+        ClassBuilder::new(mock.image("MockTests"), "Tests.Mock", "Counter")
+            .fields::<CounterFields>()
+            .field("value", int, 0x10)
+            .static_fields(CounterStaticFields { instances: 3 })
+            .static_field("instances", int, 0)
+            .property("Value")
+            .method(MethodBuilder::new("get_Value").returns(int).function(|this: &Counter| this.value))
+            .method(MethodBuilder::new("Increment").parameter("amount", int).returns(int).function(|this: &mut Counter, amount: i32| {
+                this.value += amount;
+                this.value
+            }))
+            .build();
+
+        let class = Il2CppClass::from_name("Tests.Mock", "Counter").unwrap();
+
+        assert_eq!(class.get_namespace(), "Tests.Mock");
+        assert_eq!(class.get_name(), "Counter");
+        assert!(std::ptr::eq(class.parents().next().unwrap(), mock.class("System", "Object").unwrap()));
+
+        let field = class.get_field_from_name("value").unwrap();
+
+        assert_eq!(field.get_offset(), 0x10);
+        assert!(!field.is_static());
+        assert!(std::ptr::eq(Il2CppClass::from_il2cpptype(field.get_type()).unwrap(), int));
+
+        assert!(class.get_field_from_name("instances").unwrap().is_static());
+        assert_eq!(class.get_static_field::<i32>("instances").unwrap(), 3);
+        assert_eq!(class.get_static_fields::<CounterStaticFields>().instances, 3);
+
+        let increment = class.get_method_from_name("Increment", 1).unwrap();
+
+        assert!(!increment.is_static());
+        assert_eq!(increment.get_signature(), "(System.Int32)");
+        assert_eq!(increment.get_parameters()[0].get_name().as_deref(), Some("amount"));
+        assert!(std::ptr::eq(Il2CppClass::from_il2cpptype(increment.get_return_type().unwrap()).unwrap(), int));
+        assert_eq!(class.get_methods().len(), 2);
+
+        let counter = Counter::instantiate().unwrap();
+
+        assert_eq!(unsafe { counter_increment(counter, 2, None) }, 2);
+        assert_eq!(counter.get_field::<i32>("value").unwrap(), 2);
+        assert_eq!(counter.get_property::<i32>("Value").unwrap(), 2);
+    }
+}
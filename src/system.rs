@@ -1,6 +1,8 @@
-use crate::prelude::{Il2CppArray, Il2CppClassData, MethodInfo};
+use crate::prelude::{FieldValue, Il2CppArray, Il2CppClassData, Il2CppError, Il2CppObject, Il2CppResult, MethodInfo};
 use std::{marker::PhantomData, ops::{Deref, DerefMut}};
 
+pub mod delegate;
+pub use delegate::Delegate;
 pub mod string;
 pub use string::Il2CppString;
 
@@ -61,7 +63,7 @@ impl<T> List<T> {
             .unwrap();
         
         let add = unsafe {
-            std::mem::transmute::<_, extern "C" fn(&mut Self, &'static mut T, &MethodInfo)>(
+            std::mem::transmute::<*mut u8, extern "C" fn(&mut Self, &'static mut T, &MethodInfo)>(
                 method.method_ptr,
             )
         };
@@ -79,18 +81,33 @@ impl<T> List<T> {
 
     pub fn clear(&mut self) {
         self.get_class().get_virtual_method("Clear").map(|method| {
-            let clear = unsafe { std::mem::transmute::<_, extern "C" fn(&List<T>, &MethodInfo)>(method.method_info.method_ptr) };
-            clear(&self, method.method_info);
+            let clear = unsafe { std::mem::transmute::<*mut u8, extern "C" fn(&List<T>, &MethodInfo)>(method.method_info.method_ptr) };
+            clear(self, method.method_info);
         }).unwrap();
     }
 }
 
+impl<T: Il2CppClassData> List<T> {
+    /// Like [`List::add`], but a managed exception thrown by `Add` is returned instead of unwinding through the caller.
+    pub fn try_add(&mut self, element: &'static mut T) -> Il2CppResult<()> {
+        let method = self.get_class()
+            .get_methods()
+            .iter()
+            .find(|method| method.get_name().as_deref() == Some("Add"))
+            .ok_or(Il2CppError::MissingMethod)?;
+
+        let this = unsafe { &*(self as *const Self as *const Il2CppObject<()>) };
+
+        method.invoke(Some(this), &[&element]).map(|_| ())
+    }
+}
+
 pub trait ListVirtual<T>: Il2CppClassData {
     fn add(&mut self, element: &'static mut T) {
         let method = Self::class().get_virtual_method("Add").unwrap();
         
         let add = unsafe {
-            std::mem::transmute::<_, extern "C" fn(&mut Self, &'static mut T, &MethodInfo)>(
+            std::mem::transmute::<*mut u8, extern "C" fn(&mut Self, &'static mut T, &MethodInfo)>(
                 method.method_info.method_ptr,
             )
         };
@@ -111,7 +128,7 @@ impl<TKey, TValue> Dictionary<TKey, TValue> {
             .unwrap();
         
         let add = unsafe {
-            std::mem::transmute::<_, extern "C" fn(&Self, TKey, TValue, &MethodInfo)>(
+            std::mem::transmute::<*mut u8, extern "C" fn(&Self, TKey, TValue, &MethodInfo)>(
                 method.method_info.method_ptr,
             )
         };
@@ -119,13 +136,28 @@ impl<TKey, TValue> Dictionary<TKey, TValue> {
         add(self, key, value, method.method_info);
     }
 
-    pub fn try_get_value<'a>(&self, key: TKey, value: &'a mut TValue) -> bool {
+    /// Like [`Dictionary::add`], but a managed exception thrown by `Add`, such as for a duplicate key, is returned instead of unwinding through the caller.
+    pub fn try_add(&self, key: TKey, value: TValue) -> Il2CppResult<()>
+    where
+        TKey: FieldValue,
+        TValue: FieldValue,
+    {
+        let method = self.get_class()
+            .get_virtual_method("Add")
+            .ok_or(Il2CppError::MissingMethod)?;
+
+        let this = unsafe { &*(self as *const Self as *const Il2CppObject<()>) };
+
+        method.method_info.invoke(Some(this), &[&key, &value]).map(|_| ())
+    }
+
+    pub fn try_get_value(&self, key: TKey, value: &mut TValue) -> bool {
         let method = self.get_class()
             .get_virtual_method("TryGetValue")
             .unwrap();
         
         let try_get_value = unsafe {
-            std::mem::transmute::<_, extern "C" fn(&Self, TKey, &mut TValue, &MethodInfo) -> bool>(
+            std::mem::transmute::<*mut u8, extern "C" fn(&Self, TKey, &mut TValue, &MethodInfo) -> bool>(
                 method.method_info.method_ptr,
             )
         };
@@ -133,3 +165,177 @@ impl<TKey, TValue> Dictionary<TKey, TValue> {
         try_get_value(self, key, value, method.method_info)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        il2cpp::{class::{make_generic, Il2CppClass}, object::ArrayInstantiator},
+        mock::{ClassBuilder, MethodBuilder, Mock},
+    };
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    };
+
+    #[crate::class("Tests.System", "Entry")]
+    struct Entry {
+        value: i32,
+    }
+
+    // Tests share the mock, so the class is only declared once
+    fn list_class() -> &'static Il2CppClass {
+        static CLASS: std::sync::OnceLock<&'static Il2CppClass> = std::sync::OnceLock::new();
+
+        CLASS.get_or_init(|| {
+            let mock = Mock::install();
+            let image = mock.image("SystemTests");
+
+            ClassBuilder::new(image, "Tests.System", "Entry").fields::<EntryFields>().build();
+            ClassBuilder::new(image, "System.Collections.Generic", "List`1")
+                .fields::<ListFields<Entry>>()
+                .method(MethodBuilder::new("Add").function(|this: &mut List<Entry>, item: &'static mut Entry| {
+                    if this.len() == this.capacity() {
+                        this.resize((this.capacity() * 2).max(2));
+                    }
+
+                    let index = this.len();
+                    this.items[index] = item;
+                    this.size += 1;
+                }))
+                .build()
+        })
+    }
+
+    #[test]
+    fn list_add() {
+        list_class();
+
+        let list = List::<Entry>::instantiate().unwrap();
+        list.items = Il2CppArray::<&'static mut Entry>::new(0).unwrap();
+        assert_eq!(list.len(), 0);
+
+        for value in 0..5 {
+            let entry = Entry::instantiate().unwrap();
+            entry.value = value;
+            list.add(entry);
+        }
+
+        assert_eq!(list.len(), 5);
+        assert!(list.capacity() >= 5);
+        assert_eq!(list.iter().map(|entry| entry.value).collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn generic_names() {
+        let mock = Mock::install();
+        let list = list_class();
+
+        let strings = make_generic(list, [Il2CppString::class()]).unwrap();
+        assert_eq!(strings.to_string(), "System.Collections.Generic.List<System.String>");
+
+        let nested = make_generic(list, [&*strings]).unwrap();
+        assert_eq!(nested.to_string(), "System.Collections.Generic.List<System.Collections.Generic.List<System.String>>");
+
+        let int = mock.class("System", "Int32").unwrap();
+        let pair = ClassBuilder::new(mock.image("SystemTests"), "Tests.System", "Pair`2").build();
+        let pair = make_generic(pair, [int, &*strings]).unwrap();
+        assert_eq!(pair.to_string(), "Tests.System.Pair<System.Int32,System.Collections.Generic.List<System.String>>");
+    }
+
+    #[test]
+    fn dictionary_add_and_get() {
+        static ENTRIES: Mutex<Vec<(i32, i32)>> = Mutex::new(Vec::new());
+
+        let mock = Mock::install();
+
+        ClassBuilder::new(mock.image("SystemTests"), "System.Collections.Generic", "Dictionary`1")
+            .virtual_method(MethodBuilder::new("Add").function(|_this: &Dictionary<i32, i32>, key: i32, value: i32| {
+                ENTRIES.lock().unwrap().push((key, value));
+            }))
+            .virtual_method(MethodBuilder::new("TryGetValue").function(|_this: &Dictionary<i32, i32>, key: i32, value: &mut i32| {
+                match ENTRIES.lock().unwrap().iter().find(|(entry, _)| *entry == key) {
+                    Some((_, found)) => {
+                        *value = *found;
+                        true
+                    },
+                    None => false,
+                }
+            }))
+            .build();
+
+        let dictionary = Dictionary::<i32, i32>::instantiate().unwrap();
+        dictionary.add(1, 10);
+        dictionary.add(2, 20);
+
+        let mut value = 0;
+        assert!(dictionary.try_get_value(2, &mut value));
+        assert_eq!(value, 20);
+        assert!(!dictionary.try_get_value(3, &mut value));
+    }
+
+    #[test]
+    fn delegates_share_methods() {
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+        static CONSTRUCTED: AtomicUsize = AtomicUsize::new(0);
+
+        fn on_invoke(_method_info: crate::il2cpp::method::OptionalMethod) {
+            CALLS.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let mock = Mock::install();
+
+        // Unlike the mocked System.Action, this one has a constructor filling the fields like the one of the runtime does
+        let callback = ClassBuilder::new(mock.image("SystemTests"), "Tests.System", "Callback")
+            .parent(mock.class("System", "MulticastDelegate").unwrap())
+            .fields::<delegate::DelegateFields>()
+            .method(
+                MethodBuilder::new(".ctor")
+                    .parameter("object", mock.class("System", "Object").unwrap())
+                    .parameter("method", mock.class("System", "IntPtr").unwrap())
+                    .function(|this: &mut Delegate, target: Option<&'static Il2CppObject<()>>, method: &'static MethodInfo| {
+                        CONSTRUCTED.fetch_add(1, Ordering::Relaxed);
+                        this.method_ptr = method.method_ptr;
+                        this.invoke_impl = method.invoker_method;
+                        this.m_target = target;
+                        this.method = Some(method);
+                    }),
+            )
+            .virtual_method(MethodBuilder::new("Invoke").function(|this: &Delegate| {
+                let function = unsafe { std::mem::transmute::<*mut u8, extern "C" fn(crate::il2cpp::method::OptionalMethod)>(this.method_ptr) };
+                function(this.method)
+            }))
+            .build();
+
+        let first = Delegate::from_fn_with_class(delegate::Action::class(), on_invoke).unwrap();
+        let second = Delegate::from_fn_with_class(delegate::Action::class(), on_invoke).unwrap();
+        let constructed = Delegate::from_fn_with_class(callback, on_invoke).unwrap();
+
+        assert!(std::ptr::eq(first.method.unwrap(), second.method.unwrap()));
+        assert!(!std::ptr::eq(first.method.unwrap(), constructed.method.unwrap()));
+        assert_eq!(CONSTRUCTED.load(Ordering::Relaxed), 1);
+
+        let count = Arc::new(AtomicUsize::new(0));
+        let closures = [(delegate::Action::class(), 1), (callback, 10)].map(|(class, amount)| {
+            let count = count.clone();
+            Delegate::from_closure_with_class(class, move || {
+                count.fetch_add(amount, Ordering::Relaxed);
+            })
+            .unwrap()
+        });
+
+        assert!(!std::ptr::eq(closures[0].method.unwrap(), closures[1].method.unwrap()));
+        assert_eq!(CONSTRUCTED.load(Ordering::Relaxed), 2);
+
+        // Called through Invoke, which calls the method the delegate was made with
+        for delegate in [&*first, &*second, &*constructed, &*closures[0], &*closures[1]] {
+            let invoke = delegate.get_class().get_method_from_name("Invoke", 0).unwrap();
+            let this = unsafe { &*(delegate as *const Delegate as *const Il2CppObject<()>) };
+
+            assert!(invoke.invoke(Some(this), &[]).unwrap().is_void());
+        }
+
+        assert_eq!(CALLS.load(Ordering::Relaxed), 3);
+        assert_eq!(count.load(Ordering::Relaxed), 11);
+    }
+}
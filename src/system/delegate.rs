@@ -0,0 +1,185 @@
+//! Delegates calling Rust functions, to subscribe to C# events and callbacks.
+//!
+//! The delegate calls a [`MethodInfo`] made for the function, which takes the parameters of the `Invoke` method of the delegate type.
+//!
+//! Example:
+//!
+//! ```no_run
+//! # use unity::prelude::*;
+//! # use std::sync::atomic::{AtomicUsize, Ordering};
+//! # use unity::{engine::UnityAction, system::{delegate::Action, Delegate}};
+//! # fn main() -> Il2CppResult<()> {
+//! fn on_click(method_info: OptionalMethod) {
+//!     println!("Clicked!");
+//! }
+//!
+//! let listener: &mut UnityAction = Delegate::from_fn(on_click)?;
+//!
+//! let clicks = AtomicUsize::new(0);
+//! let counter: &mut Action = Delegate::from_closure(move || { clicks.fetch_add(1, Ordering::Relaxed); })?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    sync::{Arc, LazyLock, RwLock},
+};
+
+use crate::il2cpp::{
+    class::{Il2CppClass, Il2CppClassData},
+    injection::{self, MethodFunction, NativeFunction},
+    leak_str,
+    method::{MethodInfo, INVALID_SLOT, METHOD_ATTRIBUTE_HIDE_BY_SIG, METHOD_ATTRIBUTE_PUBLIC, METHOD_ATTRIBUTE_STATIC},
+    object::Il2CppObject,
+};
+use crate::{Il2CppError, Il2CppResult};
+
+// Methods made for functions without state, by address of the delegate class and type of the function.
+static METHODS: LazyLock<RwLock<HashMap<(usize, TypeId), &'static MethodInfo>>> = LazyLock::new(Default::default);
+
+/// `System.Delegate`, the base class of every delegate type.
+///
+/// Only the fields shared by every version of Il2Cpp are mirrored.
+#[repr(C)]
+#[crate::class("System", "Delegate")]
+pub struct Delegate {
+    pub method_ptr: *mut u8,
+    pub invoke_impl: *const u8,
+    /// The object the method is called on, `None` for static methods.
+    pub m_target: Option<&'static Il2CppObject<()>>,
+    pub method: Option<&'static MethodInfo>,
+}
+
+/// `System.Action`, a delegate taking no argument and returning nothing.
+#[repr(C)]
+#[crate::class("System", "Action")]
+pub struct Action { }
+
+impl Delegate {
+    /// Create a delegate of type `D` calling a function that takes the `MethodInfo` as its last argument, like hooks do.
+    ///
+    /// The function takes the arguments of the `Invoke` method of `D` and returns what it does.
+    /// Value types are taken by value and references as references.
+    ///
+    /// Generic delegates such as `System.Action<T>` need the class of their instance, see [`from_fn_with_class`](Self::from_fn_with_class).  
+    /// The [`MethodInfo`] made for the function is shared by every delegate of that class calling it.
+    pub fn from_fn<D: Il2CppClassData + 'static, Args, F: NativeFunction<Args>>(function: F) -> Il2CppResult<&'static mut D> {
+        Self::from_fn_with_class(D::class(), function).map(|delegate| unsafe { &mut *(delegate as *mut Delegate as *mut D) })
+    }
+
+    /// Create a delegate calling a closure, which takes the same arguments as with [`from_fn`](Self::from_fn) except for the `MethodInfo`.
+    ///
+    /// The closure is never dropped, as the runtime might call the delegate at any time, and neither is the [`MethodInfo`] made for it.  
+    /// Closures capturing nothing share theirs like functions do, but every other call leaks a new one: avoid making such delegates repeatedly.
+    pub fn from_closure<D: Il2CppClassData + 'static, Args, F: MethodFunction<Args>>(function: F) -> Il2CppResult<&'static mut D> {
+        Self::from_closure_with_class(D::class(), function).map(|delegate| unsafe { &mut *(delegate as *mut Delegate as *mut D) })
+    }
+
+    /// Like [`from_fn`](Self::from_fn), for a delegate of the provided class.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// # use unity::prelude::*;
+    /// # use unity::system::Delegate;
+    /// # fn on_value(value: i32, method_info: OptionalMethod) {}
+    /// # fn main() -> Il2CppResult<()> {
+    /// let int: &Il2CppClass = Il2CppClass::from_name("System", "Int32")?;
+    /// let action = Il2CppClass::from_name("System", "Action`1")?.with_generic_type([int])?;
+    ///
+    /// let delegate = Delegate::from_fn_with_class(action, on_value)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_fn_with_class<Args, F: NativeFunction<Args>>(class: &'static Il2CppClass, function: F) -> Il2CppResult<&'static mut Delegate> {
+        Self::new(class, F::method_ptr(), F::invoker(), F::ARGUMENT_SIZES, F::RETURN_SIZE, function)
+    }
+
+    /// Like [`from_closure`](Self::from_closure), for a delegate of the provided class.
+    pub fn from_closure_with_class<Args, F: MethodFunction<Args>>(class: &'static Il2CppClass, function: F) -> Il2CppResult<&'static mut Delegate> {
+        Self::new(class, F::method_ptr(), F::invoker(), F::ARGUMENT_SIZES, F::RETURN_SIZE, function)
+    }
+
+    fn new<F: Send + Sync + 'static>(
+        class: &Il2CppClass,
+        method_ptr: *mut u8,
+        invoker: *const u8,
+        argument_sizes: &[usize],
+        return_size: usize,
+        function: F,
+    ) -> Il2CppResult<&'static mut Delegate> {
+        let fail = |reason| Err(Il2CppError::InvalidDelegate(class.to_string(), reason));
+
+        if !class.is_subclass_of(Delegate::class()) {
+            return fail("it is not a delegate type");
+        }
+
+        let Some(invoke) = class.get_methods().iter().find(|method| method.get_name().as_deref() == Some("Invoke")) else {
+            return fail("it has no Invoke method");
+        };
+
+        let parameters = invoke.get_parameters().iter().map(|parameter| parameter.parameter_type);
+
+        // The method made for the function is static, as the delegate has no target
+        if injection::check_signature(true, parameters, invoke.get_return_type(), argument_sizes, return_size).is_err() {
+            return fail("the function does not take the arguments of Invoke or return its value");
+        }
+
+        // Every value of a zero-sized type behaves the same, so the method made for the first one can be reused
+        let method = match std::mem::size_of::<F>() {
+            0 => *METHODS
+                .write()
+                .unwrap()
+                .entry((class as *const Il2CppClass as usize, TypeId::of::<F>()))
+                .or_insert_with(|| make_method(invoke, method_ptr, invoker, function)),
+            _ => make_method(invoke, method_ptr, invoker, function),
+        };
+
+        let delegate: &'static mut Delegate = crate::il2cpp::instantiate_class(class)?;
+
+        // The constructor takes care of the fields that differ between versions of Il2Cpp, such as how `invoke_impl` is used.
+        let constructor = class
+            .get_methods()
+            .iter()
+            .find(|method| method.get_name().as_deref() == Some(".ctor") && method.parameters_count == 2 && !method.method_ptr.is_null());
+
+        match constructor {
+            Some(constructor) => {
+                let constructor_fn = unsafe {
+                    std::mem::transmute::<*mut u8, extern "C" fn(&mut Delegate, Option<&Il2CppObject<()>>, &MethodInfo, &MethodInfo)>(constructor.method_ptr)
+                };
+
+                constructor_fn(delegate, None, method, constructor);
+            },
+            None => {
+                delegate.method_ptr = method.method_ptr;
+                delegate.invoke_impl = method.invoker_method;
+                delegate.m_target = None;
+                delegate.method = Some(method);
+            },
+        }
+
+        Ok(delegate)
+    }
+}
+
+/// A static method with the signature of `Invoke`, which the delegate calls without a target.
+fn make_method<F: Send + Sync + 'static>(invoke: &MethodInfo, method_ptr: *mut u8, invoker: *const u8, function: F) -> &'static MethodInfo {
+    let method: &'static MethodInfo = Box::leak(Box::new(MethodInfo {
+        method_ptr,
+        invoker_method: invoker,
+        name: leak_str(std::any::type_name::<F>()),
+        class: invoke.class,
+        return_type: invoke.return_type,
+        parameters: invoke.parameters,
+        flags: METHOD_ATTRIBUTE_PUBLIC | METHOD_ATTRIBUTE_STATIC | METHOD_ATTRIBUTE_HIDE_BY_SIG,
+        slot: INVALID_SLOT,
+        parameters_count: invoke.parameters_count,
+        ..MethodInfo::new()
+    }));
+
+    injection::register_function(method, Arc::new(function));
+    method
+}
@@ -1,6 +1,10 @@
 use std::{fmt::{Display, Formatter}, str::FromStr};
 
-use crate::prelude::{Il2CppClass, Il2CppClassData, Il2CppObject, OptionalMethod};
+use crate::{
+    il2cpp::api::{string_new, string_replace},
+    prelude::{Il2CppClass, Il2CppClassData, Il2CppObject, OptionalMethod},
+    Il2CppResult,
+};
 
 /// A type alias for `Il2CppObject<SystemString>`.
 /// 
@@ -25,14 +29,14 @@ impl Il2CppClassData for Il2CppString {
     fn class() -> &'static Il2CppClass {
         static CLASS_TYPE: std::sync::LazyLock<&'static mut Il2CppClass> = std::sync::LazyLock::new(|| {
             Il2CppClass::from_name("System", "String")
-                .expect(&format!("Failed to find class {}.{}", "System", "String"))
+                .unwrap_or_else(|_| panic!("Failed to find class {}.{}", "System", "String"))
         });
 
         &CLASS_TYPE
     }
 
     fn class_mut() -> &'static mut Il2CppClass {
-        Self::class().clone()
+        Self::class().clone().unwrap_or_else(|err| panic!("Failed to copy class {}.{}: {}", "System", "String", err))
     }
 }
 
@@ -42,11 +46,6 @@ fn system_string_copy(string: &Il2CppString, method_info: OptionalMethod) -> &'_
 #[crate::from_offset("System", "String", "Clone")]
 fn system_string_clone(this: &Il2CppString, method_info: OptionalMethod) -> &'_ mut Il2CppString;
 
-// #[crate::from_offset("System", "String", "Replace")]
-// ddlc offset
-#[skyline::from_offset(0x18ebc80)]
-fn system_string_replace_str(this: &mut Il2CppString, old_value: &Il2CppString, new_value: &Il2CppString, method_info: OptionalMethod) -> &'static mut Il2CppString;
-
 #[crate::from_offset("System", "String", "Contains")]
 fn system_string_contains(this: &Il2CppString, value: &Il2CppString, method_info: OptionalMethod) -> bool;
 
@@ -70,9 +69,10 @@ impl Il2CppString {
     /// 
     /// Example:
     ///
-    /// ```
+    /// ```no_run
+    /// # use unity::prelude::*;
     /// let string = Il2CppString::new("A new string");
-    /// ```j
+    /// ```
     pub fn new<'a>(string: impl AsRef<str>) -> &'a Il2CppString {
         let cock = std::ffi::CString::new(string.as_ref()).unwrap();
         unsafe { string_new(cock.as_bytes_with_nul().as_ptr()) }
@@ -112,8 +112,11 @@ impl Il2CppString {
         unsafe { system_string_contains(self, value.into(), None) }
     }
 
-    pub fn replace<'a>(&mut self, old_value: impl Into<&'a Il2CppString>, new_value: impl Into<&'a Il2CppString>) -> &'_ mut Il2CppString {
-        unsafe { system_string_replace_str(self, old_value.into(), new_value.into(), None) }
+    /// Provides a new instance of the Il2CppString with every occurrence of `old_value` replaced by `new_value`.
+    ///
+    /// Returns [`Il2CppError::UnresolvedSymbol`](crate::Il2CppError::UnresolvedSymbol) if the runtime cannot find `System.String::Replace`.
+    pub fn replace<'a>(&self, old_value: impl Into<&'a Il2CppString>, new_value: impl Into<&'a Il2CppString>) -> Il2CppResult<&'static mut Il2CppString> {
+        string_replace(self, old_value.into(), new_value.into())
     }
 
     /// Provides a new instance of the Il2CppString, separate from the original.
@@ -174,7 +177,33 @@ impl FromStr for &'_ Il2CppString {
     }
 }
 
-// #[lazysimd::from_pattern("ff 03 01 d1 fd 7b 02 a9 fd 83 00 91 f4 4f 03 a9 f3 03 00 aa ?? ?? ?? ?? 01 7c 40 92 e8 23 00 91 e0 03 13 aa f4 23 00 91 ?? ?? ?? ?? e8 23 40 39 0b fd 41 d3 e9 0f 40 f9")]
-// ddlc offset
-#[skyline::from_offset(0x14c050)]
-fn string_new<'a>(c_str: *const u8) -> &'a mut Il2CppString;
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::Mock;
+
+    #[test]
+    fn round_trip() {
+        Mock::install();
+
+        let string = Il2CppString::new("Héllo, wörld");
+        assert_eq!(string.to_string(), "Héllo, wörld");
+        assert_eq!(format!("<{}>", string), "<Héllo, wörld>");
+        assert!(std::ptr::eq(string.get_class(), Il2CppString::class()));
+
+        let empty: &Il2CppString = "".into();
+        assert_eq!(empty.to_string(), "");
+    }
+
+    #[test]
+    fn replace() {
+        Mock::install();
+
+        let string = Il2CppString::new("Chapter 1: Chapter");
+        let replaced = string.replace("Chapter", "Paralogue").unwrap();
+
+        assert_eq!(replaced.to_string(), "Paralogue 1: Paralogue");
+        assert_eq!(string.to_string(), "Chapter 1: Chapter");
+        assert!(matches!(string.replace("", "Paralogue"), Err(crate::Il2CppError::Exception(..))));
+    }
+}
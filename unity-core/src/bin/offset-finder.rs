@@ -0,0 +1,124 @@
+//! Run the crate's signatures against a dumped main executable and write the resulting offset profile.
+//!
+//! Usage: `offset-finder <main.nso|main.elf> [-o profile.toml] [--base profile.toml] [--name NAME] [--version VERSION]`
+//!
+//! `--base` provides offsets for the symbols that have no signature, and anything the signatures could not find.
+//! The exit code is 2 when a required symbol is missing from the resulting profile.
+
+use std::{path::PathBuf, process::ExitCode};
+
+use unity_core::{executable::Executable, profile::OffsetProfile, signatures::Symbol};
+
+const USAGE: &str = "usage: offset-finder <main.nso|main.elf> [-o profile.toml] [--base profile.toml] [--name NAME] [--version VERSION]";
+
+#[derive(Default)]
+struct Args {
+    input: Option<PathBuf>,
+    output: Option<PathBuf>,
+    base: Option<PathBuf>,
+    name: Option<String>,
+    version: Option<String>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args::default();
+    let mut iter = std::env::args().skip(1);
+
+    while let Some(arg) = iter.next() {
+        let mut value = || iter.next().ok_or(format!("missing value for `{}`", arg));
+
+        match arg.as_str() {
+            "-o" | "--output" => args.output = Some(value()?.into()),
+            "--base" => args.base = Some(value()?.into()),
+            "--name" => args.name = Some(value()?),
+            "--version" => args.version = Some(value()?),
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{}`\n{}", arg, USAGE)),
+            _ if args.input.is_none() => args.input = Some(arg.into()),
+            _ => return Err(format!("unexpected argument `{}`\n{}", arg, USAGE)),
+        }
+    }
+
+    Ok(args)
+}
+
+fn run(args: Args) -> Result<bool, String> {
+    let input = args.input.ok_or(USAGE)?;
+
+    let bytes = std::fs::read(&input).map_err(|err| format!("could not read {}: {}", input.display(), err))?;
+    let executable = Executable::parse(&bytes).map_err(|err| format!("could not load {}: {}", input.display(), err))?;
+
+    let base = match &args.base {
+        Some(path) => OffsetProfile::from_file(path).map_err(|err| format!("could not load {}: {}", path.display(), err))?,
+        None => OffsetProfile::new(),
+    };
+
+    let mut profile = OffsetProfile::new();
+    profile.name = args.name.or(base.name.clone());
+    profile.version = args.version.or(base.version.clone());
+
+    for symbol in Symbol::ALL {
+        if !symbol.has_signature() {
+            match base.get(*symbol) {
+                Some(offset) => {
+                    eprintln!("{:<28} {:#x} (from base profile)", symbol.name(), offset);
+                    profile.set(*symbol, offset);
+                },
+                None => eprintln!("{:<28} no signature", symbol.name()),
+            }
+
+            continue;
+        }
+
+        match symbol.scan(&executable.text.data) {
+            Ok(offset) => {
+                eprintln!("{:<28} {:#x}", symbol.name(), offset);
+                profile.set(*symbol, offset);
+            },
+            Err(err) => match base.get(*symbol) {
+                Some(offset) => {
+                    eprintln!("{:<28} {:#x} (from base profile, {})", symbol.name(), offset, err);
+                    profile.set(*symbol, offset);
+                },
+                None => eprintln!("{:<28} {}", symbol.name(), err),
+            },
+        }
+    }
+
+    let output = profile.to_toml();
+
+    match &args.output {
+        Some(path) => std::fs::write(path, output).map_err(|err| format!("could not write {}: {}", path.display(), err))?,
+        None => print!("{}", output),
+    }
+
+    // Optional symbols only disable the features relying on them, so they do not make the profile unusable.
+    let (required, optional): (Vec<Symbol>, Vec<Symbol>) = Symbol::ALL
+        .iter()
+        .copied()
+        .filter(|symbol| profile.get(*symbol).is_none())
+        .partition(|symbol| symbol.is_required());
+
+    if !optional.is_empty() {
+        eprintln!("{} optional symbol(s) could not be resolved", optional.len());
+    }
+
+    if !required.is_empty() {
+        eprintln!("{} required symbol(s) could not be resolved", required.len());
+    }
+
+    Ok(required.is_empty())
+}
+
+fn main() -> ExitCode {
+    let result = parse_args().and_then(run);
+
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(2),
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::FAILURE
+        },
+    }
+}
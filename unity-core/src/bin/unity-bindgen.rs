@@ -0,0 +1,103 @@
+//! Generate Rust bindings for the types of a game from its metadata and main executable.
+//!
+//! Usage: `unity-bindgen <global-metadata.dat> <main.nso|main.elf> [-o bindings.rs] [--registration ADDRESS] [--namespace NS]... [--image NAME]...`
+//!
+//! `--registration` provides the address of the metadata registration when it cannot be found automatically.
+
+use std::{path::PathBuf, process::ExitCode};
+
+use unity_core::{binary::Il2CppBinary, bindgen::BindingGenerator, executable::Executable, metadata::Metadata};
+
+const USAGE: &str =
+    "usage: unity-bindgen <global-metadata.dat> <main.nso|main.elf> [-o bindings.rs] [--registration ADDRESS] [--namespace NS]... [--image NAME]...";
+
+#[derive(Default)]
+struct Args {
+    metadata: Option<PathBuf>,
+    executable: Option<PathBuf>,
+    output: Option<PathBuf>,
+    registration: Option<u64>,
+    namespaces: Vec<String>,
+    images: Vec<String>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args::default();
+    let mut iter = std::env::args().skip(1);
+
+    while let Some(arg) = iter.next() {
+        let mut value = || iter.next().ok_or(format!("missing value for `{}`", arg));
+
+        match arg.as_str() {
+            "-o" | "--output" => args.output = Some(value()?.into()),
+            "--registration" => {
+                let address = value()?;
+                let parsed = match address.strip_prefix("0x") {
+                    Some(hex) => u64::from_str_radix(hex, 16),
+                    None => address.parse(),
+                };
+
+                args.registration = Some(parsed.map_err(|_| format!("invalid address `{}`", address))?);
+            },
+            "--namespace" => args.namespaces.push(value()?),
+            "--image" => args.images.push(value()?),
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{}`\n{}", arg, USAGE)),
+            _ if args.metadata.is_none() => args.metadata = Some(arg.into()),
+            _ if args.executable.is_none() => args.executable = Some(arg.into()),
+            _ => return Err(format!("unexpected argument `{}`\n{}", arg, USAGE)),
+        }
+    }
+
+    Ok(args)
+}
+
+fn run(args: Args) -> Result<(), String> {
+    let metadata_path = args.metadata.ok_or(USAGE)?;
+    let executable_path = args.executable.ok_or(USAGE)?;
+
+    let metadata = Metadata::from_file(&metadata_path).map_err(|err| format!("could not load {}: {}", metadata_path.display(), err))?;
+
+    let bytes = std::fs::read(&executable_path).map_err(|err| format!("could not read {}: {}", executable_path.display(), err))?;
+    let mut executable = Executable::parse(&bytes).map_err(|err| format!("could not load {}: {}", executable_path.display(), err))?;
+
+    eprintln!("metadata version {}.{}", metadata.version.major, metadata.version.minor);
+
+    // Executables that were already relocated by the tool that dumped them have no MOD0 header left to follow.
+    match executable.relocate() {
+        Ok(relocations) => eprintln!("{} relocation(s) applied", relocations),
+        Err(err) => eprintln!("could not relocate {}, reading it as is: {}", executable_path.display(), err),
+    }
+
+    let binary = Il2CppBinary::load(&executable, &metadata, args.registration)
+        .map_err(|err| format!("could not read the types of {}: {}", executable_path.display(), err))?;
+
+    let mut generator = BindingGenerator::new(&metadata, &binary);
+
+    for namespace in args.namespaces {
+        generator = generator.namespace(namespace);
+    }
+
+    for image in args.images {
+        generator = generator.image(image);
+    }
+
+    let output = generator.generate();
+
+    match &args.output {
+        Some(path) => std::fs::write(path, output).map_err(|err| format!("could not write {}: {}", path.display(), err))?,
+        None => print!("{}", output),
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match parse_args().and_then(run) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::FAILURE
+        },
+    }
+}
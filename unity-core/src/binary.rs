@@ -0,0 +1,301 @@
+//! The part of the Il2Cpp type information that is compiled into the executable rather than stored in the metadata.
+//!
+//! The metadata refers to types by index into the `Il2CppType` table of the executable, and field offsets and type sizes are only found there as well.
+//! Both are reached through the `Il2CppMetadataRegistration` structure, which can usually be found by scanning the data of the executable.
+//!
+//! Example:
+//!
+//! ```no_run
+//! # use unity_core::{binary::Il2CppBinary, executable::Executable, metadata::Metadata};
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let metadata = Metadata::from_file("global-metadata.dat")?;
+//! let mut executable = Executable::parse(&std::fs::read("main")?)?;
+//! executable.relocate()?;
+//!
+//! let binary = Il2CppBinary::load(&executable, &metadata, None)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+
+use thiserror::Error;
+
+use crate::{
+    executable::{Executable, ExecutableError},
+    metadata::{Metadata, MetadataVersion},
+    types::Il2CppTypeEnum,
+};
+
+/// Types nested deeper than this are considered corrupted, to avoid looping on bad pointers.
+const MAX_TYPE_DEPTH: usize = 32;
+
+#[derive(Debug, Error)]
+pub enum BinaryError {
+    #[error("could not find the metadata registration in the executable")]
+    MissingRegistration,
+    #[error("could not read {0} at {1:#x}")]
+    InvalidPointer(&'static str, u64),
+    #[error(transparent)]
+    Executable(#[from] ExecutableError),
+}
+
+/// What an `Il2CppType` describes, with the pointers of the executable already followed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeKind {
+    /// Primitive types, `void`, `string`, `object`, `IntPtr` and pointers without a known target.
+    Builtin(Il2CppTypeEnum),
+    /// A reference type, by index of its type definition.
+    Class(i32),
+    /// A value type, by index of its type definition.
+    ValueType(i32),
+    /// A single-dimensional array with a lower bound of zero, such as `int[]`.
+    SzArray(Box<BinaryType>),
+    /// Any other array, along with its rank.
+    Array(Box<BinaryType>, u8),
+    /// An instance of a generic type, such as `List<int>`.
+    GenericInst { definition: i32, arguments: Vec<BinaryType> },
+    /// A generic parameter of a type, by index of the generic parameter.
+    Var(i32),
+    /// A generic parameter of a method, by index of the generic parameter.
+    MVar(i32),
+    Ptr(Box<BinaryType>),
+    /// Anything the parser does not understand, with its raw `Il2CppTypeEnum`.
+    Other(u8),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinaryType {
+    /// `FieldAttributes` or `ParameterAttributes`, depending on what the type was used for.
+    pub attrs: u16,
+    pub byref: bool,
+    pub kind: TypeKind,
+}
+
+impl BinaryType {
+    pub fn new(kind: TypeKind) -> Self {
+        Self { attrs: 0, byref: false, kind }
+    }
+}
+
+/// Sizes of a type definition, as computed by Il2Cpp when building the game.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TypeSizes {
+    /// Size of an instance, including the object header for value types as well.
+    pub instance_size: u32,
+    pub native_size: i32,
+    pub static_fields_size: u32,
+    pub thread_static_fields_size: u32,
+}
+
+/// The types, field offsets and type sizes found in the executable, indexed like the metadata.
+#[derive(Debug, Clone, Default)]
+pub struct Il2CppBinary {
+    /// The `Il2CppType` table the `*_type_index` of the metadata refer to.
+    pub types: Vec<BinaryType>,
+    /// Offset of every field of each type definition, or an empty list for types without known offsets.
+    pub field_offsets: Vec<Vec<i32>>,
+    pub type_sizes: Vec<TypeSizes>,
+}
+
+// Offsets in Il2CppMetadataRegistration, for 64-bit executables
+const TYPES_COUNT: u64 = 0x30;
+const TYPES: u64 = 0x38;
+const FIELD_OFFSETS_COUNT: u64 = 0x50;
+const FIELD_OFFSETS: u64 = 0x58;
+const TYPE_DEFINITIONS_SIZES_COUNT: u64 = 0x60;
+const TYPE_DEFINITIONS_SIZES: u64 = 0x68;
+
+impl Il2CppBinary {
+    /// Read the type information from a relocated executable.
+    ///
+    /// The address of the metadata registration is looked up with [`find_metadata_registration`](Self::find_metadata_registration) if it is not provided.
+    pub fn load(executable: &Executable, metadata: &Metadata, registration: Option<u64>) -> Result<Self, BinaryError> {
+        let registration = registration
+            .or_else(|| Self::find_metadata_registration(executable, metadata))
+            .ok_or(BinaryError::MissingRegistration)?;
+
+        let mut reader = BinaryReader {
+            executable,
+            version: metadata.version,
+            cache: HashMap::new(),
+        };
+
+        let types_count = reader.u64(registration + TYPES_COUNT, "the type count")?;
+        let types_array = reader.u64(registration + TYPES, "the types")?;
+
+        let types = (0..types_count)
+            .map(|index| {
+                let ty = reader.u64(types_array + index * 8, "the types")?;
+                reader.read_type(ty, 0)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let field_offsets_count = reader.u64(registration + FIELD_OFFSETS_COUNT, "the field offset count")?;
+        let field_offsets_array = reader.u64(registration + FIELD_OFFSETS, "the field offsets")?;
+
+        let field_offsets = metadata
+            .type_definitions
+            .iter()
+            .enumerate()
+            .map(|(index, ty)| {
+                if index as u64 >= field_offsets_count {
+                    return Ok(Vec::new());
+                }
+
+                // Types without fields have no array at all.
+                match reader.u64(field_offsets_array + index as u64 * 8, "the field offsets")? {
+                    0 => Ok(Vec::new()),
+                    offsets => (0..ty.field_count as u64)
+                        .map(|field| reader.u32(offsets + field * 4, "the field offsets").map(|offset| offset as i32))
+                        .collect(),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let sizes_count = reader.u64(registration + TYPE_DEFINITIONS_SIZES_COUNT, "the type size count")?;
+        let sizes_array = reader.u64(registration + TYPE_DEFINITIONS_SIZES, "the type sizes")?;
+
+        let type_sizes = (0..sizes_count.min(metadata.type_definitions.len() as u64))
+            .map(|index| {
+                let sizes = reader.u64(sizes_array + index * 8, "the type sizes")?;
+
+                Ok(TypeSizes {
+                    instance_size: reader.u32(sizes, "the type sizes")?,
+                    native_size: reader.u32(sizes + 4, "the type sizes")? as i32,
+                    static_fields_size: reader.u32(sizes + 8, "the type sizes")?,
+                    thread_static_fields_size: reader.u32(sizes + 0xc, "the type sizes")?,
+                })
+            })
+            .collect::<Result<Vec<_>, BinaryError>>()?;
+
+        Ok(Self {
+            types,
+            field_offsets,
+            type_sizes,
+        })
+    }
+
+    /// Look for the metadata registration in the data of the executable.
+    ///
+    /// It is recognized by having as many field offset arrays and type sizes as there are type definitions, which is what Il2CppDumper relies on as well.
+    pub fn find_metadata_registration(executable: &Executable, metadata: &Metadata) -> Option<u64> {
+        let count = metadata.type_definitions.len() as u64;
+
+        [&executable.data, &executable.rodata].into_iter().find_map(|segment| {
+            (0..segment.data.len().saturating_sub(0x80) as u64).step_by(8).find_map(|offset| {
+                let address = segment.address + offset;
+                let read = |field: u64| executable.read_u64_at(address + field);
+
+                let matches = read(FIELD_OFFSETS_COUNT) == Some(count)
+                    && read(TYPE_DEFINITIONS_SIZES_COUNT) == Some(count)
+                    && read(TYPES_COUNT).is_some_and(|types| types != 0)
+                    && read(TYPES).is_some_and(|types| executable.segment(types).is_some());
+
+                matches.then_some(address)
+            })
+        })
+    }
+
+    pub fn get_type(&self, index: i32) -> Option<&BinaryType> {
+        usize::try_from(index).ok().and_then(|index| self.types.get(index))
+    }
+
+    pub fn get_field_offsets(&self, type_definition: usize) -> &[i32] {
+        self.field_offsets.get(type_definition).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    pub fn get_type_sizes(&self, type_definition: usize) -> Option<&TypeSizes> {
+        self.type_sizes.get(type_definition)
+    }
+}
+
+struct BinaryReader<'a> {
+    executable: &'a Executable,
+    version: MetadataVersion,
+    cache: HashMap<u64, BinaryType>,
+}
+
+impl BinaryReader<'_> {
+    fn u64(&self, address: u64, what: &'static str) -> Result<u64, BinaryError> {
+        self.executable.read_u64_at(address).ok_or(BinaryError::InvalidPointer(what, address))
+    }
+
+    fn u32(&self, address: u64, what: &'static str) -> Result<u32, BinaryError> {
+        self.executable.read_u32_at(address).ok_or(BinaryError::InvalidPointer(what, address))
+    }
+
+    fn read_type(&mut self, address: u64, depth: usize) -> Result<BinaryType, BinaryError> {
+        if let Some(ty) = self.cache.get(&address) {
+            return Ok(ty.clone());
+        }
+
+        if depth > MAX_TYPE_DEPTH {
+            return Err(BinaryError::InvalidPointer("a type nested too deeply", address));
+        }
+
+        let data = self.u64(address, "a type")?;
+        let bits = self.u32(address + 8, "a type")?;
+
+        // The amount of modifiers lost a bit in 27.2 to make room for the valuetype flag.
+        let byref_bit = if self.version.major >= 28 { 29 } else { 30 };
+
+        let attrs = bits as u16;
+        let type_enum = (bits >> 16) as u8;
+        let byref = bits & (1 << byref_bit) != 0;
+
+        let kind = match Il2CppTypeEnum::from_u8(type_enum) {
+            Some(Il2CppTypeEnum::Class) => TypeKind::Class(data as i32),
+            Some(Il2CppTypeEnum::ValueType) => TypeKind::ValueType(data as i32),
+            Some(Il2CppTypeEnum::Var) => TypeKind::Var(data as i32),
+            Some(Il2CppTypeEnum::MVar) => TypeKind::MVar(data as i32),
+            Some(Il2CppTypeEnum::SzArray) => TypeKind::SzArray(Box::new(self.read_type(data, depth + 1)?)),
+            Some(Il2CppTypeEnum::Ptr) => TypeKind::Ptr(Box::new(self.read_type(data, depth + 1)?)),
+            Some(Il2CppTypeEnum::Array) => {
+                let element = self.u64(data, "an array type")?;
+                let rank = self.u32(data + 8, "an array type")? as u8;
+                TypeKind::Array(Box::new(self.read_type(element, depth + 1)?), rank)
+            },
+            Some(Il2CppTypeEnum::GenericInst) => {
+                // Before 27, the generic class starts with the index of the definition rather than a pointer to its type.
+                let definition = if self.version.major >= 27 {
+                    let ty = self.u64(data, "a generic class")?;
+
+                    match self.read_type(ty, depth + 1)?.kind {
+                        TypeKind::Class(index) | TypeKind::ValueType(index) => index,
+                        _ => -1,
+                    }
+                } else {
+                    self.u32(data, "a generic class")? as i32
+                };
+
+                let inst = self.u64(data + 8, "a generic class")?;
+                let argc = self.u32(inst, "a generic instance")? as u64;
+                let argv = self.u64(inst + 8, "a generic instance")?;
+
+                let arguments = (0..argc)
+                    .map(|index| {
+                        let argument = self.u64(argv + index * 8, "a generic instance")?;
+                        self.read_type(argument, depth + 1)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                TypeKind::GenericInst { definition, arguments }
+            },
+            Some(
+                builtin @ (Il2CppTypeEnum::Void
+                | Il2CppTypeEnum::String
+                | Il2CppTypeEnum::Object
+                | Il2CppTypeEnum::FnPtr
+                | Il2CppTypeEnum::TypedByRef),
+            ) => TypeKind::Builtin(builtin),
+            Some(builtin) if builtin.is_primitive() => TypeKind::Builtin(builtin),
+            _ => TypeKind::Other(type_enum),
+        };
+
+        let ty = BinaryType { attrs, byref, kind };
+        self.cache.insert(address, ty.clone());
+
+        Ok(ty)
+    }
+}
@@ -0,0 +1,1091 @@
+//! Generate Rust bindings for the types of a game from its metadata and executable.
+//!
+//! Every type selected gets a `#[unity::class]` structure with its instance fields laid out at the offsets Il2Cpp computed, including the ones it inherits.
+//! Static fields get a separate structure registered through `#[static_fields]`, and methods are declared with `#[unity::from_offset]`.
+//!
+//! The output is meant to be saved as a module of the mod, such as `src/bindings.rs`, and is not meant to be edited by hand.
+//!
+//! Example:
+//!
+//! ```no_run
+//! # use unity_core::{binary::Il2CppBinary, bindgen::BindingGenerator, metadata::Metadata};
+//! # fn main() -> std::io::Result<()> {
+//! # let (metadata, binary): (Metadata, Il2CppBinary) = unimplemented!();
+//! let bindings = BindingGenerator::new(&metadata, &binary)
+//!     .image("Assembly-CSharp")
+//!     .namespace("App")
+//!     .generate();
+//!
+//! std::fs::write("src/bindings.rs", bindings)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Some types cannot be expressed and are worked around:
+//! * Generic type definitions and nested types are not generated. References to them use `Il2CppObject<()>`, and embedded value types become opaque bytes.
+//! * Generic methods, and methods using types that cannot be represented, are skipped.
+//! * Overloads sharing a name and an amount of parameters are looked up by the types of their parameters. When those cannot be named, only the first overload is declared.
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt::Write,
+};
+
+use crate::{
+    binary::{BinaryType, Il2CppBinary, TypeKind},
+    metadata::{Metadata, TypeDefinition},
+    types::Il2CppTypeEnum,
+};
+
+/// Size of the header of every object, which field offsets include even for value types.
+const OBJECT_HEADER_SIZE: usize = 0x10;
+
+const FIELD_ATTRIBUTE_STATIC: u16 = 0x10;
+const FIELD_ATTRIBUTE_LITERAL: u16 = 0x40;
+const TYPE_ATTRIBUTE_INTERFACE: u32 = 0x20;
+const METHOD_ATTRIBUTE_ABSTRACT: u16 = 0x400;
+const MAX_NESTING: usize = 32;
+
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for",
+    "gen", "if", "impl", "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "static", "struct", "trait",
+    "true", "try", "type", "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Builds Rust source for the types of a [`Metadata`], using the offsets and types of the matching [`Il2CppBinary`].
+pub struct BindingGenerator<'a> {
+    metadata: &'a Metadata,
+    binary: &'a Il2CppBinary,
+    crate_path: String,
+    namespaces: Vec<String>,
+    images: Vec<String>,
+}
+
+impl<'a> BindingGenerator<'a> {
+    pub fn new(metadata: &'a Metadata, binary: &'a Il2CppBinary) -> Self {
+        Self {
+            metadata,
+            binary,
+            crate_path: String::from("unity"),
+            namespaces: Vec::new(),
+            images: Vec::new(),
+        }
+    }
+
+    /// Path to this crate in the generated code, `unity` by default.
+    pub fn crate_path(mut self, path: impl Into<String>) -> Self {
+        self.crate_path = path.into();
+        self
+    }
+
+    /// Only generate the types of this namespace and the ones nested in it. Can be called more than once.
+    pub fn namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespaces.push(namespace.into());
+        self
+    }
+
+    /// Only generate the types of this image, with or without its `.dll` extension. Can be called more than once.
+    pub fn image(mut self, image: impl Into<String>) -> Self {
+        self.images.push(image.into());
+        self
+    }
+
+    /// Generate the bindings for every type selected by the filters, or every type if there are none.
+    pub fn generate(&self) -> String {
+        let mut generator = Generator::new(self);
+        generator.generate();
+
+        let mut output = String::new();
+
+        writeln!(
+            output,
+            "//! Bindings generated by unity-bindgen from metadata version {}.{}. Do not edit by hand.",
+            self.metadata.version.major, self.metadata.version.minor
+        )
+        .unwrap();
+        writeln!(output).unwrap();
+        writeln!(output, "#![allow(dead_code, non_camel_case_types, non_snake_case, clippy::all)]").unwrap();
+
+        generator.render(&mut output, &[], 0);
+
+        output
+    }
+
+    fn is_selected(&self, ty: &TypeDefinition) -> bool {
+        let name = self.metadata.string(ty.name_index).unwrap_or_default();
+        let namespace = self.metadata.string(ty.namespace_index).unwrap_or_default();
+
+        // Compiler-generated types, such as `<Module>` or the state machines of coroutines, have unspeakable names.
+        if name.is_empty() || name.contains(['<', '>', '=']) {
+            return false;
+        }
+
+        if ty.declaring_type_index != -1 || ty.is_generic() || ty.flags & TYPE_ATTRIBUTE_INTERFACE != 0 {
+            return false;
+        }
+
+        self.namespaces.is_empty()
+            || self.namespaces.iter().any(|filter| {
+                namespace == filter || namespace.strip_prefix(filter.as_str()).is_some_and(|rest| rest.starts_with('.'))
+            })
+    }
+}
+
+/// Where a generated type lives.
+struct Item {
+    module: Vec<String>,
+    name: String,
+}
+
+/// A Rust type standing for an Il2Cpp one, along with its layout.
+#[derive(Clone)]
+struct Mapped {
+    ty: String,
+    size: usize,
+    align: usize,
+}
+
+impl Mapped {
+    fn new(ty: impl Into<String>, size: usize) -> Self {
+        Self { ty: ty.into(), size, align: size }
+    }
+
+    fn opaque(size: usize) -> Self {
+        Self { ty: format!("[u8; {:#x}]", size), size, align: 1 }
+    }
+}
+
+/// The body of a generated structure.
+#[derive(Clone)]
+struct Layout {
+    lines: Vec<String>,
+    size: usize,
+    align: usize,
+}
+
+struct Generator<'a> {
+    options: &'a BindingGenerator<'a>,
+    metadata: &'a Metadata,
+    binary: &'a Il2CppBinary,
+    items: HashMap<usize, Item>,
+    /// Generated code, by module.
+    modules: BTreeMap<Vec<String>, Vec<String>>,
+    /// Names already used in each module, to avoid collisions between types, functions and submodules.
+    names: HashMap<Vec<String>, HashSet<String>>,
+    layouts: RefCell<HashMap<usize, Option<Layout>>>,
+}
+
+impl<'a> Generator<'a> {
+    fn new(options: &'a BindingGenerator<'a>) -> Self {
+        let metadata = options.metadata;
+
+        let mut generator = Self {
+            options,
+            metadata,
+            binary: options.binary,
+            items: HashMap::new(),
+            modules: BTreeMap::new(),
+            names: HashMap::new(),
+            layouts: RefCell::new(HashMap::new()),
+        };
+
+        let images = metadata.images.iter().filter(|image| {
+            options.images.is_empty()
+                || options.images.iter().any(|name| {
+                    let image_name = metadata.string(image.name_index).unwrap_or_default();
+                    image_name == name || image_name.strip_suffix(".dll") == Some(name)
+                })
+        });
+
+        for image in images {
+            for offset in 0..image.type_count as usize {
+                let index = image.type_start as usize + offset;
+
+                let Some(ty) = metadata.type_definitions.get(index) else { continue };
+
+                if !options.is_selected(ty) {
+                    continue;
+                }
+
+                let module = metadata
+                    .string(ty.namespace_index)
+                    .unwrap_or_default()
+                    .split('.')
+                    .filter(|part| !part.is_empty())
+                    .map(|part| escape(&snake_case(part)))
+                    .collect::<Vec<_>>();
+
+                // Reserve the submodules in their parents, so that functions do not take their names.
+                for depth in 0..module.len() {
+                    generator.names.entry(module[..depth].to_vec()).or_default().insert(module[depth].clone());
+                }
+
+                let name = type_name(metadata.string(ty.name_index).unwrap_or_default(), index);
+                let names = generator.names.entry(module.clone()).or_default();
+
+                let name = unique(names, &name, |name, counter| format!("{}_{}", name, counter), |name| {
+                    [name.to_string(), format!("{}Fields", name), format!("{}StaticFields", name)]
+                });
+
+                generator.items.insert(index, Item { module, name });
+            }
+        }
+
+        generator
+    }
+
+    fn generate(&mut self) {
+        let mut indices = self.items.keys().copied().collect::<Vec<_>>();
+        indices.sort_unstable();
+
+        for index in indices {
+            let ty = &self.metadata.type_definitions[index];
+            let code = if ty.is_enum() { self.generate_enum(index) } else { self.generate_class(index) };
+
+            let module = self.items[&index].module.clone();
+
+            self.modules.entry(module).or_default().push(code);
+        }
+
+        // Make sure every parent module exists, even when it has no type of its own.
+        let modules = self.modules.keys().cloned().collect::<Vec<_>>();
+
+        for module in modules {
+            for depth in 0..module.len() {
+                self.modules.entry(module[..depth].to_vec()).or_default();
+            }
+        }
+    }
+
+    fn render(&self, output: &mut String, module: &[String], depth: usize) {
+        let indent = "    ".repeat(depth);
+
+        // Separate every item from the previous one, except right after the opening of a module.
+        let mut first = depth != 0;
+        let mut separate = |output: &mut String| {
+            if !std::mem::take(&mut first) {
+                writeln!(output).unwrap();
+            }
+        };
+
+        for code in self.modules.get(module).into_iter().flatten() {
+            separate(output);
+
+            for line in code.lines() {
+                match line {
+                    "" => writeln!(output).unwrap(),
+                    line => writeln!(output, "{}{}", indent, line).unwrap(),
+                }
+            }
+        }
+
+        let children = self
+            .modules
+            .keys()
+            .filter(|child| child.len() == module.len() + 1 && child.starts_with(module))
+            .collect::<Vec<_>>();
+
+        for child in children {
+            separate(output);
+            writeln!(output, "{}pub mod {} {{", indent, child[module.len()]).unwrap();
+            self.render(output, child, depth + 1);
+            writeln!(output, "{}}}", indent).unwrap();
+        }
+    }
+
+    fn generate_enum(&self, index: usize) -> String {
+        let ty = &self.metadata.type_definitions[index];
+        let item = &self.items[&index];
+
+        let underlying = self.enum_underlying(ty).map(|mapped| mapped.ty).unwrap_or_else(|| String::from("i32"));
+
+        let mut code = String::new();
+        writeln!(code, "// {}", self.metadata.type_name(ty)).unwrap();
+        writeln!(code, "#[repr(transparent)]").unwrap();
+        writeln!(code, "#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]").unwrap();
+        writeln!(code, "pub struct {}(pub {});", item.name, underlying).unwrap();
+
+        code
+    }
+
+    fn generate_class(&mut self, index: usize) -> String {
+        let metadata = self.metadata;
+        let ty = &metadata.type_definitions[index];
+        let item = &self.items[&index];
+        let module = item.module.clone();
+        let name = item.name.clone();
+
+        let namespace = metadata.string(ty.namespace_index).unwrap_or_default();
+        let class_name = metadata.string(ty.name_index).unwrap_or_default();
+
+        let mut code = String::new();
+
+        match self.binary.get_type_sizes(index) {
+            Some(sizes) => writeln!(code, "// {} ({:#x} bytes)", metadata.type_name(ty), sizes.instance_size).unwrap(),
+            None => writeln!(code, "// {}", metadata.type_name(ty)).unwrap(),
+        }
+
+        let statics = self.static_layout(index, &module);
+
+        writeln!(code, "#[{}::class({:?}, {:?})]", self.options.crate_path, namespace, class_name).unwrap();
+
+        if statics.is_some() {
+            writeln!(code, "#[static_fields({}StaticFields)]", name).unwrap();
+        }
+
+        let layout = self.instance_layout(index).unwrap_or_else(|| Layout { lines: Vec::new(), size: 0, align: 1 });
+        write_struct(&mut code, &name, &layout);
+
+        if let Some(statics) = statics {
+            writeln!(code).unwrap();
+            writeln!(code, "#[repr(C)]").unwrap();
+            write_struct(&mut code, &format!("{}StaticFields", name), &statics);
+        }
+
+        let methods = metadata
+            .methods_of(ty)
+            .iter()
+            .map(|method| (metadata.string(method.name_index).unwrap_or_default(), method))
+            .filter(|(method_name, method)| {
+                method.generic_container_index == -1 && method.flags & METHOD_ATTRIBUTE_ABSTRACT == 0 && *method_name != ".cctor"
+            })
+            .collect::<Vec<_>>();
+
+        let mut overloads = HashMap::new();
+
+        for (method_name, method) in &methods {
+            *overloads.entry((*method_name, method.parameter_count)).or_insert(0) += 1;
+        }
+
+        let mut declared = HashSet::new();
+
+        for (method_name, method) in methods {
+            let key = (method_name, method.parameter_count);
+
+            // The runtime looks methods up by name and amount of parameters, so overloads sharing both are told apart by the types of their parameters.
+            // Without a signature, only the first of them can be reached.
+            let lookup = match overloads[&key] {
+                1 => String::new(),
+                _ => match self.method_lookup_signature(method) {
+                    Some(signature) => format!(", {:?}", signature),
+                    None if !declared.contains(&key) => String::new(),
+                    None => continue,
+                },
+            };
+
+            let Some(signature) = self.method_signature(index, method) else { continue };
+
+            declared.insert(key);
+
+            let names = self.names.entry(module.clone()).or_default();
+            let base = format!("{}_{}", snake_case(&name), snake_case(method_name));
+
+            let function = if names.contains(&escape(&base)) {
+                unique(names, &format!("{}_{}", base, method.parameter_count), |name, counter| format!("{}_{}", name, counter), |name| {
+                    [escape(name)]
+                })
+            } else {
+                unique(names, &base, |name, counter| format!("{}_{}", name, counter), |name| [escape(name)])
+            };
+
+            writeln!(code).unwrap();
+            writeln!(
+                code,
+                "#[{}::from_offset({:?}, {:?}, {:?}{})]",
+                self.options.crate_path, namespace, class_name, method_name, lookup
+            )
+            .unwrap();
+            writeln!(code, "pub fn {}{};", escape(&function), signature).unwrap();
+        }
+
+        code
+    }
+
+    /// The parameters and return type of a method, or `None` if one of them cannot be represented.
+    fn method_signature(&self, index: usize, method: &crate::metadata::MethodDefinition) -> Option<String> {
+        let ty = &self.metadata.type_definitions[index];
+        let item = &self.items[&index];
+
+        let mut arguments = Vec::new();
+        let mut used = HashSet::new();
+
+        if !method.is_static() {
+            if ty.is_valuetype() {
+                arguments.push(format!("this: &mut {}Fields", item.name));
+            } else {
+                arguments.push(format!("this: &mut {}", item.name));
+            }
+        }
+
+        for (position, parameter) in self.metadata.parameters_of(method).iter().enumerate() {
+            let parameter_type = self.binary.get_type(parameter.type_index)?;
+            let mapped = self.map_type(parameter_type, &item.module, true)?;
+
+            let name = match snake_case(self.metadata.string(parameter.name_index).unwrap_or_default()) {
+                name if name.is_empty() || name == "this" || name == "method_info" => format!("arg{}", position),
+                name => name,
+            };
+
+            let name = unique(&mut used, &name, |name, counter| format!("{}_{}", name, counter), |name| [name.to_string()]);
+
+            arguments.push(format!("{}: {}", escape(&name), mapped.ty));
+        }
+
+        arguments.push(format!("method_info: {}::il2cpp::method::OptionalMethod", self.options.crate_path));
+
+        let return_type = self.binary.get_type(method.return_type)?;
+
+        let output = match return_type.kind {
+            TypeKind::Builtin(Il2CppTypeEnum::Void) if !return_type.byref => String::new(),
+            _ => format!(" -> {}", self.map_type(return_type, &item.module, false)?.ty),
+        };
+
+        Some(format!("({}){}", arguments.join(", "), output))
+    }
+
+    /// The types of the parameters of a method, written like `MethodInfo::get_signature` does, such as `(System.String,System.Int32&)`.
+    fn method_lookup_signature(&self, method: &crate::metadata::MethodDefinition) -> Option<String> {
+        let parameters = self
+            .metadata
+            .parameters_of(method)
+            .iter()
+            .map(|parameter| self.runtime_type_name(self.binary.get_type(parameter.type_index)?))
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(format!("({})", parameters.join(",")))
+    }
+
+    /// Name of a type the way the runtime formats it, such as `System.Byte[]` or `App.Unit/Skill`.
+    fn runtime_type_name(&self, ty: &BinaryType) -> Option<String> {
+        let mut name = match &ty.kind {
+            TypeKind::Builtin(builtin) => builtin_name(*builtin)?.to_string(),
+            TypeKind::Class(index) | TypeKind::ValueType(index) => self.definition_name(*index)?,
+            TypeKind::SzArray(element) => format!("{}[]", self.runtime_type_name(element)?),
+            TypeKind::Array(element, rank) => format!("{}[{}]", self.runtime_type_name(element)?, ",".repeat(rank.saturating_sub(1) as usize)),
+            TypeKind::Ptr(element) => format!("{}*", self.runtime_type_name(element)?),
+            TypeKind::GenericInst { definition, arguments } => {
+                let arguments = arguments.iter().map(|argument| self.runtime_type_name(argument)).collect::<Option<Vec<_>>>()?;
+                format!("{}<{}>", self.definition_name(*definition)?, arguments.join(","))
+            },
+            // Generic parameters are named after the method being looked up, which generic methods are not generated for anyway.
+            TypeKind::Var(_) | TypeKind::MVar(_) | TypeKind::Other(_) => return None,
+        };
+
+        if ty.byref {
+            name.push('&');
+        }
+
+        Some(name)
+    }
+
+    /// Full name of a type definition without its arity, prefixed by its declaring types for nested types.
+    fn definition_name(&self, index: i32) -> Option<String> {
+        let mut name = String::new();
+        let mut current = index;
+
+        // Declaring types are not expected to go this deep, but a corrupted binary could loop.
+        for _ in 0..MAX_NESTING {
+            let ty = self.metadata.type_definitions.get(usize::try_from(current).ok()?)?;
+            let short = self.metadata.string(ty.name_index).ok()?.split('`').next().unwrap_or_default();
+
+            name = match name.is_empty() {
+                true => short.to_string(),
+                false => format!("{}/{}", short, name),
+            };
+
+            match self.binary.get_type(ty.declaring_type_index).map(|declaring| &declaring.kind) {
+                Some(TypeKind::Class(declaring) | TypeKind::ValueType(declaring)) => current = *declaring,
+                _ => {
+                    return match self.metadata.string(ty.namespace_index).ok()? {
+                        "" => Some(name),
+                        namespace => Some(format!("{}.{}", namespace, name)),
+                    }
+                },
+            }
+        }
+
+        None
+    }
+
+    /// Layout of the instance fields of a type, including the ones it inherits, without the object header.
+    fn instance_layout(&self, index: usize) -> Option<Layout> {
+        if let Some(layout) = self.layouts.borrow().get(&index) {
+            return layout.clone();
+        }
+
+        // Value types cannot contain themselves, but a corrupted binary could claim otherwise.
+        self.layouts.borrow_mut().insert(index, None);
+
+        let metadata = self.metadata;
+        let ty = &metadata.type_definitions[index];
+        let module = self.items.get(&index).map(|item| item.module.clone()).unwrap_or_default();
+
+        let mut chain = vec![index];
+
+        if !ty.is_valuetype() {
+            let mut current = ty;
+
+            while let Some(TypeKind::Class(parent)) = self.binary.get_type(current.parent_index).map(|parent| &parent.kind) {
+                let Some(parent_type) = metadata.type_definitions.get(*parent as usize) else { break };
+
+                // System.Object has no field.
+                if parent_type.parent_index == -1 || chain.contains(&(*parent as usize)) {
+                    break;
+                }
+
+                chain.push(*parent as usize);
+                current = parent_type;
+            }
+        }
+
+        let mut fields = Vec::new();
+
+        for &definition in chain.iter().rev() {
+            let offsets = self.binary.get_field_offsets(definition);
+
+            for (field, offset) in metadata.fields_of(&metadata.type_definitions[definition]).iter().zip(offsets) {
+                let Some(field_type) = self.binary.get_type(field.type_index) else { continue };
+
+                if field_type.attrs & (FIELD_ATTRIBUTE_STATIC | FIELD_ATTRIBUTE_LITERAL) != 0 || (*offset as usize) < OBJECT_HEADER_SIZE {
+                    continue;
+                }
+
+                let name = field_name(metadata.string(field.name_index).unwrap_or_default());
+                let mapped = self.map_type(field_type, &module, false);
+
+                fields.push((*offset as usize - OBJECT_HEADER_SIZE, name, mapped));
+            }
+        }
+
+        let size = self
+            .binary
+            .get_type_sizes(index)
+            .map(|sizes| (sizes.instance_size as usize).saturating_sub(OBJECT_HEADER_SIZE));
+
+        let layout = Some(build_layout(fields, size, OBJECT_HEADER_SIZE));
+        self.layouts.borrow_mut().insert(index, layout.clone());
+
+        layout
+    }
+
+    /// Layout of the static fields of a type, or `None` if it has none worth generating.
+    fn static_layout(&self, index: usize, module: &[String]) -> Option<Layout> {
+        let metadata = self.metadata;
+        let ty = &metadata.type_definitions[index];
+        let offsets = self.binary.get_field_offsets(index);
+
+        let mut fields = Vec::new();
+
+        for (field, offset) in metadata.fields_of(ty).iter().zip(offsets) {
+            let Some(field_type) = self.binary.get_type(field.type_index) else { continue };
+
+            // Constants are not stored anywhere, and thread static fields live in a separate storage.
+            if field_type.attrs & FIELD_ATTRIBUTE_STATIC == 0 || field_type.attrs & FIELD_ATTRIBUTE_LITERAL != 0 || *offset < 0 {
+                continue;
+            }
+
+            let name = field_name(metadata.string(field.name_index).unwrap_or_default());
+            fields.push((*offset as usize, name, self.map_type(field_type, module, false)));
+        }
+
+        if fields.is_empty() {
+            return None;
+        }
+
+        let size = self.binary.get_type_sizes(index).map(|sizes| sizes.static_fields_size as usize);
+
+        Some(build_layout(fields, size, 0))
+    }
+
+    fn enum_underlying(&self, ty: &TypeDefinition) -> Option<Mapped> {
+        match self.binary.get_type(ty.element_type_index)?.kind {
+            TypeKind::Builtin(builtin) if builtin.is_primitive() => primitive(builtin),
+            _ => None,
+        }
+    }
+
+    /// Rust type to use for an Il2Cpp type, as seen from `module`.
+    ///
+    /// Parameters take references without a `'static` lifetime, while fields and return values keep it.
+    fn map_type(&self, ty: &BinaryType, module: &[String], parameter: bool) -> Option<Mapped> {
+        let by_value = self.map_value(ty, module, parameter)?;
+
+        if ty.byref {
+            return Some(Mapped::new(format!("&mut {}", by_value.ty), 8));
+        }
+
+        Some(by_value)
+    }
+
+    fn map_value(&self, ty: &BinaryType, module: &[String], parameter: bool) -> Option<Mapped> {
+        let krate = &self.options.crate_path;
+        let lifetime = if parameter { "" } else { "'static " };
+        let reference = |target: String| Mapped::new(format!("Option<&{}mut {}>", lifetime, target), 8);
+        let object = || format!("{}::il2cpp::object::Il2CppObject<()>", krate);
+
+        match &ty.kind {
+            TypeKind::Builtin(Il2CppTypeEnum::String) => Some(reference(format!("{}::system::Il2CppString", krate))),
+            TypeKind::Builtin(Il2CppTypeEnum::Object) => Some(reference(object())),
+            TypeKind::Builtin(Il2CppTypeEnum::FnPtr) => Some(Mapped::new("*const u8", 8)),
+            TypeKind::Builtin(builtin) => primitive(*builtin),
+            TypeKind::Ptr(_) => Some(Mapped::new("*mut u8", 8)),
+            TypeKind::Class(index) => match self.items.get(&(*index as usize)) {
+                Some(item) => Some(reference(self.path(module, item, ""))),
+                None => Some(reference(object())),
+            },
+            TypeKind::ValueType(index) => self.map_value_type(*index as usize, module, parameter),
+            TypeKind::SzArray(element) => {
+                let element = self.map_type(element, module, false)?;
+                Some(reference(format!("{}::il2cpp::object::Il2CppArray<{}>", krate, element.ty)))
+            },
+            TypeKind::Array(..) => Some(reference(object())),
+            TypeKind::GenericInst { definition, arguments } => {
+                let definition = self.metadata.type_definitions.get(*definition as usize)?;
+
+                if definition.is_valuetype() {
+                    return None;
+                }
+
+                // Lists of references are common enough to deserve their proper type.
+                if self.metadata.type_name(definition) == "System.Collections.Generic.List`1" {
+                    if let [argument] = arguments.as_slice() {
+                        if let Some(target) = self.reference_target(argument, module) {
+                            return Some(reference(format!("{}::system::List<{}>", krate, target)));
+                        }
+                    }
+                }
+
+                Some(reference(object()))
+            },
+            TypeKind::Var(_) | TypeKind::MVar(_) | TypeKind::Other(_) => None,
+        }
+    }
+
+    fn map_value_type(&self, index: usize, module: &[String], parameter: bool) -> Option<Mapped> {
+        let ty = self.metadata.type_definitions.get(index)?;
+
+        if ty.is_enum() {
+            let underlying = self.enum_underlying(ty)?;
+
+            return match self.items.get(&index) {
+                Some(item) => Some(Mapped { ty: self.path(module, item, ""), ..underlying }),
+                None => Some(underlying),
+            };
+        }
+
+        match self.items.get(&index) {
+            Some(item) => {
+                let layout = self.instance_layout(index)?;
+
+                Some(Mapped {
+                    ty: self.path(module, item, "Fields"),
+                    size: layout.size,
+                    align: layout.align,
+                })
+            },
+            // Bytes are not passed the same way as structures, so they are only good enough for fields.
+            None if !parameter => {
+                let sizes = self.binary.get_type_sizes(index)?;
+                Some(Mapped::opaque((sizes.instance_size as usize).checked_sub(OBJECT_HEADER_SIZE)?))
+            },
+            None => None,
+        }
+    }
+
+    /// The type a reference points to, for the generic arguments of containers.
+    fn reference_target(&self, ty: &BinaryType, module: &[String]) -> Option<String> {
+        let krate = &self.options.crate_path;
+
+        match &ty.kind {
+            TypeKind::Builtin(Il2CppTypeEnum::String) => Some(format!("{}::system::Il2CppString", krate)),
+            TypeKind::Builtin(Il2CppTypeEnum::Object) => Some(format!("{}::il2cpp::object::Il2CppObject<()>", krate)),
+            TypeKind::Class(index) => match self.items.get(&(*index as usize)) {
+                Some(item) => Some(self.path(module, item, "")),
+                None => Some(format!("{}::il2cpp::object::Il2CppObject<()>", krate)),
+            },
+            _ => None,
+        }
+    }
+
+    /// Relative path from `module` to a generated type.
+    fn path(&self, module: &[String], item: &Item, suffix: &str) -> String {
+        let common = module.iter().zip(&item.module).take_while(|(a, b)| a == b).count();
+
+        let mut path = vec![String::from("super"); module.len() - common];
+        path.extend(item.module[common..].iter().cloned());
+        path.push(format!("{}{}", item.name, suffix));
+
+        path.join("::")
+    }
+}
+
+fn primitive(builtin: Il2CppTypeEnum) -> Option<Mapped> {
+    use Il2CppTypeEnum::*;
+
+    let ty = match builtin {
+        Boolean => "bool",
+        Char => "u16",
+        I1 => "i8",
+        U1 => "u8",
+        I2 => "i16",
+        U2 => "u16",
+        I4 => "i32",
+        U4 => "u32",
+        I8 => "i64",
+        U8 => "u64",
+        R4 => "f32",
+        R8 => "f64",
+        I => "isize",
+        U => "usize",
+        _ => return None,
+    };
+
+    Some(Mapped::new(ty, builtin.size()?))
+}
+
+/// Full name of the class the runtime uses for a built-in type.
+fn builtin_name(builtin: Il2CppTypeEnum) -> Option<&'static str> {
+    use Il2CppTypeEnum::*;
+
+    let name = match builtin {
+        Void => "System.Void",
+        Boolean => "System.Boolean",
+        Char => "System.Char",
+        I1 => "System.SByte",
+        U1 => "System.Byte",
+        I2 => "System.Int16",
+        U2 => "System.UInt16",
+        I4 => "System.Int32",
+        U4 => "System.UInt32",
+        I8 => "System.Int64",
+        U8 => "System.UInt64",
+        R4 => "System.Single",
+        R8 => "System.Double",
+        String => "System.String",
+        Object => "System.Object",
+        I | FnPtr => "System.IntPtr",
+        U => "System.UIntPtr",
+        TypedByRef => "System.TypedReference",
+        _ => return None,
+    };
+
+    Some(name)
+}
+
+/// Lay fields out in offset order, filling the gaps with padding.
+///
+/// Fields overlapping a previous one (explicit layouts) are dropped, and fields Rust would not place at their offset become bytes.
+fn build_layout(mut fields: Vec<(usize, String, Option<Mapped>)>, size: Option<usize>, base: usize) -> Layout {
+    fields.sort_by_key(|(offset, ..)| *offset);
+
+    let mut lines = Vec::new();
+    let mut names = HashSet::new();
+    let mut cursor = 0;
+    let mut align = 1;
+
+    for (offset, name, mapped) in fields {
+        let Some(mapped) = mapped else { continue };
+
+        if offset < cursor {
+            continue;
+        }
+
+        let mapped = if offset % mapped.align != 0 { Mapped::opaque(mapped.size) } else { mapped };
+
+        if offset > cursor {
+            lines.push(format!("__pad_{:#x}: [u8; {:#x}],", base + cursor, offset - cursor));
+        }
+
+        let name = unique(&mut names, &name, |name, counter| format!("{}_{}", name, counter), |name| [name.to_string()]);
+
+        lines.push(format!("pub {}: {}, // {:#x}", escape(&name), mapped.ty, base + offset));
+
+        cursor = offset + mapped.size;
+        align = align.max(mapped.align);
+    }
+
+    if let Some(size) = size {
+        if size > cursor {
+            lines.push(format!("__pad_{:#x}: [u8; {:#x}],", base + cursor, size - cursor));
+            cursor = size;
+        }
+    }
+
+    Layout {
+        lines,
+        size: cursor.div_ceil(align) * align,
+        align,
+    }
+}
+
+fn write_struct(code: &mut String, name: &str, layout: &Layout) {
+    if layout.lines.is_empty() {
+        writeln!(code, "pub struct {} {{}}", name).unwrap();
+        return;
+    }
+
+    writeln!(code, "pub struct {} {{", name).unwrap();
+
+    for line in &layout.lines {
+        writeln!(code, "    {}", line).unwrap();
+    }
+
+    writeln!(code, "}}").unwrap();
+}
+
+/// Reserve a name that does not collide with the ones in `used`, by adding a counter to it if needed.
+///
+/// `reserved` lists every name a candidate would take, such as the `Fields` structure of a class.
+fn unique<const N: usize>(
+    used: &mut HashSet<String>,
+    name: &str,
+    counter: impl Fn(&str, usize) -> String,
+    reserved: impl Fn(&str) -> [String; N],
+) -> String {
+    let mut candidate = name.to_string();
+    let mut count = 1;
+
+    while reserved(&candidate).iter().any(|name| used.contains(name)) {
+        count += 1;
+        candidate = counter(name, count);
+    }
+
+    used.extend(reserved(&candidate));
+
+    candidate
+}
+
+/// Name of a field, using the property name for auto-implemented properties.
+fn field_name(name: &str) -> String {
+    let name = name
+        .strip_prefix('<')
+        .and_then(|name| name.strip_suffix(">k__BackingField"))
+        .unwrap_or(name);
+
+    match snake_case(name) {
+        name if name.is_empty() => String::from("field"),
+        name => name,
+    }
+}
+
+/// Turn a C# type name into a Rust one, keeping its case but replacing what Rust does not accept.
+fn type_name(name: &str, index: usize) -> String {
+    let mut result = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect::<String>()
+        .trim_matches('_')
+        .to_string();
+
+    if result.is_empty() {
+        result = format!("Type{}", index);
+    }
+
+    if result.starts_with(|c: char| c.is_ascii_digit()) {
+        result.insert(0, '_');
+    }
+
+    // `Option` is used by the generated fields, so a type of the game cannot shadow it.
+    if result == "Self" || result == "Option" || KEYWORDS.contains(&result.as_str()) {
+        result.push('_');
+    }
+
+    result
+}
+
+/// Convert a name from PascalCase or camelCase to snake_case, dropping what Rust does not accept.
+fn snake_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut result = String::new();
+
+    for (index, c) in chars.iter().enumerate() {
+        if !c.is_ascii_alphanumeric() {
+            if !result.is_empty() && !result.ends_with('_') {
+                result.push('_');
+            }
+
+            continue;
+        }
+
+        if c.is_ascii_uppercase() && !result.is_empty() && !result.ends_with('_') {
+            let previous = chars[index - 1];
+            let next = chars.get(index + 1);
+
+            // Split "getHP" into "get_hp" and "HPValue" into "hp_value".
+            if previous.is_ascii_lowercase() || previous.is_ascii_digit() || (previous.is_ascii_uppercase() && next.is_some_and(|c| c.is_ascii_lowercase())) {
+                result.push('_');
+            }
+        }
+
+        result.push(c.to_ascii_lowercase());
+    }
+
+    let mut result = result.trim_end_matches('_').to_string();
+
+    if result.starts_with(|c: char| c.is_ascii_digit()) {
+        result.insert(0, 'n');
+    }
+
+    result
+}
+
+/// Make an identifier usable, as a raw identifier for keywords or with a suffix for the ones that cannot be raw.
+fn escape(name: &str) -> String {
+    match name {
+        "self" | "super" | "crate" | "Self" | "_" => format!("{}_", name),
+        name if KEYWORDS.contains(&name) => format!("r#{}", name),
+        name => name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        binary::TypeSizes,
+        metadata::{
+            fixture::{self, MethodFixture, TypeFixture},
+            MetadataVersion,
+        },
+    };
+
+    fn method(name: &'static str, return_type: i32, flags: u16, parameters: Vec<(&'static str, i32)>) -> MethodFixture {
+        MethodFixture {
+            name,
+            return_type,
+            flags,
+            parameters,
+            ..Default::default()
+        }
+    }
+
+    fn generate() -> String {
+        let types = [
+            TypeFixture {
+                namespace: "System",
+                name: "Object",
+                parent_type: -1,
+                ..Default::default()
+            },
+            TypeFixture {
+                namespace: "App",
+                name: "Unit",
+                fields: vec![("hp", 3), ("<Name>k__BackingField", 4), ("count", 7)],
+                methods: vec![
+                    method("GetHp", 2, 0x86, vec![]),
+                    method("Add", 5, 0x86, vec![("value", 2)]),
+                    method("Add", 5, 0x86, vec![("name", 9)]),
+                    method("Add", 5, 0x86, vec![("units", 6)]),
+                    method("Add", 5, 0x86, vec![("result", 8)]),
+                    method("Create", 1, 0x96, vec![("kind", 10)]),
+                ],
+                ..Default::default()
+            },
+            TypeFixture {
+                namespace: "App",
+                name: "Kind",
+                parent_type: -1,
+                element_type: 2,
+                bitfield: 0x3,
+                fields: vec![("value__", 3)],
+                ..Default::default()
+            },
+        ];
+
+        let metadata = Metadata::parse(fixture::build(MetadataVersion::new(29, 0), "Assembly-CSharp.dll", &types, &[])).unwrap();
+
+        let field = |attrs, kind| BinaryType { attrs, byref: false, kind };
+
+        let binary = Il2CppBinary {
+            types: vec![
+                BinaryType::new(TypeKind::Class(0)),
+                BinaryType::new(TypeKind::Class(1)),
+                BinaryType::new(TypeKind::Builtin(Il2CppTypeEnum::I4)),
+                field(0x1, TypeKind::Builtin(Il2CppTypeEnum::I4)),
+                field(0x1, TypeKind::Builtin(Il2CppTypeEnum::String)),
+                BinaryType::new(TypeKind::Builtin(Il2CppTypeEnum::Void)),
+                BinaryType::new(TypeKind::SzArray(Box::new(BinaryType::new(TypeKind::Class(1))))),
+                field(0x11, TypeKind::Builtin(Il2CppTypeEnum::I4)),
+                BinaryType {
+                    attrs: 0,
+                    byref: true,
+                    kind: TypeKind::Builtin(Il2CppTypeEnum::I4),
+                },
+                BinaryType::new(TypeKind::Builtin(Il2CppTypeEnum::String)),
+                BinaryType::new(TypeKind::ValueType(2)),
+            ],
+            field_offsets: vec![vec![], vec![0x10, 0x18, 0], vec![0x10]],
+            type_sizes: vec![
+                TypeSizes {
+                    instance_size: 0x10,
+                    ..Default::default()
+                },
+                TypeSizes {
+                    instance_size: 0x20,
+                    static_fields_size: 4,
+                    ..Default::default()
+                },
+                TypeSizes {
+                    instance_size: 0x14,
+                    ..Default::default()
+                },
+            ],
+        };
+
+        BindingGenerator::new(&metadata, &binary).namespace("App").generate()
+    }
+
+    #[test]
+    fn generate_bindings() {
+        let bindings = generate();
+
+        syn::parse_file(&bindings).unwrap_or_else(|error| panic!("invalid bindings: {}\n{}", error, bindings));
+
+        let expected = [
+            "pub mod app {",
+            "#[unity::class(\"App\", \"Unit\")]",
+            "#[static_fields(UnitStaticFields)]",
+            "pub hp: i32, // 0x10",
+            "pub name: Option<&'static mut unity::system::Il2CppString>, // 0x18",
+            "pub count: i32, // 0x0",
+            "pub struct Kind(pub i32);",
+            "#[unity::from_offset(\"App\", \"Unit\", \"GetHp\")]",
+            "pub fn unit_get_hp(this: &mut Unit, method_info: unity::il2cpp::method::OptionalMethod) -> i32;",
+            "#[unity::from_offset(\"App\", \"Unit\", \"Create\")]",
+            "pub fn unit_create(kind: Kind, method_info: unity::il2cpp::method::OptionalMethod) -> Option<&'static mut Unit>;",
+        ];
+
+        for line in expected {
+            assert!(bindings.contains(line), "missing {:?} in\n{}", line, bindings);
+        }
+
+        assert!(!bindings.contains("Object"), "System.Object should not be generated\n{}", bindings);
+    }
+
+    #[test]
+    fn overloads_use_signatures() {
+        let bindings = generate();
+
+        for signature in ["(System.Int32)", "(System.String)", "(App.Unit[])", "(System.Int32&)"] {
+            let attribute = format!("#[unity::from_offset(\"App\", \"Unit\", \"Add\", {:?})]", signature);
+            assert!(bindings.contains(&attribute), "missing {:?} in\n{}", attribute, bindings);
+        }
+
+        assert!(bindings.contains("pub fn unit_add(this: &mut Unit, value: i32, "));
+        assert!(bindings.contains("pub fn unit_add_1(this: &mut Unit, name: Option<&mut unity::system::Il2CppString>, "));
+        assert!(bindings.contains("result: &mut i32, "));
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!(snake_case("getHP"), "get_hp");
+        assert_eq!(snake_case("HPValue"), "hp_value");
+        assert_eq!(snake_case("2D"), "n2_d");
+        assert_eq!(type_name("List`1", 0), "List_1");
+        assert_eq!(type_name("Option", 0), "Option_");
+        assert_eq!(escape("type"), "r#type");
+        assert_eq!(escape("self"), "self_");
+        assert_eq!(field_name("<Name>k__BackingField"), "name");
+    }
+}
@@ -0,0 +1,404 @@
+//! Minimal loader for Switch main executables, used to run signatures offline.
+//!
+//! Supports NSO files (with or without LZ4-compressed segments) and ELF files such as the ones produced by `nx2elf`.
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ExecutableError {
+    #[error("the file is neither an NSO nor an ELF executable")]
+    UnknownFormat,
+    #[error("the file is truncated while reading {0}")]
+    Truncated(&'static str),
+    #[error("could not decompress the {0} segment: {1}")]
+    Decompression(&'static str, String),
+    #[error("unsupported executable: {0}")]
+    Unsupported(&'static str),
+}
+
+/// A segment of the executable, as it would be laid out once loaded in memory.
+#[derive(Debug, Clone, Default)]
+pub struct Segment {
+    /// Offset of the segment from the start of the module once loaded.
+    pub address: u64,
+    pub data: Vec<u8>,
+}
+
+impl Segment {
+    pub fn contains(&self, address: u64) -> bool {
+        address.checked_sub(self.address).is_some_and(|offset| offset < self.data.len() as u64)
+    }
+
+    /// Get the bytes found at the provided module address, if they belong to this segment.
+    pub fn get(&self, address: u64, len: usize) -> Option<&[u8]> {
+        let start = address.checked_sub(self.address)? as usize;
+        self.data.get(start..start.checked_add(len)?)
+    }
+
+    pub fn get_mut(&mut self, address: u64, len: usize) -> Option<&mut [u8]> {
+        let start = address.checked_sub(self.address)? as usize;
+        self.data.get_mut(start..start.checked_add(len)?)
+    }
+}
+
+/// The loaded segments of a main executable.
+#[derive(Debug, Clone, Default)]
+pub struct Executable {
+    pub text: Segment,
+    pub rodata: Segment,
+    pub data: Segment,
+}
+
+impl Executable {
+    /// Parse an executable, guessing its format from the magic.
+    pub fn parse(bytes: &[u8]) -> Result<Self, ExecutableError> {
+        match bytes.get(0..4) {
+            Some(b"NSO0") => Self::parse_nso(bytes),
+            Some(b"\x7fELF") => Self::parse_elf(bytes),
+            _ => Err(ExecutableError::UnknownFormat),
+        }
+    }
+
+    pub fn parse_nso(bytes: &[u8]) -> Result<Self, ExecutableError> {
+        if bytes.len() < 0x100 {
+            return Err(ExecutableError::Truncated("the NSO header"));
+        }
+
+        let flags = read_u32(bytes, 0xc)?;
+
+        let load = |name: &'static str, header: usize, compressed_size: usize, bit: u32| -> Result<Segment, ExecutableError> {
+            let file_offset = read_u32(bytes, header)? as usize;
+            let address = read_u32(bytes, header + 4)? as u64;
+            let size = read_u32(bytes, header + 8)? as usize;
+            let compressed = flags & (1 << bit) != 0;
+            let file_size = if compressed { read_u32(bytes, compressed_size)? as usize } else { size };
+
+            let raw = file_offset
+                .checked_add(file_size)
+                .and_then(|end| bytes.get(file_offset..end))
+                .ok_or(ExecutableError::Truncated(name))?;
+
+            let data = if compressed {
+                lz4_flex::block::decompress(raw, size).map_err(|err| ExecutableError::Decompression(name, err.to_string()))?
+            } else {
+                raw.to_vec()
+            };
+
+            Ok(Segment { address, data })
+        };
+
+        Ok(Self {
+            text: load("text", 0x10, 0x60, 0)?,
+            rodata: load("rodata", 0x20, 0x64, 1)?,
+            data: load("data", 0x30, 0x68, 2)?,
+        })
+    }
+
+    pub fn parse_elf(bytes: &[u8]) -> Result<Self, ExecutableError> {
+        const PT_LOAD: u32 = 1;
+        const PF_X: u32 = 1;
+        const PF_W: u32 = 2;
+
+        if bytes.len() < 0x40 {
+            return Err(ExecutableError::Truncated("the ELF header"));
+        }
+
+        // ELFCLASS64, little endian
+        if bytes[4] != 2 || bytes[5] != 1 {
+            return Err(ExecutableError::Unsupported("only 64-bit little endian ELF files are supported"));
+        }
+
+        let phoff = read_u64(bytes, 0x20)? as usize;
+        let phentsize = read_u16(bytes, 0x36)? as usize;
+        let phnum = read_u16(bytes, 0x38)? as usize;
+
+        let mut executable = Self::default();
+
+        for index in 0..phnum {
+            let header = index
+                .checked_mul(phentsize)
+                .and_then(|offset| offset.checked_add(phoff))
+                .ok_or(ExecutableError::Truncated("the program headers"))?;
+
+            if read_u32(bytes, header)? != PT_LOAD {
+                continue;
+            }
+
+            // `header` is within the file at this point, so the field offsets cannot overflow
+            let flags = read_u32(bytes, header + 4)?;
+            let offset = read_u64(bytes, header + 8)? as usize;
+            let address = read_u64(bytes, header + 0x10)?;
+            let file_size = read_u64(bytes, header + 0x20)? as usize;
+            let memory_size = read_u64(bytes, header + 0x28)? as usize;
+
+            let mut data = offset
+                .checked_add(file_size)
+                .and_then(|end| bytes.get(offset..end))
+                .ok_or(ExecutableError::Truncated("a program segment"))?
+                .to_vec();
+            data.resize(memory_size.max(file_size), 0);
+
+            let segment = if flags & PF_X != 0 {
+                &mut executable.text
+            } else if flags & PF_W != 0 {
+                &mut executable.data
+            } else {
+                &mut executable.rodata
+            };
+
+            // Keep the first segment of each kind, which is the one belonging to the main module.
+            if segment.data.is_empty() {
+                *segment = Segment { address, data };
+            }
+        }
+
+        if executable.text.data.is_empty() {
+            return Err(ExecutableError::Unsupported("the ELF file has no executable segment"));
+        }
+
+        Ok(executable)
+    }
+
+    /// Find the segment an address belongs to.
+    pub fn segment(&self, address: u64) -> Option<&Segment> {
+        [&self.text, &self.rodata, &self.data].into_iter().find(|segment| segment.contains(address))
+    }
+
+    /// Read bytes at a module address, in whichever segment they are.
+    pub fn read(&self, address: u64, len: usize) -> Option<&[u8]> {
+        self.segment(address)?.get(address, len)
+    }
+
+    pub fn read_u32_at(&self, address: u64) -> Option<u32> {
+        self.read(address, 4).map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    pub fn read_u64_at(&self, address: u64) -> Option<u64> {
+        self.read(address, 8).map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    /// Apply the relative relocations of the module, returning how many were applied.
+    ///
+    /// Pointers stored in the data of an executable are only filled by the loader, using `.rela.dyn`.
+    /// This needs to be done before following them, such as when reading the Il2Cpp registrations.
+    pub fn relocate(&mut self) -> Result<usize, ExecutableError> {
+        const DT_NULL: u64 = 0;
+        const DT_RELA: u64 = 7;
+        const DT_RELASZ: u64 = 8;
+        const R_AARCH64_RELATIVE: u64 = 0x403;
+
+        // The second word of the module is the offset of its MOD0 header, which points to the dynamic section.
+        let mod0 = self.read_u32_at(self.text.address + 4).ok_or(ExecutableError::Truncated("the MOD0 offset"))? as u64;
+
+        if self.read(mod0, 4) != Some(b"MOD0") {
+            return Err(ExecutableError::Unsupported("the executable has no MOD0 header"));
+        }
+
+        let dynamic_offset = self.read_u32_at(mod0 + 4).ok_or(ExecutableError::Truncated("the MOD0 header"))? as i32;
+        let dynamic = mod0.wrapping_add(dynamic_offset as i64 as u64);
+
+        let (mut rela, mut rela_size) = (None, 0);
+
+        for entry in (dynamic..).step_by(0x10) {
+            let tag = self.read_u64_at(entry).ok_or(ExecutableError::Truncated("the dynamic section"))?;
+            let value = self.read_u64_at(entry + 8).ok_or(ExecutableError::Truncated("the dynamic section"))?;
+
+            match tag {
+                DT_NULL => break,
+                DT_RELA => rela = Some(value),
+                DT_RELASZ => rela_size = value,
+                _ => (),
+            }
+        }
+
+        let Some(rela) = rela else { return Ok(0) };
+        let mut applied = 0;
+
+        let rela_end = rela.checked_add(rela_size).ok_or(ExecutableError::Truncated("the relocations"))?;
+
+        for entry in (rela..rela_end).step_by(0x18) {
+            let offset = self.read_u64_at(entry).ok_or(ExecutableError::Truncated("the relocations"))?;
+            let info = self.read_u64_at(entry + 8).ok_or(ExecutableError::Truncated("the relocations"))?;
+            let addend = self.read_u64_at(entry + 0x10).ok_or(ExecutableError::Truncated("the relocations"))?;
+
+            if info & 0xffffffff != R_AARCH64_RELATIVE {
+                continue;
+            }
+
+            let target = [&mut self.text, &mut self.rodata, &mut self.data]
+                .into_iter()
+                .find_map(|segment| segment.get_mut(offset, 8));
+
+            // Relocations in the bss are skipped, as it is not part of the file.
+            if let Some(target) = target {
+                target.copy_from_slice(&addend.to_le_bytes());
+                applied += 1;
+            }
+        }
+
+        Ok(applied)
+    }
+}
+
+fn read<const N: usize>(bytes: &[u8], offset: usize) -> Result<[u8; N], ExecutableError> {
+    offset
+        .checked_add(N)
+        .and_then(|end| bytes.get(offset..end))
+        .map(|slice| slice.try_into().unwrap())
+        .ok_or(ExecutableError::Truncated("the headers"))
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Result<u16, ExecutableError> {
+    read(bytes, offset).map(u16::from_le_bytes)
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, ExecutableError> {
+    read(bytes, offset).map(u32::from_le_bytes)
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> Result<u64, ExecutableError> {
+    read(bytes, offset).map(u64::from_le_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: u64 = 0;
+    const RODATA: u64 = 0x800;
+    const DATA: u64 = 0x1000;
+
+    /// A `.text` segment whose MOD0 header points to a single relative relocation of the first word of `.data`.
+    fn text() -> Vec<u8> {
+        let mut text = vec![0; 0x100];
+        let mut write = |offset: usize, value: u64| text[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+
+        // MOD0 at 0x10, with the dynamic section at 0x20
+        write(0, 0x10 << 32);
+        write(0x10, u32::from_le_bytes(*b"MOD0") as u64 | 0x10 << 32);
+        write(0x20, 7);
+        write(0x28, 0x60);
+        write(0x30, 8);
+        write(0x38, 0x18);
+
+        // DT_NULL at 0x40, then the relocation
+        write(0x60, DATA);
+        write(0x68, 0x403);
+        write(0x70, 0x1234);
+        text
+    }
+
+    fn nso(compressed: bool) -> Vec<u8> {
+        let segments = [(TEXT, text()), (RODATA, b"rodata".to_vec()), (DATA, vec![0; 0x10])];
+        let mut file = vec![0; 0x100];
+        file[..4].copy_from_slice(b"NSO0");
+
+        if compressed {
+            file[0xc] = 0b111;
+        }
+
+        for (index, (address, data)) in segments.into_iter().enumerate() {
+            let stored = if compressed { lz4_flex::block::compress(&data) } else { data.clone() };
+            let header = 0x10 + index * 0x10;
+            let fields = [file.len() as u32, address as u32, data.len() as u32];
+
+            for (offset, value) in fields.into_iter().enumerate() {
+                file[header + offset * 4..][..4].copy_from_slice(&value.to_le_bytes());
+            }
+
+            file[0x60 + index * 4..][..4].copy_from_slice(&(stored.len() as u32).to_le_bytes());
+            file.extend(stored);
+        }
+
+        file
+    }
+
+    /// An ELF file with the same segments, where `.data` is followed by 0x10 bytes of bss.
+    fn elf() -> Vec<u8> {
+        let segments = [(TEXT, 5, text()), (RODATA, 4, b"rodata".to_vec()), (DATA, 6, vec![0; 0x10])];
+        let mut file = vec![0; 0x40];
+        file[..6].copy_from_slice(b"\x7fELF\x02\x01");
+        file[0x20..0x28].copy_from_slice(&0x40u64.to_le_bytes());
+        file[0x36..0x38].copy_from_slice(&0x38u16.to_le_bytes());
+        file[0x38..0x3a].copy_from_slice(&(segments.len() as u16).to_le_bytes());
+
+        let mut offset = 0x40 + segments.len() * 0x38;
+
+        for (address, flags, data) in &segments {
+            let memory_size = if *address == DATA { data.len() * 2 } else { data.len() };
+
+            file.extend(1u32.to_le_bytes());
+            file.extend((*flags as u32).to_le_bytes());
+
+            for value in [offset, *address as usize, *address as usize, data.len(), memory_size] {
+                file.extend((value as u64).to_le_bytes());
+            }
+
+            file.extend(0x10u64.to_le_bytes());
+            offset += data.len();
+        }
+
+        for (_, _, data) in segments {
+            file.extend(data);
+        }
+
+        file
+    }
+
+    fn check(mut executable: Executable, data_size: usize) {
+        assert_eq!(executable.text.data, text());
+        assert_eq!(executable.rodata.address, RODATA);
+        assert_eq!(executable.read(RODATA + 2, 4), Some(&b"data"[..]));
+        assert_eq!(executable.data.data.len(), data_size);
+        assert_eq!(executable.segment(DATA + 8).map(|segment| segment.address), Some(DATA));
+        assert!(executable.segment(RODATA + 6).is_none());
+
+        assert_eq!(executable.read_u64_at(DATA), Some(0));
+        assert_eq!(executable.relocate().unwrap(), 1);
+        assert_eq!(executable.read_u64_at(DATA), Some(0x1234));
+    }
+
+    #[test]
+    fn parse_nso() {
+        check(Executable::parse(&nso(false)).unwrap(), 0x10);
+    }
+
+    #[test]
+    fn parse_compressed_nso() {
+        let file = nso(true);
+        assert!(file.len() < nso(false).len());
+        check(Executable::parse(&file).unwrap(), 0x10);
+    }
+
+    #[test]
+    fn parse_elf() {
+        check(Executable::parse(&elf()).unwrap(), 0x20);
+    }
+
+    #[test]
+    fn reject_invalid_files() {
+        assert!(matches!(Executable::parse(b"MZ\0\0"), Err(ExecutableError::UnknownFormat)));
+        assert!(matches!(Executable::parse(&nso(false)[..0x80]), Err(ExecutableError::Truncated("the NSO header"))));
+
+        let file = nso(false);
+        assert!(matches!(Executable::parse(&file[..file.len() - 1]), Err(ExecutableError::Truncated("data"))));
+
+        // The compressed size of `.text` no longer matches its data
+        let mut invalid = nso(true);
+        invalid[0x60] -= 1;
+        assert!(matches!(Executable::parse(&invalid), Err(ExecutableError::Decompression("text", _))));
+
+        // Offsets that would overflow once added to a size
+        let mut invalid = elf();
+        invalid[0x20..0x28].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(matches!(Executable::parse(&invalid), Err(ExecutableError::Truncated(_))));
+
+        let mut invalid = elf();
+        invalid[0x48..0x50].copy_from_slice(&(u64::MAX - 4).to_le_bytes());
+        assert!(matches!(Executable::parse(&invalid), Err(ExecutableError::Truncated("a program segment"))));
+
+        let mut invalid = elf();
+        invalid[4] = 1;
+        assert!(matches!(Executable::parse(&invalid), Err(ExecutableError::Unsupported(_))));
+    }
+}
@@ -0,0 +1,12 @@
+//! Platform-independent building blocks shared by the `unity` crate and its host-side tools.
+//!
+//! Nothing in here depends on Skyline, so everything can be built and exercised on a regular desktop machine.
+
+pub mod binary;
+pub mod bindgen;
+pub mod executable;
+pub mod metadata;
+pub mod profile;
+pub mod scan;
+pub mod signatures;
+pub mod types;
@@ -0,0 +1,1029 @@
+//! Parser for `global-metadata.dat`, the file Il2Cpp stores the metadata of every managed type in.
+//!
+//! Supports metadata versions 24 through 31, which covers Unity 2018.3 to 2022.
+//! The layout of the definitions changed a few times within version 24 without the version number being bumped,
+//! so the revision is guessed from the header and section sizes the same way Il2CppDumper does.
+//!
+//! Indices named `*_type_index` refer to the `Il2CppType` table, which is found in the executable rather than in the metadata.
+//! Other indices and starts are `-1` when absent, like in the runtime.
+//!
+//! Example:
+//!
+//! ```no_run
+//! # use unity_core::metadata::Metadata;
+//! # fn main() -> Result<(), unity_core::metadata::MetadataError> {
+//! let metadata = Metadata::from_file("global-metadata.dat")?;
+//!
+//! let ty = metadata.find_type("App", "Unit").unwrap();
+//!
+//! for method in metadata.methods_of(ty) {
+//!     println!("{}::{}", metadata.type_name(ty), metadata.string(method.name_index)?);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{fmt, path::Path};
+
+use thiserror::Error;
+
+const SANITY: u32 = 0xFAB11BAF;
+/// Size of the header of 24.0 and 24.1, which still have the rgctx entries section.
+const HEADER_SIZE_WITH_RGCTX: u32 = 272;
+
+#[derive(Debug, Error)]
+pub enum MetadataError {
+    #[error("could not read the metadata: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("the file is not an Il2Cpp metadata file")]
+    InvalidSanity,
+    #[error("unsupported metadata version {0}, only versions 24 to 31 are supported")]
+    UnsupportedVersion(i32),
+    #[error("the metadata is truncated while reading {0}")]
+    Truncated(&'static str),
+    #[error("the size of the {0} section does not match the metadata version")]
+    InvalidSection(&'static str),
+    #[error("invalid string index {0}")]
+    InvalidString(u32),
+}
+
+/// Version of the metadata, along with the revision for version 24 (24.0, 24.1, 24.2 or 24.4).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct MetadataVersion {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl MetadataVersion {
+    pub const fn new(major: u32, minor: u32) -> Self {
+        Self { major, minor }
+    }
+
+    // Removed from the definitions in 24.1, in favor of looking up attributes by token.
+    fn has_custom_attribute_index(self) -> bool {
+        self < Self::new(24, 1)
+    }
+
+    // Removed in 24.2, along with the method pointers and invokers of method definitions.
+    fn has_rgctx(self) -> bool {
+        self < Self::new(24, 2)
+    }
+
+    fn has_byref_type_index(self) -> bool {
+        self.major < 27
+    }
+
+    fn has_return_parameter_token(self) -> bool {
+        self.major >= 31
+    }
+}
+
+impl fmt::Display for MetadataVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// Offset and size in bytes of a section of the metadata.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Section {
+    pub offset: u32,
+    pub size: u32,
+}
+
+/// The sections of the header that are understood by the parser.
+#[derive(Debug, Clone, Default)]
+pub struct MetadataHeader {
+    pub version: i32,
+    pub string_literals: Section,
+    pub string_literal_data: Section,
+    pub strings: Section,
+    pub events: Section,
+    pub properties: Section,
+    pub methods: Section,
+    pub parameter_default_values: Section,
+    pub field_default_values: Section,
+    pub field_and_parameter_default_value_data: Section,
+    pub field_marshaled_sizes: Section,
+    pub parameters: Section,
+    pub fields: Section,
+    pub generic_parameters: Section,
+    pub generic_parameter_constraints: Section,
+    pub generic_containers: Section,
+    pub nested_types: Section,
+    pub interfaces: Section,
+    pub vtable_methods: Section,
+    pub interface_offsets: Section,
+    pub type_definitions: Section,
+    /// Only present up to 24.1.
+    pub rgctx_entries: Option<Section>,
+    pub images: Section,
+    pub assemblies: Section,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StringLiteral {
+    pub length: u32,
+    pub data_index: i32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TypeDefinition {
+    pub name_index: u32,
+    pub namespace_index: u32,
+    /// Only present in 24.0.
+    pub custom_attribute_index: Option<i32>,
+    pub byval_type_index: i32,
+    /// Removed in version 27.
+    pub byref_type_index: Option<i32>,
+    pub declaring_type_index: i32,
+    pub parent_index: i32,
+    pub element_type_index: i32,
+    /// Only present up to 24.1, as `(start, count)`.
+    pub rgctx: Option<(i32, i32)>,
+    pub generic_container_index: i32,
+    /// `TypeAttributes` of the type.
+    pub flags: u32,
+    pub field_start: i32,
+    pub method_start: i32,
+    pub event_start: i32,
+    pub property_start: i32,
+    pub nested_types_start: i32,
+    pub interfaces_start: i32,
+    pub vtable_start: i32,
+    pub interface_offsets_start: i32,
+    pub method_count: u16,
+    pub property_count: u16,
+    pub field_count: u16,
+    pub event_count: u16,
+    pub nested_type_count: u16,
+    pub vtable_count: u16,
+    pub interfaces_count: u16,
+    pub interface_offsets_count: u16,
+    pub bitfield: u32,
+    pub token: u32,
+}
+
+impl TypeDefinition {
+    pub fn is_valuetype(&self) -> bool {
+        self.bitfield & 0x1 != 0
+    }
+
+    pub fn is_enum(&self) -> bool {
+        self.bitfield & 0x2 != 0
+    }
+
+    pub fn is_generic(&self) -> bool {
+        self.generic_container_index != -1
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MethodDefinition {
+    pub name_index: u32,
+    /// Index of the type definition the method belongs to.
+    pub declaring_type: i32,
+    pub return_type: i32,
+    /// Only present from version 31.
+    pub return_parameter_token: Option<i32>,
+    pub parameter_start: i32,
+    /// Only present in 24.0.
+    pub custom_attribute_index: Option<i32>,
+    pub generic_container_index: i32,
+    /// Only present up to 24.1.
+    pub method_index: Option<i32>,
+    /// Only present up to 24.1.
+    pub invoker_index: Option<i32>,
+    /// Only present up to 24.1.
+    pub delegate_wrapper_index: Option<i32>,
+    /// Only present up to 24.1, as `(start, count)`.
+    pub rgctx: Option<(i32, i32)>,
+    pub token: u32,
+    /// `MethodAttributes` of the method.
+    pub flags: u16,
+    pub iflags: u16,
+    pub slot: u16,
+    pub parameter_count: u16,
+}
+
+impl MethodDefinition {
+    pub fn is_static(&self) -> bool {
+        self.flags & 0x10 != 0
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FieldDefinition {
+    pub name_index: u32,
+    pub type_index: i32,
+    /// Only present in 24.0.
+    pub custom_attribute_index: Option<i32>,
+    pub token: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParameterDefinition {
+    pub name_index: u32,
+    pub token: u32,
+    /// Only present in 24.0.
+    pub custom_attribute_index: Option<i32>,
+    pub type_index: i32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GenericContainer {
+    /// Index of the type or method definition owning the container.
+    pub owner_index: i32,
+    pub type_argc: i32,
+    pub is_method: bool,
+    pub generic_parameter_start: i32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GenericParameter {
+    pub owner_index: i32,
+    pub name_index: u32,
+    pub constraints_start: i16,
+    pub constraints_count: i16,
+    pub num: u16,
+    pub flags: u16,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImageDefinition {
+    pub name_index: u32,
+    pub assembly_index: i32,
+    pub type_start: i32,
+    pub type_count: u32,
+    pub exported_type_start: i32,
+    pub exported_type_count: u32,
+    pub entry_point_index: i32,
+    pub token: u32,
+    /// Only present from 24.1, as `(start, count)`.
+    pub custom_attributes: Option<(i32, u32)>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AssemblyDefinition {
+    pub image_index: i32,
+    /// Only present from 24.1.
+    pub token: Option<u32>,
+    /// Only present in 24.0.
+    pub custom_attribute_index: Option<i32>,
+    pub referenced_assembly_start: i32,
+    pub referenced_assembly_count: i32,
+    pub name_index: u32,
+    pub culture_index: u32,
+    pub public_key_index: u32,
+    pub hash_alg: u32,
+    pub hash_len: i32,
+    pub flags: u32,
+    pub major: i32,
+    pub minor: i32,
+    pub build: i32,
+    pub revision: i32,
+    pub public_key_token: [u8; 8],
+}
+
+/// The parsed content of a `global-metadata.dat` file.
+#[derive(Debug, Clone)]
+pub struct Metadata {
+    data: Vec<u8>,
+    pub version: MetadataVersion,
+    pub header: MetadataHeader,
+    pub string_literals: Vec<StringLiteral>,
+    pub type_definitions: Vec<TypeDefinition>,
+    pub methods: Vec<MethodDefinition>,
+    pub fields: Vec<FieldDefinition>,
+    pub parameters: Vec<ParameterDefinition>,
+    pub generic_containers: Vec<GenericContainer>,
+    pub generic_parameters: Vec<GenericParameter>,
+    pub images: Vec<ImageDefinition>,
+    pub assemblies: Vec<AssemblyDefinition>,
+}
+
+impl Metadata {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, MetadataError> {
+        Self::parse(std::fs::read(path)?)
+    }
+
+    pub fn parse(data: impl Into<Vec<u8>>) -> Result<Self, MetadataError> {
+        let data = data.into();
+        let mut reader = Reader::new(&data, 0, "the header");
+
+        if reader.u32()? != SANITY {
+            return Err(MetadataError::InvalidSanity);
+        }
+
+        let raw_version = reader.i32()?;
+
+        if !(24..=31).contains(&raw_version) {
+            return Err(MetadataError::UnsupportedVersion(raw_version));
+        }
+
+        let mut section = || -> Result<Section, MetadataError> {
+            Ok(Section {
+                offset: reader.u32()?,
+                size: reader.u32()?,
+            })
+        };
+
+        let mut header = MetadataHeader {
+            version: raw_version,
+            string_literals: section()?,
+            string_literal_data: section()?,
+            strings: section()?,
+            events: section()?,
+            properties: section()?,
+            methods: section()?,
+            parameter_default_values: section()?,
+            field_default_values: section()?,
+            field_and_parameter_default_value_data: section()?,
+            field_marshaled_sizes: section()?,
+            parameters: section()?,
+            fields: section()?,
+            generic_parameters: section()?,
+            generic_parameter_constraints: section()?,
+            generic_containers: section()?,
+            nested_types: section()?,
+            interfaces: section()?,
+            vtable_methods: section()?,
+            interface_offsets: section()?,
+            type_definitions: section()?,
+            ..Default::default()
+        };
+
+        // The header is directly followed by the first section, which gives away its size.
+        if raw_version == 24 && header.string_literals.offset >= HEADER_SIZE_WITH_RGCTX {
+            header.rgctx_entries = Some(section()?);
+        }
+
+        header.images = section()?;
+        header.assemblies = section()?;
+
+        let version = Self::guess_version(&data, &header)?;
+
+        let mut metadata = Self {
+            version,
+            string_literals: read_section(&data, header.string_literals, "string literals", |reader| {
+                Ok(StringLiteral {
+                    length: reader.u32()?,
+                    data_index: reader.i32()?,
+                })
+            })?,
+            type_definitions: read_section(&data, header.type_definitions, "type definitions", |reader| {
+                TypeDefinition::read(reader, version)
+            })?,
+            methods: read_section(&data, header.methods, "methods", |reader| MethodDefinition::read(reader, version))?,
+            fields: read_section(&data, header.fields, "fields", |reader| FieldDefinition::read(reader, version))?,
+            parameters: read_section(&data, header.parameters, "parameters", |reader| ParameterDefinition::read(reader, version))?,
+            generic_containers: read_section(&data, header.generic_containers, "generic containers", |reader| {
+                Ok(GenericContainer {
+                    owner_index: reader.i32()?,
+                    type_argc: reader.i32()?,
+                    is_method: reader.i32()? != 0,
+                    generic_parameter_start: reader.i32()?,
+                })
+            })?,
+            generic_parameters: read_section(&data, header.generic_parameters, "generic parameters", |reader| {
+                Ok(GenericParameter {
+                    owner_index: reader.i32()?,
+                    name_index: reader.u32()?,
+                    constraints_start: reader.i16()?,
+                    constraints_count: reader.i16()?,
+                    num: reader.u16()?,
+                    flags: reader.u16()?,
+                })
+            })?,
+            images: read_section(&data, header.images, "images", |reader| ImageDefinition::read(reader, version))?,
+            assemblies: Vec::new(),
+            header,
+            data: Vec::new(),
+        };
+
+        let hash_value_index = metadata.header.assemblies.size as usize == metadata.images.len() * AssemblyDefinition::size(true);
+
+        metadata.assemblies = read_section(&data, metadata.header.assemblies, "assemblies", |reader| {
+            AssemblyDefinition::read(reader, version, hash_value_index)
+        })?;
+
+        metadata.data = data;
+
+        Ok(metadata)
+    }
+
+    fn guess_version(data: &[u8], header: &MetadataHeader) -> Result<MetadataVersion, MetadataError> {
+        let major = header.version as u32;
+
+        if major != 24 {
+            return Ok(MetadataVersion::new(major, 0));
+        }
+
+        if header.rgctx_entries.is_some() {
+            // Images of 24.0 have no custom attribute range, so reading 24.1 images with that layout misaligns the tokens, which are always 1.
+            let images = read_section(data, header.images, "images", |reader| ImageDefinition::read(reader, MetadataVersion::new(24, 0)));
+            let minor = match images {
+                Ok(images) if images.iter().all(|image| image.token == 1) => 0,
+                _ => 1,
+            };
+
+            return Ok(MetadataVersion::new(24, minor));
+        }
+
+        // 24.4 dropped the hash value index of assembly names, making assemblies smaller.
+        let image_count = header.images.size as usize / ImageDefinition::size(MetadataVersion::new(24, 2));
+        let minor = if header.assemblies.size as usize == image_count * AssemblyDefinition::size(false) {
+            4
+        } else {
+            2
+        };
+
+        Ok(MetadataVersion::new(24, minor))
+    }
+
+    /// Get a string of the metadata string table, such as the name of a type or method.
+    pub fn string(&self, index: u32) -> Result<&str, MetadataError> {
+        let start = self.header.strings.offset as usize + index as usize;
+        let end = self.header.strings.offset as usize + self.header.strings.size as usize;
+
+        let bytes = self.data.get(start..end).ok_or(MetadataError::InvalidString(index))?;
+        let length = bytes.iter().position(|byte| *byte == 0).ok_or(MetadataError::InvalidString(index))?;
+
+        std::str::from_utf8(&bytes[..length]).map_err(|_| MetadataError::InvalidString(index))
+    }
+
+    /// Get the content of a string literal used by managed code, decoded from UTF-8.
+    pub fn string_literal(&self, index: usize) -> Option<String> {
+        let literal = self.string_literals.get(index)?;
+        let start = self.header.string_literal_data.offset as usize + literal.data_index as usize;
+
+        self.data
+            .get(start..start + literal.length as usize)
+            .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+    }
+
+    /// Full name of the type, such as `System.Collections.Generic.List`1`, without the declaring type of nested types.
+    pub fn type_name(&self, ty: &TypeDefinition) -> String {
+        let name = self.string(ty.name_index).unwrap_or_default();
+
+        match self.string(ty.namespace_index).unwrap_or_default() {
+            "" => name.to_string(),
+            namespace => format!("{}.{}", namespace, name),
+        }
+    }
+
+    pub fn find_type(&self, namespace: impl AsRef<str>, name: impl AsRef<str>) -> Option<&TypeDefinition> {
+        self.type_definitions.iter().find(|ty| {
+            self.string(ty.namespace_index).ok() == Some(namespace.as_ref()) && self.string(ty.name_index).ok() == Some(name.as_ref())
+        })
+    }
+
+    pub fn find_image(&self, name: impl AsRef<str>) -> Option<&ImageDefinition> {
+        let name = name.as_ref();
+
+        self.images.iter().find(|image| {
+            let image_name = self.string(image.name_index).unwrap_or_default();
+            image_name == name || image_name.strip_suffix(".dll") == Some(name)
+        })
+    }
+
+    pub fn types_of(&self, image: &ImageDefinition) -> &[TypeDefinition] {
+        slice(&self.type_definitions, image.type_start, image.type_count as usize)
+    }
+
+    pub fn methods_of(&self, ty: &TypeDefinition) -> &[MethodDefinition] {
+        slice(&self.methods, ty.method_start, ty.method_count as usize)
+    }
+
+    pub fn fields_of(&self, ty: &TypeDefinition) -> &[FieldDefinition] {
+        slice(&self.fields, ty.field_start, ty.field_count as usize)
+    }
+
+    pub fn parameters_of(&self, method: &MethodDefinition) -> &[ParameterDefinition] {
+        slice(&self.parameters, method.parameter_start, method.parameter_count as usize)
+    }
+
+    pub fn generic_container(&self, index: i32) -> Option<&GenericContainer> {
+        usize::try_from(index).ok().and_then(|index| self.generic_containers.get(index))
+    }
+
+    pub fn generic_parameters_of(&self, container: &GenericContainer) -> &[GenericParameter] {
+        slice(&self.generic_parameters, container.generic_parameter_start, container.type_argc.max(0) as usize)
+    }
+
+    /// Find a type of the image by its token, such as the one of an `Il2CppClass`.
+    pub fn type_by_token(&self, image: &ImageDefinition, token: u32) -> Option<&TypeDefinition> {
+        self.types_of(image).iter().find(|ty| ty.token == token)
+    }
+
+    /// Find a method of the type by its token, such as the one of a `MethodInfo`.
+    pub fn method_by_token(&self, ty: &TypeDefinition, token: u32) -> Option<&MethodDefinition> {
+        self.methods_of(ty).iter().find(|method| method.token == token)
+    }
+
+    /// Find the assembly by its token, such as the one of an `Il2CppAssembly`, or its image if the version has no assembly tokens.
+    pub fn assembly_by_token(&self, image: &ImageDefinition, token: u32) -> Option<&AssemblyDefinition> {
+        self.assemblies.iter().find(|assembly| match assembly.token {
+            Some(assembly_token) => assembly_token == token,
+            None => self.images.get(assembly.image_index as usize) == Some(image),
+        })
+    }
+}
+
+impl TypeDefinition {
+    fn read(reader: &mut Reader, version: MetadataVersion) -> Result<Self, MetadataError> {
+        Ok(Self {
+            name_index: reader.u32()?,
+            namespace_index: reader.u32()?,
+            custom_attribute_index: reader.i32_if(version.has_custom_attribute_index())?,
+            byval_type_index: reader.i32()?,
+            byref_type_index: reader.i32_if(version.has_byref_type_index())?,
+            declaring_type_index: reader.i32()?,
+            parent_index: reader.i32()?,
+            element_type_index: reader.i32()?,
+            rgctx: reader.pair_if(version.has_rgctx())?,
+            generic_container_index: reader.i32()?,
+            flags: reader.u32()?,
+            field_start: reader.i32()?,
+            method_start: reader.i32()?,
+            event_start: reader.i32()?,
+            property_start: reader.i32()?,
+            nested_types_start: reader.i32()?,
+            interfaces_start: reader.i32()?,
+            vtable_start: reader.i32()?,
+            interface_offsets_start: reader.i32()?,
+            method_count: reader.u16()?,
+            property_count: reader.u16()?,
+            field_count: reader.u16()?,
+            event_count: reader.u16()?,
+            nested_type_count: reader.u16()?,
+            vtable_count: reader.u16()?,
+            interfaces_count: reader.u16()?,
+            interface_offsets_count: reader.u16()?,
+            bitfield: reader.u32()?,
+            token: reader.u32()?,
+        })
+    }
+}
+
+impl MethodDefinition {
+    fn read(reader: &mut Reader, version: MetadataVersion) -> Result<Self, MetadataError> {
+        Ok(Self {
+            name_index: reader.u32()?,
+            declaring_type: reader.i32()?,
+            return_type: reader.i32()?,
+            return_parameter_token: reader.i32_if(version.has_return_parameter_token())?,
+            parameter_start: reader.i32()?,
+            custom_attribute_index: reader.i32_if(version.has_custom_attribute_index())?,
+            generic_container_index: reader.i32()?,
+            method_index: reader.i32_if(version.has_rgctx())?,
+            invoker_index: reader.i32_if(version.has_rgctx())?,
+            delegate_wrapper_index: reader.i32_if(version.has_rgctx())?,
+            rgctx: reader.pair_if(version.has_rgctx())?,
+            token: reader.u32()?,
+            flags: reader.u16()?,
+            iflags: reader.u16()?,
+            slot: reader.u16()?,
+            parameter_count: reader.u16()?,
+        })
+    }
+}
+
+impl FieldDefinition {
+    fn read(reader: &mut Reader, version: MetadataVersion) -> Result<Self, MetadataError> {
+        Ok(Self {
+            name_index: reader.u32()?,
+            type_index: reader.i32()?,
+            custom_attribute_index: reader.i32_if(version.has_custom_attribute_index())?,
+            token: reader.u32()?,
+        })
+    }
+}
+
+impl ParameterDefinition {
+    fn read(reader: &mut Reader, version: MetadataVersion) -> Result<Self, MetadataError> {
+        Ok(Self {
+            name_index: reader.u32()?,
+            token: reader.u32()?,
+            custom_attribute_index: reader.i32_if(version.has_custom_attribute_index())?,
+            type_index: reader.i32()?,
+        })
+    }
+}
+
+impl ImageDefinition {
+    fn size(version: MetadataVersion) -> usize {
+        if version.has_custom_attribute_index() {
+            0x20
+        } else {
+            0x28
+        }
+    }
+
+    fn read(reader: &mut Reader, version: MetadataVersion) -> Result<Self, MetadataError> {
+        Ok(Self {
+            name_index: reader.u32()?,
+            assembly_index: reader.i32()?,
+            type_start: reader.i32()?,
+            type_count: reader.u32()?,
+            exported_type_start: reader.i32()?,
+            exported_type_count: reader.u32()?,
+            entry_point_index: reader.i32()?,
+            token: reader.u32()?,
+            custom_attributes: match version.has_custom_attribute_index() {
+                true => None,
+                false => Some((reader.i32()?, reader.u32()?)),
+            },
+        })
+    }
+}
+
+impl AssemblyDefinition {
+    fn size(hash_value_index: bool) -> usize {
+        // Either the token or the custom attribute index is present, never both.
+        if hash_value_index {
+            0x44
+        } else {
+            0x40
+        }
+    }
+
+    fn read(reader: &mut Reader, version: MetadataVersion, hash_value_index: bool) -> Result<Self, MetadataError> {
+        let image_index = reader.i32()?;
+        let (token, custom_attribute_index) = match version.has_custom_attribute_index() {
+            true => (None, Some(reader.i32()?)),
+            false => (Some(reader.u32()?), None),
+        };
+
+        let referenced_assembly_start = reader.i32()?;
+        let referenced_assembly_count = reader.i32()?;
+        let name_index = reader.u32()?;
+        let culture_index = reader.u32()?;
+        reader.i32_if(hash_value_index)?;
+
+        Ok(Self {
+            image_index,
+            token,
+            custom_attribute_index,
+            referenced_assembly_start,
+            referenced_assembly_count,
+            name_index,
+            culture_index,
+            public_key_index: reader.u32()?,
+            hash_alg: reader.u32()?,
+            hash_len: reader.i32()?,
+            flags: reader.u32()?,
+            major: reader.i32()?,
+            minor: reader.i32()?,
+            build: reader.i32()?,
+            revision: reader.i32()?,
+            public_key_token: reader.bytes(8)?.try_into().unwrap(),
+        })
+    }
+}
+
+fn slice<T>(items: &[T], start: i32, count: usize) -> &[T] {
+    usize::try_from(start)
+        .ok()
+        .and_then(|start| items.get(start..start.checked_add(count)?))
+        .unwrap_or_default()
+}
+
+/// Read every entry of a section, making sure its size is a multiple of the size of an entry.
+///
+/// The section being within the file is checked beforehand, so running out of bytes means the last entry is incomplete.
+fn read_section<T>(
+    data: &[u8],
+    section: Section,
+    name: &'static str,
+    mut read: impl FnMut(&mut Reader) -> Result<T, MetadataError>,
+) -> Result<Vec<T>, MetadataError> {
+    let start = section.offset as usize;
+    let end = start + section.size as usize;
+
+    if end > data.len() {
+        return Err(MetadataError::Truncated(name));
+    }
+
+    let mut reader = Reader::new(&data[..end], start, name);
+    let mut entries = Vec::new();
+
+    while reader.position < end {
+        entries.push(read(&mut reader).map_err(|_| MetadataError::InvalidSection(name))?);
+    }
+
+    Ok(entries)
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    position: usize,
+    name: &'static str,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8], position: usize, name: &'static str) -> Self {
+        Self { data, position, name }
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], MetadataError> {
+        let bytes = self
+            .data
+            .get(self.position..self.position + len)
+            .ok_or(MetadataError::Truncated(self.name))?;
+
+        self.position += len;
+        Ok(bytes)
+    }
+
+    fn u16(&mut self) -> Result<u16, MetadataError> {
+        Ok(u16::from_le_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    fn i16(&mut self) -> Result<i16, MetadataError> {
+        Ok(self.u16()? as i16)
+    }
+
+    fn u32(&mut self) -> Result<u32, MetadataError> {
+        Ok(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    fn i32(&mut self) -> Result<i32, MetadataError> {
+        Ok(self.u32()? as i32)
+    }
+
+    fn i32_if(&mut self, present: bool) -> Result<Option<i32>, MetadataError> {
+        present.then(|| self.i32()).transpose()
+    }
+
+    fn pair_if(&mut self, present: bool) -> Result<Option<(i32, i32)>, MetadataError> {
+        present.then(|| Ok((self.i32()?, self.i32()?))).transpose()
+    }
+}
+
+/// Writes small metadata files for the tests of the parsers built on top of the metadata.
+#[cfg(test)]
+pub(crate) mod fixture {
+    use super::*;
+
+    #[derive(Default)]
+    pub struct TypeFixture {
+        pub namespace: &'static str,
+        pub name: &'static str,
+        /// Indices of the `Il2CppType` table, which the tests provide separately.
+        pub byval_type: i32,
+        pub parent_type: i32,
+        pub element_type: i32,
+        pub flags: u32,
+        pub bitfield: u32,
+        /// Name and type index of every field.
+        pub fields: Vec<(&'static str, i32)>,
+        pub methods: Vec<MethodFixture>,
+    }
+
+    #[derive(Default)]
+    pub struct MethodFixture {
+        pub name: &'static str,
+        pub return_type: i32,
+        pub flags: u16,
+        pub slot: u16,
+        /// Name and type index of every parameter.
+        pub parameters: Vec<(&'static str, i32)>,
+    }
+
+    /// Lay out a metadata file of the version with a single image holding the types, giving every definition token `0x..000001` onwards.
+    pub fn build(version: MetadataVersion, image_name: &str, types: &[TypeFixture], literals: &[&str]) -> Vec<u8> {
+        let legacy = version.has_custom_attribute_index();
+        let rgctx = version.has_rgctx();
+
+        let mut strings = vec![0];
+        let mut string = |string: &str| {
+            let index = strings.len() as i32;
+            strings.extend_from_slice(string.as_bytes());
+            strings.push(0);
+            index
+        };
+
+        let (mut type_definitions, mut methods, mut fields, mut parameters) = (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        let (mut method_count, mut field_count, mut parameter_count) = (0, 0, 0);
+
+        for (index, ty) in types.iter().enumerate() {
+            let mut words = vec![string(ty.name), string(ty.namespace)];
+            words.extend(legacy.then_some(-1));
+            words.push(ty.byval_type);
+            words.extend(version.has_byref_type_index().then_some(-1));
+            words.extend([-1, ty.parent_type, ty.element_type]);
+            words.extend(if rgctx { vec![-1, 0] } else { vec![] });
+            words.extend([-1, ty.flags as i32, field_count, method_count, -1, -1, -1, -1, -1, -1]);
+            type_definitions.extend(i32s(&words));
+            type_definitions.extend(u16s(&[ty.methods.len() as u16, 0, ty.fields.len() as u16, 0, 0, 0, 0, 0]));
+            type_definitions.extend(i32s(&[ty.bitfield as i32, 0x02000001 + index as i32]));
+
+            for (name, type_index) in &ty.fields {
+                let mut words = vec![string(name), *type_index];
+                words.extend(legacy.then_some(-1));
+                words.push(0x04000001 + field_count);
+                fields.extend(i32s(&words));
+                field_count += 1;
+            }
+
+            for method in &ty.methods {
+                let mut words = vec![string(method.name), index as i32, method.return_type];
+                words.extend(version.has_return_parameter_token().then_some(0x08000000));
+                words.push(parameter_count);
+                words.extend(legacy.then_some(-1));
+                words.push(-1);
+                words.extend(if rgctx { vec![method_count, 0, -1, -1, 0] } else { vec![] });
+                words.push(0x06000001 + method_count);
+                methods.extend(i32s(&words));
+                methods.extend(u16s(&[method.flags, 0, method.slot, method.parameters.len() as u16]));
+                method_count += 1;
+
+                for (name, type_index) in &method.parameters {
+                    let mut words = vec![string(name), 0x08000001 + parameter_count];
+                    words.extend(legacy.then_some(-1));
+                    words.push(*type_index);
+                    parameters.extend(i32s(&words));
+                    parameter_count += 1;
+                }
+            }
+        }
+
+        let image_name = string(image_name);
+        let mut image = vec![image_name, 0, 0, types.len() as i32, 0, 0, -1, 1];
+        image.extend(if legacy { vec![] } else { vec![0, 0] });
+
+        // 24.4 dropped the hash value index of assembly names, which the other revisions of 24 have.
+        let mut assembly = vec![0, if legacy { -1 } else { 0x20000001 }, 0, 0, image_name, 0];
+        assembly.extend((version.major == 24 && version.minor != 4).then_some(-1));
+        assembly.extend([0, 0x8004, 0, 0, 1, 2, 3, 4, 0, 0]);
+
+        let mut literal_data = Vec::new();
+        let mut literal_entries = Vec::new();
+
+        for literal in literals {
+            literal_entries.extend([literal.len() as i32, literal_data.len() as i32]);
+            literal_data.extend_from_slice(literal.as_bytes());
+        }
+
+        let mut sections = vec![i32s(&literal_entries), literal_data, strings, vec![], vec![], methods];
+        sections.extend([vec![], vec![], vec![], vec![], parameters, fields]);
+        sections.extend([vec![], vec![], vec![], vec![], vec![], vec![], vec![], type_definitions]);
+
+        if rgctx {
+            sections.push(vec![]);
+        }
+
+        sections.push(i32s(&image));
+        sections.push(i32s(&assembly));
+
+        // 24.0 and 24.1 are told apart from later revisions by the size of their header.
+        let header_size = if rgctx { HEADER_SIZE_WITH_RGCTX as usize } else { 8 + sections.len() * 8 };
+        let mut file = i32s(&[SANITY as i32, version.major as i32]);
+        let mut data = Vec::new();
+
+        for section in &sections {
+            file.extend(i32s(&[(header_size + data.len()) as i32, section.len() as i32]));
+            data.extend_from_slice(section);
+        }
+
+        file.resize(header_size, 0);
+        file.extend(data);
+        file
+    }
+
+    fn i32s(words: &[i32]) -> Vec<u8> {
+        words.iter().flat_map(|word| word.to_le_bytes()).collect()
+    }
+
+    fn u16s(halves: &[u16]) -> Vec<u8> {
+        halves.iter().flat_map(|half| half.to_le_bytes()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fixture::*, *};
+
+    /// `App.Unit`, with a field and an `Update(value)` method.
+    fn build(version: MetadataVersion) -> Vec<u8> {
+        let unit = TypeFixture {
+            namespace: "App",
+            name: "Unit",
+            byval_type: 10,
+            parent_type: 0,
+            element_type: -1,
+            flags: 0x100001,
+            fields: vec![("hp", 12)],
+            methods: vec![MethodFixture {
+                name: "Update",
+                return_type: 1,
+                flags: 0x86,
+                slot: 4,
+                parameters: vec![("value", 12)],
+            }],
+            ..Default::default()
+        };
+
+        fixture::build(version, "Assembly-CSharp.dll", &[unit], &["Hello"])
+    }
+
+    fn check(version: MetadataVersion) -> Metadata {
+        let metadata = Metadata::parse(build(version)).unwrap();
+        assert_eq!(metadata.version, version);
+
+        let image = metadata.find_image("Assembly-CSharp").unwrap();
+        assert_eq!(metadata.types_of(image).len(), 1);
+
+        let ty = metadata.find_type("App", "Unit").unwrap();
+        assert_eq!(metadata.type_name(ty), "App.Unit");
+        assert_eq!(metadata.type_by_token(image, 0x02000001), Some(ty));
+        assert!(!ty.is_valuetype() && !ty.is_generic());
+
+        let method = &metadata.methods_of(ty)[0];
+        assert_eq!(metadata.string(method.name_index).unwrap(), "Update");
+        assert_eq!(metadata.method_by_token(ty, 0x06000001), Some(method));
+        assert_eq!(method.slot, 4);
+        assert!(!method.is_static());
+
+        let parameters = metadata.parameters_of(method);
+        assert_eq!(parameters.len(), 1);
+        assert_eq!(metadata.string(parameters[0].name_index).unwrap(), "value");
+        assert_eq!(parameters[0].type_index, 12);
+
+        let field = &metadata.fields_of(ty)[0];
+        assert_eq!(metadata.string(field.name_index).unwrap(), "hp");
+        assert_eq!(field.token, 0x04000001);
+
+        let assembly = &metadata.assemblies[0];
+        assert_eq!((assembly.major, assembly.minor, assembly.build, assembly.revision), (1, 2, 3, 4));
+        assert_eq!(assembly.hash_alg, 0x8004);
+
+        assert_eq!(metadata.string_literal(0).as_deref(), Some("Hello"));
+        assert_eq!(metadata.string_literal(1), None);
+
+        metadata
+    }
+
+    #[test]
+    fn parse_v24_0() {
+        let metadata = check(MetadataVersion::new(24, 0));
+        let ty = &metadata.type_definitions[0];
+
+        assert!(metadata.header.rgctx_entries.is_some());
+        assert_eq!(ty.custom_attribute_index, Some(-1));
+        assert_eq!(ty.byref_type_index, Some(-1));
+        assert_eq!(ty.rgctx, Some((-1, 0)));
+        assert_eq!(metadata.methods[0].invoker_index, Some(0));
+        assert_eq!(metadata.assemblies[0].token, None);
+        assert_eq!(metadata.images[0].custom_attributes, None);
+    }
+
+    #[test]
+    fn parse_v24_2_and_v24_4() {
+        for minor in [2, 4] {
+            let metadata = check(MetadataVersion::new(24, minor));
+            let ty = &metadata.type_definitions[0];
+
+            assert!(metadata.header.rgctx_entries.is_none());
+            assert_eq!(ty.custom_attribute_index, None);
+            assert_eq!(ty.byref_type_index, Some(-1));
+            assert_eq!(ty.rgctx, None);
+            assert_eq!(metadata.assemblies[0].token, Some(0x20000001));
+        }
+    }
+
+    #[test]
+    fn parse_v29_and_v31() {
+        for major in [29, 31] {
+            let metadata = check(MetadataVersion::new(major, 0));
+            let method = &metadata.methods[0];
+
+            assert_eq!(metadata.type_definitions[0].byref_type_index, None);
+            assert_eq!(method.method_index, None);
+            assert_eq!(method.return_parameter_token, (major >= 31).then_some(0x08000000));
+            assert_eq!(metadata.images[0].custom_attributes, Some((0, 0)));
+        }
+    }
+
+    #[test]
+    fn reject_invalid_files() {
+        let data = build(MetadataVersion::new(29, 0));
+
+        let mut invalid = data.clone();
+        invalid[0] = 0;
+        assert!(matches!(Metadata::parse(invalid), Err(MetadataError::InvalidSanity)));
+
+        let mut invalid = data.clone();
+        invalid[4] = 23;
+        assert!(matches!(Metadata::parse(invalid), Err(MetadataError::UnsupportedVersion(23))));
+
+        assert!(matches!(Metadata::parse(&data[..data.len() - 1]), Err(MetadataError::Truncated("assemblies"))));
+        assert!(matches!(Metadata::parse(&data[..6]), Err(MetadataError::Truncated("the header"))));
+
+        // A type definition of version 24 is longer than the section
+        let mut invalid = data;
+        invalid[4] = 24;
+        assert!(matches!(Metadata::parse(invalid), Err(MetadataError::InvalidSection(_))));
+    }
+}
@@ -0,0 +1,353 @@
+//! Per-game offset profiles.
+//!
+//! A profile maps [`Symbol`] names to their offset from the start of `.text`, so one build of a plugin can target several games or game updates.
+//! Profiles can be written in TOML or JSON:
+//!
+//! ```toml
+//! name = "Doki Doki Literature Club Plus!"
+//! version = "1.0.0"
+//!
+//! [offsets]
+//! class_from_name = 0x151e30
+//! string_new = "0x14c050"
+//! ```
+//!
+//! Offsets can either be integers or strings, so hexadecimal can be used in JSON as well.
+//! Symbols missing from a profile are looked up through their signature instead.
+
+use std::{collections::BTreeMap, fmt::Write, path::Path};
+
+use serde::{
+    de::{MapAccess, Visitor},
+    Deserialize, Deserializer,
+};
+use thiserror::Error;
+
+use crate::signatures::Symbol;
+
+#[derive(Debug, Error)]
+pub enum ProfileError {
+    #[error("could not read the profile: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse the profile: {0}")]
+    Parse(String),
+    #[error("unknown symbol `{0}`")]
+    UnknownSymbol(String),
+    #[error("the symbol `{0}` is defined more than once")]
+    DuplicateSymbol(String),
+    #[error("invalid offset `{1}` for the symbol `{0}`")]
+    InvalidOffset(String, String),
+    #[error("the profile is missing offsets for symbols without a signature: {}", .0.join(", "))]
+    MissingSymbols(Vec<&'static str>),
+}
+
+/// The offsets of the functions used by the crate for one specific game executable.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OffsetProfile {
+    /// Name of the game the profile was made for.
+    pub name: Option<String>,
+    /// Version of the game the profile was made for.
+    pub version: Option<String>,
+    offsets: BTreeMap<Symbol, usize>,
+}
+
+impl OffsetProfile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The offsets of Doki Doki Literature Club Plus!, which this crate originally targeted.
+    pub fn ddlc() -> Self {
+        Self::from_toml(include_str!("../profiles/ddlc.toml")).expect("the embedded DDLC profile should be valid")
+    }
+
+    pub fn from_toml(source: impl AsRef<str>) -> Result<Self, ProfileError> {
+        toml::from_str::<RawProfile>(source.as_ref())
+            .map_err(|err| ProfileError::Parse(err.to_string()))?
+            .try_into()
+    }
+
+    pub fn from_json(source: impl AsRef<str>) -> Result<Self, ProfileError> {
+        serde_json::from_str::<RawProfile>(source.as_ref())
+            .map_err(|err| ProfileError::Parse(err.to_string()))?
+            .try_into()
+    }
+
+    /// Load a profile from a file, such as one on the SD card.
+    ///
+    /// Files ending with `.json` are parsed as JSON, anything else as TOML.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ProfileError> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)?;
+
+        if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")) {
+            Self::from_json(source)
+        } else {
+            Self::from_toml(source)
+        }
+    }
+
+    pub fn get(&self, symbol: Symbol) -> Option<usize> {
+        self.offsets.get(&symbol).copied()
+    }
+
+    pub fn set(&mut self, symbol: Symbol, offset: usize) -> Option<usize> {
+        self.offsets.insert(symbol, offset)
+    }
+
+    pub fn remove(&mut self, symbol: Symbol) -> Option<usize> {
+        self.offsets.remove(&symbol)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Symbol, usize)> + '_ {
+        self.offsets.iter().map(|(symbol, offset)| (*symbol, *offset))
+    }
+
+    /// The symbols that have no signature and are not provided by this profile, meaning they cannot be resolved at all.
+    pub fn missing(&self) -> Vec<Symbol> {
+        Symbol::ALL
+            .iter()
+            .copied()
+            .filter(|symbol| !symbol.has_signature() && self.get(*symbol).is_none())
+            .collect()
+    }
+
+    /// Make sure every [required](Symbol::is_required) symbol can be resolved with this profile, either through the offsets it contains or through a signature.
+    ///
+    /// Optional symbols are still listed by [`missing`](Self::missing), but only disable the features relying on them.
+    pub fn validate(&self) -> Result<(), ProfileError> {
+        let missing = self.missing().into_iter().filter(|symbol| symbol.is_required()).collect::<Vec<_>>();
+
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            Err(ProfileError::MissingSymbols(missing.into_iter().map(Symbol::name).collect()))
+        }
+    }
+
+    /// Write the profile as TOML, with offsets in hexadecimal.
+    pub fn to_toml(&self) -> String {
+        let mut out = String::new();
+
+        if let Some(name) = &self.name {
+            writeln!(out, "name = {:?}", name).unwrap();
+        }
+
+        if let Some(version) = &self.version {
+            writeln!(out, "version = {:?}", version).unwrap();
+        }
+
+        if !out.is_empty() {
+            out.push('\n');
+        }
+
+        out.push_str("[offsets]\n");
+
+        for (symbol, offset) in self.iter() {
+            writeln!(out, "{} = {:#x}", symbol.name(), offset).unwrap();
+        }
+
+        out
+    }
+}
+
+// Intermediate representation, so the entries can be checked one by one with proper errors.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawProfile {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    offsets: RawOffsets,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawOffset {
+    Integer(u64),
+    String(String),
+}
+
+impl RawOffset {
+    fn parse(&self) -> Option<usize> {
+        match self {
+            RawOffset::Integer(offset) => usize::try_from(*offset).ok(),
+            RawOffset::String(offset) => {
+                let offset = offset.trim();
+
+                match offset.strip_prefix("0x").or_else(|| offset.strip_prefix("0X")) {
+                    Some(hex) => usize::from_str_radix(hex, 16).ok(),
+                    None => offset.parse().ok(),
+                }
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for RawOffset {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            RawOffset::Integer(offset) => write!(f, "{}", offset),
+            RawOffset::String(offset) => write!(f, "{}", offset),
+        }
+    }
+}
+
+/// Keeps every entry of the table in order, duplicates included, instead of silently overwriting them like a map would.
+#[derive(Default)]
+struct RawOffsets(Vec<(String, RawOffset)>);
+
+impl<'de> Deserialize<'de> for RawOffsets {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct OffsetsVisitor;
+
+        impl<'de> Visitor<'de> for OffsetsVisitor {
+            type Value = RawOffsets;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a table of symbol names to offsets")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut entries = Vec::new();
+
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+
+                Ok(RawOffsets(entries))
+            }
+        }
+
+        deserializer.deserialize_map(OffsetsVisitor)
+    }
+}
+
+impl TryFrom<RawProfile> for OffsetProfile {
+    type Error = ProfileError;
+
+    fn try_from(raw: RawProfile) -> Result<Self, Self::Error> {
+        let mut profile = OffsetProfile {
+            name: raw.name,
+            version: raw.version,
+            offsets: BTreeMap::new(),
+        };
+
+        for (name, offset) in raw.offsets.0 {
+            let symbol = Symbol::from_name(&name).ok_or_else(|| ProfileError::UnknownSymbol(name.clone()))?;
+            let value = offset.parse().ok_or_else(|| ProfileError::InvalidOffset(name.clone(), offset.to_string()))?;
+
+            if profile.set(symbol, value).is_some() {
+                return Err(ProfileError::DuplicateSymbol(name));
+            }
+        }
+
+        Ok(profile)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_toml() {
+        let profile = OffsetProfile::from_toml(
+            r#"
+            name = "Test"
+            version = "1.0.2"
+
+            [offsets]
+            class_from_name = 0x151e30
+            string_new = "0x14c050"
+            object_new = "1234"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(profile.name.as_deref(), Some("Test"));
+        assert_eq!(profile.version.as_deref(), Some("1.0.2"));
+        assert_eq!(profile.get(Symbol::ClassFromName), Some(0x151e30));
+        assert_eq!(profile.get(Symbol::StringNew), Some(0x14c050));
+        assert_eq!(profile.get(Symbol::ObjectNew), Some(1234));
+        assert_eq!(profile.get(Symbol::ClassInit), None);
+    }
+
+    #[test]
+    fn parse_json() {
+        let profile = OffsetProfile::from_json(r#"{ "name": "Test", "offsets": { "class_init": "0x14bd30", "array_new": 1358736 } }"#).unwrap();
+
+        assert_eq!(profile.name.as_deref(), Some("Test"));
+        assert_eq!(profile.version, None);
+        assert_eq!(profile.get(Symbol::ClassInit), Some(0x14bd30));
+        assert_eq!(profile.get(Symbol::ArrayNew), Some(0x14bb90));
+    }
+
+    #[test]
+    fn reject_unknown_keys() {
+        assert!(matches!(
+            OffsetProfile::from_toml("[offsets]\nnot_a_symbol = 0x10"),
+            Err(ProfileError::UnknownSymbol(name)) if name == "not_a_symbol"
+        ));
+        assert!(matches!(
+            OffsetProfile::from_json(r#"{ "offsets": { "not_a_symbol": 16 } }"#),
+            Err(ProfileError::UnknownSymbol(name)) if name == "not_a_symbol"
+        ));
+
+        assert!(matches!(OffsetProfile::from_toml("game = \"Test\""), Err(ProfileError::Parse(_))));
+        assert!(matches!(OffsetProfile::from_json(r#"{ "game": "Test" }"#), Err(ProfileError::Parse(_))));
+    }
+
+    #[test]
+    fn reject_duplicate_keys() {
+        // The TOML parser already refuses duplicate keys on its own
+        assert!(matches!(
+            OffsetProfile::from_toml("[offsets]\nclass_init = 0x10\nclass_init = 0x20"),
+            Err(ProfileError::Parse(_))
+        ));
+        assert!(matches!(
+            OffsetProfile::from_json(r#"{ "offsets": { "class_init": 16, "class_init": 32 } }"#),
+            Err(ProfileError::DuplicateSymbol(name)) if name == "class_init"
+        ));
+    }
+
+    #[test]
+    fn reject_invalid_offsets() {
+        assert!(matches!(
+            OffsetProfile::from_toml("[offsets]\nclass_init = \"0xzz\""),
+            Err(ProfileError::InvalidOffset(name, offset)) if name == "class_init" && offset == "0xzz"
+        ));
+        assert!(matches!(OffsetProfile::from_json(r#"{ "offsets": { "class_init": -1 } }"#), Err(ProfileError::Parse(_))));
+    }
+
+    #[test]
+    fn validate_missing_symbols() {
+        let mut profile = OffsetProfile::ddlc();
+        profile.remove(Symbol::AssemblyGetAllAssemblies);
+        profile.remove(Symbol::MethodFromName);
+
+        match profile.validate() {
+            Err(ProfileError::MissingSymbols(missing)) => assert_eq!(missing, vec!["assembly_getallassemblies", "method_from_name"]),
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        assert!(profile.missing().contains(&Symbol::MethodFromName));
+    }
+
+    #[test]
+    fn validate_ddlc() {
+        let profile = OffsetProfile::ddlc();
+        profile.validate().unwrap();
+
+        // Only the optional features can be missing from the bundled profile
+        assert!(profile.missing().iter().all(|symbol| !symbol.is_required()));
+    }
+
+    #[test]
+    fn toml_round_trip() {
+        let profile = OffsetProfile::ddlc();
+        assert_eq!(OffsetProfile::from_toml(profile.to_toml()).unwrap(), profile);
+    }
+}
@@ -0,0 +1,207 @@
+//! Byte pattern scanning over plain slices.
+//!
+//! Patterns are written the same way as the ones `lazysimd` accepts: space-separated hexadecimal bytes, where `??` matches any byte.
+//! A single `?` can also replace one nibble (`4?`, `?f`) for instructions where only the register changes.
+
+use std::str::FromStr;
+
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ScanError {
+    #[error("invalid pattern token `{0}`")]
+    InvalidToken(String),
+    #[error("the pattern is empty or only made of wildcards")]
+    EmptyPattern,
+    #[error("no signature is known for `{0}`")]
+    NoSignature(&'static str),
+    #[error("none of the signatures for `{0}` matched")]
+    NotFound(&'static str),
+    #[error("the signature for `{name}` matched {count} locations")]
+    Ambiguous { name: &'static str, count: usize },
+}
+
+/// A parsed byte pattern. Every byte of the haystack is compared as `(byte & mask) == value`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    values: Vec<u8>,
+    masks: Vec<u8>,
+    // Index of the first byte without any wildcard, used to quickly skip over the haystack.
+    anchor: usize,
+}
+
+impl Pattern {
+    pub fn new(pattern: impl AsRef<str>) -> Result<Self, ScanError> {
+        let mut values = Vec::new();
+        let mut masks = Vec::new();
+
+        for token in pattern.as_ref().split_whitespace() {
+            let (value, mask) = parse_token(token)?;
+            values.push(value);
+            masks.push(mask);
+        }
+
+        let anchor = masks.iter().position(|mask| *mask == 0xff).ok_or(ScanError::EmptyPattern)?;
+
+        Ok(Self { values, masks, anchor })
+    }
+
+    /// Length of the pattern in bytes, wildcards included.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Check if the pattern matches the start of the provided slice.
+    pub fn matches(&self, data: &[u8]) -> bool {
+        data.len() >= self.len()
+            && data
+                .iter()
+                .zip(self.values.iter().zip(&self.masks))
+                .all(|(byte, (value, mask))| byte & mask == *value)
+    }
+
+    /// Iterate over the offset of every location in the haystack matching the pattern.
+    pub fn find_iter<'a>(&'a self, haystack: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+        let anchor_value = self.values[self.anchor];
+        let last = haystack.len().saturating_sub(self.len() - 1);
+
+        haystack[self.anchor.min(haystack.len())..]
+            .iter()
+            .enumerate()
+            .filter(move |(_, byte)| **byte == anchor_value)
+            .map(|(index, _)| index)
+            .take_while(move |start| *start < last)
+            .filter(move |start| self.matches(&haystack[*start..]))
+    }
+
+    /// Get the offset of the first match in the haystack, if any.
+    pub fn find(&self, haystack: &[u8]) -> Option<usize> {
+        self.find_iter(haystack).next()
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = ScanError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+fn parse_token(token: &str) -> Result<(u8, u8), ScanError> {
+    let invalid = || ScanError::InvalidToken(token.to_string());
+
+    let nibbles = token.chars().collect::<Vec<_>>();
+
+    if nibbles.len() != 2 {
+        return Err(invalid());
+    }
+
+    nibbles.iter().try_fold((0u8, 0u8), |(value, mask), nibble| {
+        if *nibble == '?' {
+            Ok((value << 4, mask << 4))
+        } else {
+            let digit = nibble.to_digit(16).ok_or_else(invalid)? as u8;
+            Ok(((value << 4) | digit, (mask << 4) | 0xf))
+        }
+    })
+}
+
+/// Look for a single location matched by the first pattern that succeeds.
+///
+/// Patterns are tried in order, so fallbacks for other game versions can be appended after the main one.
+/// A pattern matching more than one location is rejected, as picking one at random would mean calling into the wrong function.
+pub fn find_unique(name: &'static str, haystack: &[u8], patterns: &[Pattern]) -> Result<usize, ScanError> {
+    if patterns.is_empty() {
+        return Err(ScanError::NoSignature(name));
+    }
+
+    for pattern in patterns {
+        let mut matches = pattern.find_iter(haystack);
+
+        if let Some(offset) = matches.next() {
+            let extra = matches.count();
+
+            if extra != 0 {
+                return Err(ScanError::Ambiguous { name, count: extra + 1 });
+            }
+
+            return Ok(offset);
+        }
+    }
+
+    Err(ScanError::NotFound(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Two fake functions separated by padding, the second one only differing by its first register
+    const FIXTURE: &[u8] = &[
+        0x00, 0x00, 0x00, 0x00, //
+        0xfd, 0x7b, 0xbe, 0xa9, 0xf3, 0x0b, 0x00, 0xf9, 0x12, 0x34, 0x56, 0x78, 0xc0, 0x03, 0x5f, 0xd6, //
+        0x1f, 0x20, 0x03, 0xd5, //
+        0xfd, 0x7b, 0xbe, 0xa9, 0xf4, 0x0b, 0x00, 0xf9, 0x9a, 0xbc, 0xde, 0xf0, 0xc0, 0x03, 0x5f, 0xd6,
+    ];
+
+    fn patterns(patterns: &[&str]) -> Vec<Pattern> {
+        patterns.iter().map(|pattern| pattern.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn parse_tokens() {
+        assert_eq!(parse_token("a9"), Ok((0xa9, 0xff)));
+        assert_eq!(parse_token("??"), Ok((0x00, 0x00)));
+        assert_eq!(parse_token("4?"), Ok((0x40, 0xf0)));
+        assert_eq!(parse_token("?f"), Ok((0x0f, 0x0f)));
+        assert_eq!(parse_token("a"), Err(ScanError::InvalidToken("a".to_string())));
+        assert_eq!(parse_token("zz"), Err(ScanError::InvalidToken("zz".to_string())));
+    }
+
+    #[test]
+    fn reject_wildcard_only_patterns() {
+        assert_eq!(Pattern::new(""), Err(ScanError::EmptyPattern));
+        assert_eq!(Pattern::new("?? ?? 0? ??"), Err(ScanError::EmptyPattern));
+    }
+
+    #[test]
+    fn find_exact_and_wildcards() {
+        let exact: Pattern = "f4 0b 00 f9".parse().unwrap();
+        assert_eq!(exact.find(FIXTURE), Some(28));
+
+        let wildcard: Pattern = "?? ?? ?? ?? f3 0b 00 f9 ?? ?? ?? ?? c0 03".parse().unwrap();
+        assert_eq!(wildcard.find(FIXTURE), Some(4));
+
+        let nibble: Pattern = "fd 7b be a9 f? 0b 00 f9".parse().unwrap();
+        assert_eq!(nibble.find_iter(FIXTURE).collect::<Vec<_>>(), vec![4, 24]);
+    }
+
+    #[test]
+    fn no_match_past_the_end() {
+        // Matches the start of the last function, but runs past the end of the haystack
+        let pattern: Pattern = "c0 03 5f d6 00".parse().unwrap();
+        assert_eq!(pattern.find_iter(FIXTURE).count(), 0);
+
+        assert_eq!(pattern.find(&[]), None);
+    }
+
+    #[test]
+    fn find_unique_with_fallbacks() {
+        assert_eq!(find_unique("test", FIXTURE, &patterns(&["12 34 56 78"])), Ok(12));
+        assert_eq!(find_unique("test", FIXTURE, &patterns(&["ff ff ff ff", "9a bc de f0"])), Ok(32));
+
+        // The first pattern matching anything wins, even if a later one would have been unique
+        assert_eq!(
+            find_unique("test", FIXTURE, &patterns(&["fd 7b be a9", "12 34 56 78"])),
+            Err(ScanError::Ambiguous { name: "test", count: 2 })
+        );
+
+        assert_eq!(find_unique("test", FIXTURE, &patterns(&["ff ff ff ff"])), Err(ScanError::NotFound("test")));
+        assert_eq!(find_unique("test", FIXTURE, &[]), Err(ScanError::NoSignature("test")));
+    }
+}
@@ -0,0 +1,231 @@
+//! The set of functions the `unity` crate needs from the game executable, along with the byte patterns used to locate them in `.text`.
+//!
+//! Switch Il2Cpp games ship without symbols, so every runtime function has to be found by looking for its machine code.
+//! The few that do not have a signature yet must be provided by an [`OffsetProfile`](crate::profile::OffsetProfile).
+
+use crate::scan::{self, Pattern, ScanError};
+
+const INIT: &str = "fd 7b be a9 f3 0b 00 f9 fd 03 00 91 f3 03 00 aa ?? ?? ?? ?? ?? ?? ?? ?? c0 00 80 52 ?? ?? ?? ?? e0 03 13 aa ?? ?? ?? ?? f3 0b 40 f9 00 00 00 12 fd 7b c2 a8 c0 03 5f d6";
+
+/// A function of the game executable the crate calls into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Symbol {
+    Il2CppInit,
+    Init,
+    GetImageByAssemblyName,
+    ClassFromName,
+    ObjectNew,
+    GetMethodFromNameFlags,
+    ArrayNewSpecific,
+    ArrayNew,
+    TypeGetObject,
+    DomainGetCurrent,
+    ClassFromIl2CppType,
+    ClassInit,
+    StringNew,
+    AssemblyGetAllAssemblies,
+    MethodFromName,
+    GcMallocKind,
+    SetupGcDescriptor,
+    GcWbarrierSetField,
+    RuntimeInvoke,
+    RaiseException,
+    ValueBox,
+    GcAllocFixed,
+    GcHandleNew,
+    GcHandleNewWeakref,
+    GcHandleGetTarget,
+    GcHandleFree,
+    StringReplace,
+    SpriteCreate2,
+}
+
+impl Symbol {
+    pub const ALL: &'static [Symbol] = &[
+        Symbol::Il2CppInit,
+        Symbol::Init,
+        Symbol::GetImageByAssemblyName,
+        Symbol::ClassFromName,
+        Symbol::ObjectNew,
+        Symbol::GetMethodFromNameFlags,
+        Symbol::ArrayNewSpecific,
+        Symbol::ArrayNew,
+        Symbol::TypeGetObject,
+        Symbol::DomainGetCurrent,
+        Symbol::ClassFromIl2CppType,
+        Symbol::ClassInit,
+        Symbol::StringNew,
+        Symbol::AssemblyGetAllAssemblies,
+        Symbol::MethodFromName,
+        Symbol::GcMallocKind,
+        Symbol::SetupGcDescriptor,
+        Symbol::GcWbarrierSetField,
+        Symbol::RuntimeInvoke,
+        Symbol::RaiseException,
+        Symbol::ValueBox,
+        Symbol::GcAllocFixed,
+        Symbol::GcHandleNew,
+        Symbol::GcHandleNewWeakref,
+        Symbol::GcHandleGetTarget,
+        Symbol::GcHandleFree,
+        Symbol::StringReplace,
+        Symbol::SpriteCreate2,
+    ];
+
+    /// The name used to refer to the symbol in logs and offset profiles.
+    pub fn name(self) -> &'static str {
+        match self {
+            Symbol::Il2CppInit => "il2cpp_init",
+            Symbol::Init => "init",
+            Symbol::GetImageByAssemblyName => "get_image_by_assembly_name",
+            Symbol::ClassFromName => "class_from_name",
+            Symbol::ObjectNew => "object_new",
+            Symbol::GetMethodFromNameFlags => "get_method_from_name_flags",
+            Symbol::ArrayNewSpecific => "array_new_specific",
+            Symbol::ArrayNew => "array_new",
+            Symbol::TypeGetObject => "type_get_object",
+            Symbol::DomainGetCurrent => "domain_get_current",
+            Symbol::ClassFromIl2CppType => "class_from_il2cpptype",
+            Symbol::ClassInit => "class_init",
+            Symbol::StringNew => "string_new",
+            Symbol::AssemblyGetAllAssemblies => "assembly_getallassemblies",
+            Symbol::MethodFromName => "method_from_name",
+            Symbol::GcMallocKind => "gc_malloc_kind",
+            Symbol::SetupGcDescriptor => "setup_gc_descriptor",
+            Symbol::GcWbarrierSetField => "gc_wbarrier_set_field",
+            Symbol::RuntimeInvoke => "runtime_invoke",
+            Symbol::RaiseException => "raise_exception",
+            Symbol::ValueBox => "value_box",
+            Symbol::GcAllocFixed => "gc_alloc_fixed",
+            Symbol::GcHandleNew => "gchandle_new",
+            Symbol::GcHandleNewWeakref => "gchandle_new_weakref",
+            Symbol::GcHandleGetTarget => "gchandle_get_target",
+            Symbol::GcHandleFree => "gchandle_free",
+            Symbol::StringReplace => "string_replace",
+            Symbol::SpriteCreate2 => "sprite_create2",
+        }
+    }
+
+    pub fn from_name(name: impl AsRef<str>) -> Option<Self> {
+        Self::ALL.iter().copied().find(|symbol| symbol.name() == name.as_ref())
+    }
+
+    /// The patterns to try for this symbol, in order of preference.
+    ///
+    /// Empty for symbols that can only be provided by an offset profile.
+    pub fn patterns(self) -> &'static [&'static str] {
+        match self {
+            Symbol::Init => &[INIT],
+            // Get Image By Assembly Name*
+            Symbol::GetImageByAssemblyName => &["ff 03 01 d1 fd 7b 01 a9 fd 43 00 91 f6 57 02 a9 f4 4f 03 a9 f3 03 00 aa ?? ?? ?? ?? ?? ?? ?? ?? 08 21 32 91 f4 03 00 aa ?? ?? ?? ?? df 02 08 eb 80 01 00 54 ?? ?? ?? ??"],
+            Symbol::ClassFromName => &["ff c3 02 d1 fd 7b 05 a9 fd 43 01 91 fc 6f 06 a9 fa 67 07 a9 f8 5f 08 a9 f6 57 09 a9 f4 4f 0a a9 f8 03 00 aa 16 0f 43 f8 f9 03 02 aa f4 03 01 aa"],
+            Symbol::ObjectNew => &["ff 43 01 d1 fd 7b 01 a9 fd 43 00 91 f7 13 00 f9 f6 57 03 a9 f4 4f 04 a9 08 c8 44 39 f3 03 00 aa e8 02 10 37"],
+            Symbol::GetMethodFromNameFlags => &["ff 43 01 d1 fd 7b 01 a9 fd 43 00 91 f8 5f 02 a9 f6 57 03 a9 f4 4f 04 a9 08 c8 44 39 f3 03 03 2a f6 03 02 2a f4 03 01 aa f5 03 00 aa"],
+            Symbol::ArrayNewSpecific => &["ff 03 01 d1 fd 7b 01 a9 fd 43 00 91 f6 57 02 a9 f4 4f 03 a9 08 c8 44 39 f4 03 01 aa f3 03 00 aa"],
+            Symbol::ArrayNew => &["fd 7b be a9 f3 0b 00 f9 fd 03 00 91 f3 03 01 aa 21 00 80 52 e2 03 1f 2a ?? ?? ?? ?? e1 03 13 aa f3 0b 40 f9 fd 7b c2 a8"],
+            Symbol::TypeGetObject => &["ff 03 01 d1 fd 7b 01 a9 fd 43 00 91 f5 13 00 f9 f4 4f 03 a9 ?? ?? ?? ?? ?? ?? ?? ?? a0 0f 00 f9 e0 03 13 aa ff 07 00 f9"],
+            Symbol::DomainGetCurrent => &["fd 7b be a9 f3 0b 00 f9 fd 03 00 91 ?? ?? ?? ?? ?? ?? ?? ?? ?? ?? ?? ?? 80 00 00 b4 f3 0b 40 f9 fd 7b c2 a8 c0 03 5f d6"],
+            Symbol::ClassFromIl2CppType => &["ff 03 01 d1 fd 7b 01 a9 fd 43 00 91 f6 57 02 a9 f4 4f 03 a9 f3 03 00 aa e0 03 1f aa 68 2a 40 39 08 05 00 51 1f 75 00 71"],
+            Symbol::ClassInit => &["fd 7b bd a9 f5 0b 00 f9 fd 03 00 91 f4 4f 02 a9 08 c8 44 39 08 03 10 37 ?? ?? ?? ?? ?? ?? ?? ?? f3 03 00 aa b5 0f 00 f9"],
+            Symbol::StringNew => &["ff 03 01 d1 fd 7b 02 a9 fd 83 00 91 f4 4f 03 a9 f3 03 00 aa ?? ?? ?? ?? 01 7c 40 92 e8 23 00 91 e0 03 13 aa f4 23 00 91 ?? ?? ?? ?? e8 23 40 39 0b fd 41 d3 e9 0f 40 f9"],
+            // Only `Init` is matched by the `il2cpp_init` pattern, this one has to come from a profile for now
+            Symbol::Il2CppInit
+            | Symbol::AssemblyGetAllAssemblies
+            | Symbol::MethodFromName
+            | Symbol::GcMallocKind
+            | Symbol::SetupGcDescriptor
+            | Symbol::GcWbarrierSetField
+            | Symbol::RuntimeInvoke
+            | Symbol::RaiseException
+            | Symbol::ValueBox
+            | Symbol::GcAllocFixed
+            | Symbol::GcHandleNew
+            | Symbol::GcHandleNewWeakref
+            | Symbol::GcHandleGetTarget
+            | Symbol::GcHandleFree
+            | Symbol::StringReplace
+            | Symbol::SpriteCreate2 => &[],
+        }
+    }
+
+    /// Whether the crate cannot work at all without this symbol.
+    ///
+    /// The other symbols back optional features, such as GC handles or managed exceptions, which return an error when used without them.
+    pub fn is_required(self) -> bool {
+        !matches!(
+            self,
+            Symbol::GcWbarrierSetField
+                | Symbol::RuntimeInvoke
+                | Symbol::RaiseException
+                | Symbol::ValueBox
+                | Symbol::GcAllocFixed
+                | Symbol::GcHandleNew
+                | Symbol::GcHandleNewWeakref
+                | Symbol::GcHandleGetTarget
+                | Symbol::GcHandleFree
+                | Symbol::StringReplace
+                | Symbol::SpriteCreate2
+        )
+    }
+
+    pub fn has_signature(self) -> bool {
+        !self.patterns().is_empty()
+    }
+
+    /// Look for the symbol in the provided `.text` section and return its offset from the start of the slice.
+    pub fn scan(self, text: &[u8]) -> Result<usize, ScanError> {
+        let patterns = self
+            .patterns()
+            .iter()
+            .map(|pattern| pattern.parse())
+            .collect::<Result<Vec<Pattern>, _>>()?;
+
+        scan::find_unique(self.name(), text, &patterns)
+    }
+}
+
+/// Scan for every known symbol at once.
+pub fn scan_all(text: &[u8]) -> Vec<(Symbol, Result<usize, ScanError>)> {
+    Symbol::ALL.iter().map(|symbol| (*symbol, symbol.scan(text))).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_round_trip() {
+        for symbol in Symbol::ALL {
+            assert_eq!(Symbol::from_name(symbol.name()), Some(*symbol));
+        }
+
+        assert_eq!(Symbol::from_name("not_a_symbol"), None);
+    }
+
+    #[test]
+    fn patterns_are_valid_and_distinct() {
+        let mut seen = std::collections::HashMap::new();
+
+        for symbol in Symbol::ALL {
+            for pattern in symbol.patterns() {
+                pattern.parse::<Pattern>().unwrap_or_else(|err| panic!("{}: {}", symbol.name(), err));
+
+                // Two symbols sharing a pattern would always resolve to the same function
+                if let Some(other) = seen.insert(*pattern, *symbol) {
+                    panic!("{} and {} share a pattern", other.name(), symbol.name());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn scan_fixture() {
+        let mut text = vec![0u8; 0x40];
+        let init = "fd 7b be a9 f3 0b 00 f9 fd 03 00 91 f3 03 00 aa 11 11 11 11 22 22 22 22 c0 00 80 52 33 33 33 33 e0 03 13 aa 44 44 44 44 f3 0b 40 f9 00 00 00 12 fd 7b c2 a8 c0 03 5f d6";
+        text.extend(init.split_whitespace().map(|byte| u8::from_str_radix(byte, 16).unwrap()));
+
+        assert_eq!(Symbol::Init.scan(&text), Ok(0x40));
+        assert_eq!(Symbol::ClassInit.scan(&text), Err(ScanError::NotFound("class_init")));
+        assert_eq!(Symbol::Il2CppInit.scan(&text), Err(ScanError::NoSignature("il2cpp_init")));
+    }
+}
@@ -0,0 +1,109 @@
+//! The kinds of types Il2Cpp distinguishes, as stored in the `type` bits of an `Il2CppType`.
+
+/// Mirrors `Il2CppTypeEnum`, which itself follows the `ELEMENT_TYPE_*` values of ECMA-335.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Il2CppTypeEnum {
+    End = 0x00,
+    Void = 0x01,
+    Boolean = 0x02,
+    Char = 0x03,
+    I1 = 0x04,
+    U1 = 0x05,
+    I2 = 0x06,
+    U2 = 0x07,
+    I4 = 0x08,
+    U4 = 0x09,
+    I8 = 0x0a,
+    U8 = 0x0b,
+    R4 = 0x0c,
+    R8 = 0x0d,
+    String = 0x0e,
+    Ptr = 0x0f,
+    ByRef = 0x10,
+    ValueType = 0x11,
+    Class = 0x12,
+    Var = 0x13,
+    Array = 0x14,
+    GenericInst = 0x15,
+    TypedByRef = 0x16,
+    I = 0x18,
+    U = 0x19,
+    FnPtr = 0x1b,
+    Object = 0x1c,
+    SzArray = 0x1d,
+    MVar = 0x1e,
+    CModReqd = 0x1f,
+    CModOpt = 0x20,
+    Internal = 0x21,
+    Modifier = 0x40,
+    Sentinel = 0x41,
+    Pinned = 0x45,
+    Enum = 0x55,
+    Il2CppTypeIndex = 0xff,
+}
+
+impl Il2CppTypeEnum {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        use Il2CppTypeEnum::*;
+
+        Some(match value {
+            0x00 => End,
+            0x01 => Void,
+            0x02 => Boolean,
+            0x03 => Char,
+            0x04 => I1,
+            0x05 => U1,
+            0x06 => I2,
+            0x07 => U2,
+            0x08 => I4,
+            0x09 => U4,
+            0x0a => I8,
+            0x0b => U8,
+            0x0c => R4,
+            0x0d => R8,
+            0x0e => String,
+            0x0f => Ptr,
+            0x10 => ByRef,
+            0x11 => ValueType,
+            0x12 => Class,
+            0x13 => Var,
+            0x14 => Array,
+            0x15 => GenericInst,
+            0x16 => TypedByRef,
+            0x18 => I,
+            0x19 => U,
+            0x1b => FnPtr,
+            0x1c => Object,
+            0x1d => SzArray,
+            0x1e => MVar,
+            0x1f => CModReqd,
+            0x20 => CModOpt,
+            0x21 => Internal,
+            0x40 => Modifier,
+            0x41 => Sentinel,
+            0x45 => Pinned,
+            0x55 => Enum,
+            0xff => Il2CppTypeIndex,
+            _ => return None,
+        })
+    }
+
+    /// Whether the type is one of the built-in numeric types, `bool` or `char`.
+    pub fn is_primitive(self) -> bool {
+        matches!(self as u8, 0x02..=0x0d | 0x18 | 0x19)
+    }
+
+    /// Size in bytes of primitive types, pointers and references.
+    pub fn size(self) -> Option<usize> {
+        use Il2CppTypeEnum::*;
+
+        match self {
+            Boolean | I1 | U1 => Some(1),
+            Char | I2 | U2 => Some(2),
+            I4 | U4 | R4 => Some(4),
+            I8 | U8 | R8 | I | U | Ptr | FnPtr | String | Class | Object | SzArray | Array => Some(8),
+            _ => None,
+        }
+    }
+}
@@ -1,12 +1,33 @@
 use proc_macro::TokenStream;
 use proc_macro2::{Ident, Span};
 use quote::quote;
-use syn::Attribute;
+use syn::{ext::IdentExt, Attribute, LitStr};
 use syn::{parse_macro_input, Type, TypePath, ItemStruct, Generics, GenericParam, punctuated::Punctuated, Token};
 use syn::parse::Result;
 
 #[derive(deluxe::ParseMetaItem)]
-struct ClassData(String, String);
+struct ClassData(String, String, #[deluxe(default)] ClassMode);
+
+/// Where the class comes from, as the optional last argument of the attribute.
+#[derive(Default, PartialEq)]
+enum ClassMode {
+    /// The class is looked up in the game.
+    #[default]
+    Lookup,
+    /// The class is defined in Rust and injected into the runtime, see `ClassInjector`.
+    Inject,
+}
+
+impl deluxe::ParseMetaItem for ClassMode {
+    fn parse_meta_item(input: syn::parse::ParseStream, _mode: deluxe::ParseMode) -> deluxe::Result<Self> {
+        let ident = input.parse::<Ident>()?;
+
+        match ident.to_string().as_str() {
+            "inject" => Ok(Self::Inject),
+            _ => Err(syn::Error::new(ident.span(), "expected `inject`")),
+        }
+    }
+}
 
 #[derive(Default, Debug)]
 struct ClassAttributes {
@@ -17,7 +38,7 @@ struct ClassAttributes {
 pub fn class(attrs: TokenStream, item: TokenStream) -> TokenStream {
     let mut input = parse_macro_input!(item as ItemStruct);
 
-    let ClassData(namespace, class) = match deluxe::parse(attrs) {
+    let ClassData(namespace, class, mode) = match deluxe::parse(attrs) {
         Ok(info) => info,
         Err(err) => return err.to_compile_error().into(),
     };
@@ -33,20 +54,39 @@ pub fn class(attrs: TokenStream, item: TokenStream) -> TokenStream {
     // let generic_type = &generics.type_params();
     // dbg!(&type_generics);
 
-    let static_method = static_type.and_then(|static_ty| {
-        Some(quote! {
+    let static_method = static_type.map(|static_ty| {
+        quote! {
             impl #impl_generics #name #type_generics #where_clause {
                 pub fn get_static_fields(&self) -> &#static_ty {
                     unsafe { std::mem::transmute(self.klass.static_fields) }
                 }
             }
-        })
+        }
     });
 
-    let fields = input.fields;
+    let mut fields = input.fields;
+    let layout = match field_layouts(&mut fields) {
+        Ok(layout) => layout,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    let layout_names = layout.iter().map(|field| &field.name);
+    let layout_idents = layout.iter().map(|field| &field.ident);
+    let layout_padding = layout.iter().map(|field| field.padding);
 
 
     let ctx = super::utils::context();
+
+    let injector = (mode == ClassMode::Inject).then(|| {
+        quote! {
+            impl #impl_generics #name #type_generics #where_clause {
+                /// Start declaring the class to inject, with instances the size of this structure.
+                pub fn injector() -> #ctx::ClassInjector {
+                    #ctx::ClassInjector::new(#namespace, #class).instance_size(std::mem::size_of::<Self>())
+                }
+            }
+        }
+    });
     
     quote! {
         /// New Il2CppObject structure using the name from the struct item
@@ -65,6 +105,8 @@ pub fn class(attrs: TokenStream, item: TokenStream) -> TokenStream {
         // TODO: Make sure the type provided implements Il2CppClassData or something
         #static_method
 
+        #injector
+
         impl #impl_generics #name #type_generics #where_clause {
             pub fn get_class(&self) -> &#ctx::Il2CppClass {
                 &self.klass
@@ -73,6 +115,36 @@ pub fn class(attrs: TokenStream, item: TokenStream) -> TokenStream {
             pub fn get_class_mut(&mut self) -> &mut #ctx::Il2CppClass {
                 &mut self.klass
             }
+
+            /// Get the object as an instance of another class, see [`Il2CppObject::cast`](#ctx::Il2CppObject::cast).
+            pub fn cast<C: #ctx::Il2CppClassData>(&self) -> #ctx::Il2CppResult<&C> {
+                unsafe { &*(self as *const Self as *const #ctx::Il2CppObject<()>) }.cast()
+            }
+
+            /// Get the object as a mutable instance of another class, see [`Il2CppObject::cast_mut`](#ctx::Il2CppObject::cast_mut).
+            pub fn cast_mut<C: #ctx::Il2CppClassData>(&mut self) -> #ctx::Il2CppResult<&mut C> {
+                unsafe { &mut *(self as *mut Self as *mut #ctx::Il2CppObject<()>) }.cast_mut()
+            }
+
+            /// Read a field by name, see [`Il2CppObject::get_field`](#ctx::Il2CppObject::get_field).
+            pub fn get_field<V: #ctx::FieldValue>(&self, name: impl AsRef<str>) -> #ctx::Il2CppResult<V> {
+                unsafe { &*(self as *const Self as *const #ctx::Il2CppObject<()>) }.get_field(name)
+            }
+
+            /// Write a field by name, see [`Il2CppObject::set_field`](#ctx::Il2CppObject::set_field).
+            pub fn set_field<V: #ctx::FieldValue>(&mut self, name: impl AsRef<str>, value: V) -> #ctx::Il2CppResult<()> {
+                unsafe { &mut *(self as *mut Self as *mut #ctx::Il2CppObject<()>) }.set_field(name, value)
+            }
+
+            /// Call the getter of a property by name, see [`Il2CppObject::get_property`](#ctx::Il2CppObject::get_property).
+            pub fn get_property<V: #ctx::FieldValue>(&self, name: impl AsRef<str>) -> #ctx::Il2CppResult<V> {
+                unsafe { &*(self as *const Self as *const #ctx::Il2CppObject<()>) }.get_property(name)
+            }
+
+            /// Call the setter of a property by name, see [`Il2CppObject::set_property`](#ctx::Il2CppObject::set_property).
+            pub fn set_property<V: #ctx::FieldValue>(&mut self, name: impl AsRef<str>, value: V) -> #ctx::Il2CppResult<()> {
+                unsafe { &mut *(self as *mut Self as *mut #ctx::Il2CppObject<()>) }.set_property(name, value)
+            }
         }
 
         // AsRef/AsMut to the Fields variant
@@ -122,7 +194,28 @@ pub fn class(attrs: TokenStream, item: TokenStream) -> TokenStream {
             }
 
             fn class_mut() -> &'static mut #ctx::Il2CppClass {
-                Self::class().clone()
+                Self::class().clone().unwrap_or_else(|err| panic!("Failed to copy class {}.{}: {}", #namespace, #class, err))
+            }
+
+            fn layout() -> Option<#ctx::ClassLayout> {
+                Some(#ctx::ClassLayout {
+                    size: std::mem::size_of::<Self>(),
+                    fields: vec![
+                        #(
+                            {
+                                let span = #ctx::span_of!(#fields_name #type_generics, #layout_idents);
+
+                                #ctx::FieldLayout {
+                                    name: #layout_names,
+                                    // Fields come right after the klass and monitor header
+                                    offset: 0x10 + span.start,
+                                    size: span.len(),
+                                    padding: #layout_padding,
+                                }
+                            }
+                        ),*
+                    ],
+                })
             }
         }
 
@@ -132,6 +225,34 @@ pub fn class(attrs: TokenStream, item: TokenStream) -> TokenStream {
     }.into()  
 }
 
+struct LayoutField {
+    ident: Ident,
+    name: String,
+    padding: bool,
+}
+
+// Collect the fields to check with `verify_layout`, removing the `#[field_name]` attributes as they are not real ones.
+fn field_layouts(fields: &mut syn::Fields) -> Result<Vec<LayoutField>> {
+    let mut layout = Vec::new();
+
+    for field in fields.iter_mut() {
+        let Some(ident) = field.ident.clone() else { continue };
+
+        let mut name = ident.unraw().to_string();
+
+        if let Some(index) = field.attrs.iter().position(|attr| attr.path().is_ident("field_name")) {
+            name = field.attrs.remove(index).parse_args::<LitStr>()?.value();
+        }
+
+        // Byte arrays stand for fields that are not mirrored.
+        let padding = matches!(&field.ty, Type::Array(array) if matches!(&*array.elem, Type::Path(path) if path.path.is_ident("u8")));
+
+        layout.push(LayoutField { ident, name, padding });
+    }
+
+    Ok(layout)
+}
+
 // Remove the attributes on Generics
 // TODO: Collect and return them
 fn strip_generics_attrs(mut generics: Generics) -> Generics {
@@ -12,7 +12,7 @@ fn inject_record_fields(fields: &mut FieldsNamed) {
         }
     };
 
-    new_fields.named.extend(std::mem::take(&mut fields.named).into_iter());
+    new_fields.named.extend(std::mem::take(&mut fields.named));
     fields.named = new_fields.named;
 }
 
@@ -38,6 +38,11 @@ pub fn hook(attr: TokenStream, item: TokenStream) -> TokenStream {
     scan::hook(attr, item)
 }
 
+#[proc_macro_attribute]
+pub fn vhook(attr: TokenStream, item: TokenStream) -> TokenStream {
+    scan::vhook(attr, item)
+}
+
 #[proc_macro_attribute]
 pub fn from_offset(attr: TokenStream, item: TokenStream) -> TokenStream {
     scan::from_offset(attr, item)
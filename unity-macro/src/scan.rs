@@ -1,44 +1,79 @@
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as Quote;
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::{parse_macro_input, punctuated::Punctuated, token::Comma, FnArg, ForeignItemFn, ItemFn};
 
 #[derive(deluxe::ParseMetaItem)]
-struct ScanInfo(String, String, String, #[deluxe(default)] usize);
+struct ScanInfo(String, String, String, #[deluxe(default)] Overload);
+
+/// How to pick the method among the ones sharing its name, as the optional last argument of the attribute.
+#[derive(Default)]
+enum Overload {
+    /// Count the arguments of the function.
+    #[default]
+    Inferred,
+    /// Take the method with this many arguments, such as `2`.
+    ArgCount(usize),
+    /// Take the method with these parameter types, such as `"(System.Int32,System.String)"`.
+    Signature(String),
+}
+
+impl deluxe::ParseMetaItem for Overload {
+    fn parse_meta_item(input: syn::parse::ParseStream, _mode: deluxe::ParseMode) -> deluxe::Result<Self> {
+        match input.parse::<syn::Lit>()? {
+            syn::Lit::Int(count) => Ok(Self::ArgCount(count.base10_parse()?)),
+            syn::Lit::Str(signature) => Ok(Self::Signature(signature.value())),
+            lit => Err(syn::Error::new(lit.span(), "expected an argument count or a signature such as \"(System.Int32,System.String)\"")),
+        }
+    }
+}
 
 impl ScanInfo {
     pub fn get_scan_fn(self, arg_count: usize) -> Quote {
-        let ScanInfo(namespace, class, method, forced_arg_count) = self;
-        
-        let arg_count = if forced_arg_count == usize::default() {
-            arg_count
-        } else {
-            forced_arg_count
+        let ScanInfo(namespace, class, method, overload) = self;
+
+        let arg_count = match overload {
+            Overload::ArgCount(forced_arg_count) if forced_arg_count != usize::default() => forced_arg_count,
+            _ => arg_count,
         };
 
         let ctx = super::utils::context();
 
+        let (lookup, description) = match overload {
+            Overload::Signature(signature) => (
+                quote!(.get_method_from_signature(METHOD_NAME, #signature)),
+                quote!(format!("with signature {}", #signature)),
+            ),
+            _ => (
+                quote!(.get_method_from_name(METHOD_NAME, ARG_COUNT)),
+                quote!(format!("arg count {}", ARG_COUNT)),
+            ),
+        };
+
         quote!(
             pub const NAMESPACE: &str = #namespace;
             pub const CLASS_NAME: &str = #class;
             pub const METHOD_NAME: &str = #method;
             pub const ARG_COUNT: usize = #arg_count;
 
-            static INFO: #ctx::LazyLock<&'static mut #ctx::MethodInfo> = #ctx::LazyLock::new(||
-                #ctx::Il2CppClass::from_name(NAMESPACE, CLASS_NAME)
-                    .expect(&format!("Failed to find class {}.{}", NAMESPACE, CLASS_NAME))
-                    .get_method_from_name(METHOD_NAME, ARG_COUNT)
-                    .expect(&format!("Failed to find method {}.{}({}) arg count {}", NAMESPACE, CLASS_NAME, METHOD_NAME, ARG_COUNT))
-            );
+            static INFO: #ctx::LazyLock<&'static #ctx::MethodInfo> = #ctx::LazyLock::new(|| {
+                find_method().unwrap_or_else(|err| panic!("Failed to find method {}.{}({}) {}: {}", NAMESPACE, CLASS_NAME, METHOD_NAME, #description, err))
+            });
+
+            /// Look the method up, returning an error where [`get_ref`] panics.
+            pub fn find_method() -> #ctx::Il2CppResult<&'static #ctx::MethodInfo> {
+                #ctx::Il2CppClass::from_name(NAMESPACE, CLASS_NAME)? #lookup .map(|method| &*method)
+            }
 
             pub fn as_base() -> #ctx::MethodInfo {
-                #ctx::MethodInfo::new_from(INFO.clone())
+                #ctx::MethodInfo::new_from(**INFO)
             }
 
             pub fn get_ref<'a>() -> &'a #ctx::MethodInfo {
                 &INFO
             }
 
+            #[cfg(target_os = "horizon")]
             pub fn get_offset() -> usize {
                 static OFFSETS: #ctx::LazyLock<usize> = #ctx::LazyLock::new(|| {
                     let method = &INFO;
@@ -67,6 +102,45 @@ fn get_fn_arg_count(inputs: &Punctuated<FnArg, Comma>) -> usize {
     count
 }
 
+fn is_method_info(pat_type: &syn::PatType) -> bool {
+    matches!(&*pat_type.pat, syn::Pat::Ident(pat_ident) if pat_ident.ident == "method_info")
+        && matches!(&*pat_type.ty, syn::Type::Path(path) if path.path.segments.last().is_some_and(|segment| segment.ident == "OptionalMethod"))
+}
+
+/// Outside of the Switch, call the method through the pointer of its MethodInfo instead, as provided by the current Il2Cpp runtime.
+/// The MethodInfo is passed along when the caller provides `None`, as methods running in Rust (such as mocked ones) may need it.
+fn get_host_fn(function: &ForeignItemFn) -> Quote {
+    let ForeignItemFn { attrs, vis, sig, .. } = function;
+    let name = &sig.ident;
+    let generics = &sig.generics;
+    let where_clause = &generics.where_clause;
+    let output = &sig.output;
+
+    let typed = sig.inputs.iter().filter_map(|input| match input {
+        FnArg::Typed(pat_type) => Some(pat_type),
+        FnArg::Receiver(_) => None,
+    });
+
+    let args = typed.clone().enumerate().map(|(index, _)| format_ident!("__arg{}", index)).collect::<Vec<_>>();
+    let types = typed.clone().map(|pat_type| &pat_type.ty).collect::<Vec<_>>();
+
+    let values = typed.zip(&args).map(|(pat_type, arg)| {
+        if is_method_info(pat_type) {
+            quote!(#arg.or(Some(#name::get_ref())))
+        } else {
+            quote!(#arg)
+        }
+    });
+
+    quote!(
+        #(#attrs)*
+        #vis unsafe fn #name #generics (#(#args: #types),*) #output #where_clause {
+            let function = std::mem::transmute::<*mut u8, extern "C" fn(#(#types),*) #output>(#name::get_ref().method_ptr);
+            function(#(#values),*)
+        }
+    )
+}
+
 pub fn hook(attr: TokenStream, item: TokenStream) -> TokenStream {
     // parse
     let hook_function = parse_macro_input!(item as ItemFn);
@@ -80,7 +154,7 @@ pub fn hook(attr: TokenStream, item: TokenStream) -> TokenStream {
     let scan_fn_token = scan_info.get_scan_fn(get_fn_arg_count(&hook_function.sig.inputs));
 
     quote!(
-        #[skyline::hook(offset = #scan_fn_name::get_offset())]
+        #[cfg_attr(target_os = "horizon", skyline::hook(offset = #scan_fn_name::get_offset()))]
         #hook_function
         pub mod #scan_fn_name {
             #scan_fn_token
@@ -89,6 +163,90 @@ pub fn hook(attr: TokenStream, item: TokenStream) -> TokenStream {
     .into()
 }
 
+/// Hook a virtual method by replacing its entry in the vtable of the class, rather than the code of the method.
+///
+/// The hook is installed by calling `install()` in the module of the same name, and the original is reached with `call_original!`.
+/// The hook is made `extern "C"` unless it is declared with an ABI, such as `extern "C-unwind"` for hooks throwing managed exceptions.
+pub fn vhook(attr: TokenStream, item: TokenStream) -> TokenStream {
+    // parse
+    let mut hook_function = parse_macro_input!(item as ItemFn);
+    let scan_info = match deluxe::parse::<ScanInfo>(attr) {
+        Ok(info) => info,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    // prepare tokens
+    let ctx = super::utils::context();
+    let name = hook_function.sig.ident.clone();
+    let scan_module = scan_info.get_scan_fn(get_fn_arg_count(&hook_function.sig.inputs));
+
+    let types = hook_function.sig.inputs.iter().filter_map(|input| match input {
+        FnArg::Typed(pat_type) => Some(&pat_type.ty),
+        FnArg::Receiver(_) => None,
+    });
+    let output = &hook_function.sig.output;
+
+    // The vtable calls the hook like it would call the method, an ABI given to the hook is kept so it can be `C-unwind`
+    let abi = hook_function.sig.abi.clone().unwrap_or_else(|| syn::parse_quote!(extern "C"));
+
+    let call_original: syn::Stmt = syn::parse_quote!(
+        #[allow(unused_macros)]
+        macro_rules! call_original {
+            ($($arg:expr),* $(,)?) => {{
+                let original = #name::original();
+                assert!(!original.is_null(), "call_original! was used in {} before the hook was installed", stringify!(#name));
+                unsafe { std::mem::transmute::<*mut u8, #abi fn(#(#types),*) #output>(original)($($arg),*) }
+            }};
+        }
+    );
+
+    hook_function.sig.abi = Some(abi);
+    hook_function.block.stmts.insert(0, call_original);
+
+    quote!(
+        #hook_function
+        pub mod #name {
+            #scan_module
+
+            static ORIGINAL: #ctx::AtomicPtr<u8> = #ctx::AtomicPtr::new(std::ptr::null_mut());
+
+            /// Replace the method in the vtable of the class with the hook.
+            ///
+            /// Installing it again does nothing once it succeeded. Until then, every call tries again, such as once the class is loaded.
+            pub fn install() -> #ctx::Il2CppResult<()> {
+                static INSTALLED: #ctx::Mutex<bool> = #ctx::Mutex::new(false);
+
+                // Held while replacing the entry, as replacing it twice would make the hook its own original
+                let mut installed = INSTALLED.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+                if !*installed {
+                    replace_entry()?;
+                    *installed = true;
+                }
+
+                Ok(())
+            }
+
+            fn replace_entry() -> #ctx::Il2CppResult<()> {
+                let method = find_method()?;
+                let entry = #ctx::Il2CppClass::from_name(NAMESPACE, CLASS_NAME)?.get_vtable_entry_mut(method)?;
+
+                // The original has to be known before the hook can be called
+                ORIGINAL.store(entry.method_ptr, #ctx::Ordering::Release);
+                entry.method_ptr = super::#name as *mut u8;
+
+                Ok(())
+            }
+
+            /// The implementation the hook replaced, or null if it is not installed.
+            pub fn original() -> *mut u8 {
+                ORIGINAL.load(#ctx::Ordering::Acquire)
+            }
+        }
+    )
+    .into()
+}
+
 pub fn from_offset(attr: TokenStream, item: TokenStream) -> TokenStream {
     // parse
     let function = parse_macro_input!(item as ForeignItemFn);
@@ -101,9 +259,14 @@ pub fn from_offset(attr: TokenStream, item: TokenStream) -> TokenStream {
     let scan_fn_name = &function.sig.ident;
     let scan_module = scan_info.get_scan_fn(get_fn_arg_count(&function.sig.inputs));
 
+    let host_function = get_host_fn(&function);
+
     quote!(
+        #[cfg(target_os = "horizon")]
         #[skyline::from_offset(#scan_fn_name::get_offset())]
         #function
+        #[cfg(not(target_os = "horizon"))]
+        #host_function
         #[doc(hidden)]
         pub mod #scan_fn_name {
             #scan_module